@@ -0,0 +1,158 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use std::io;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use graphviz_dot_builder::colors::GraphvizColor;
+use graphviz_dot_builder::edge::edge::GraphVizEdge;
+use graphviz_dot_builder::edge::style::{GraphvizEdgeStyleItem, GvArrowHeadFill, GvArrowHeadSide, GvArrowHeadStyle};
+use graphviz_dot_builder::graph::graph::GraphVizDiGraph;
+use graphviz_dot_builder::graph::style::{GraphvizGraphStyleItem, GvGraphRankDir};
+use graphviz_dot_builder::item::cluster::GraphVizCluster;
+use graphviz_dot_builder::item::item::GraphVizGraphItem;
+use graphviz_dot_builder::item::node::node::GraphVizNode;
+use graphviz_dot_builder::item::node::style::{GraphvizNodeStyleItem, GvNodeShape, GvNodeStyleKind};
+use graphviz_dot_builder::traits::{DotBuildable, DotTranslatable};
+
+
+/// a graph with "clusters" clusters of "nodes" styled nodes each, chained by styled edges
+fn sample_graph(clusters : usize, nodes : usize) -> GraphVizDiGraph {
+    let mut graph = GraphVizDiGraph::new(vec![GraphvizGraphStyleItem::Rankdir(GvGraphRankDir::LR)]);
+    for c in 0..clusters {
+        let mut cluster = GraphVizCluster::new(format!("c{}",c),
+                                               vec![GraphvizNodeStyleItem::Label(format!("cluster {}",c)),
+                                                    GraphvizNodeStyleItem::FillColor(GraphvizColor::lightgrey)],
+                                               vec![],
+                                               vec![]);
+        for n in 0..nodes {
+            cluster.add_node(GraphVizNode::new(format!("n{}_{}",c,n),
+                                               vec![GraphvizNodeStyleItem::Style(vec![GvNodeStyleKind::Rounded,GvNodeStyleKind::Filled]),
+                                                    GraphvizNodeStyleItem::Shape(GvNodeShape::Rectangle),
+                                                    GraphvizNodeStyleItem::Label(format!("node {} of cluster {}",n,c)),
+                                                    GraphvizNodeStyleItem::FillColor(GraphvizColor::lightskyblue)]));
+            if n > 0 {
+                cluster.add_edge(GraphVizEdge::new(format!("n{}_{}",c,n-1),None,format!("n{}_{}",c,n),None,
+                                                   vec![GraphvizEdgeStyleItem::Color(GraphvizColor::darkslategray)]));
+            }
+        }
+        graph.add_cluster(cluster);
+        if c > 0 {
+            graph.add_edge(GraphVizEdge::new(format!("n{}_0",c-1),Some(format!("c{}",c-1)),format!("n{}_0",c),Some(format!("c{}",c)),
+                                             vec![GraphvizEdgeStyleItem::Head(GvArrowHeadStyle::Diamond(GvArrowHeadFill::Open,GvArrowHeadSide::Both)),
+                                                  GraphvizEdgeStyleItem::Label(format!("from {} to {}",c-1,c))]));
+        }
+    }
+    graph
+}
+
+/* the former translation, which built one intermediate string per element and attribute */
+
+fn legacy_node(node : &GraphVizNode) -> String {
+    let style : Vec<String> = node.style.iter().map(|item| item.to_dot_string()).collect();
+    if style.is_empty() {
+        format!("{};", node.id)
+    } else {
+        format!("{} [{}];", node.id, style.join(","))
+    }
+}
+
+fn legacy_edge(edge : &GraphVizEdge) -> String {
+    let mut style : Vec<String> = edge.style.iter().map(|item| item.to_dot_string()).collect();
+    if let Some(cluster_id) = &edge.origin_cluster {
+        style.push(format!("ltail=cluster_{}",cluster_id));
+    }
+    if let Some(cluster_id) = &edge.target_cluster {
+        style.push(format!("lhead=cluster_{}",cluster_id));
+    }
+    if style.is_empty() {
+        format!("{}->{};", edge.origin_node_id, edge.target_node_id)
+    } else {
+        format!("{}->{} [{}];", edge.origin_node_id, edge.target_node_id, style.join(","))
+    }
+}
+
+fn legacy_item(item : &GraphVizGraphItem) -> String {
+    match item {
+        GraphVizGraphItem::Node(node) => {
+            legacy_node(node)
+        },
+        GraphVizGraphItem::Cluster(cluster) => {
+            let mut res = String::new();
+            res.push_str(&format!("subgraph cluster_{:} {{\n",cluster.id));
+            for item in &cluster.style {
+                res.push_str(&format!("{};\n",item.to_dot_string()) );
+            }
+            for item in &cluster.items {
+                res.push('\t');
+                res.push_str(&legacy_item(item));
+                res.push('\n');
+            }
+            for edge in &cluster.edges {
+                res.push('\t');
+                res.push_str(&legacy_edge(edge));
+                res.push('\n');
+            }
+            res.push('}');
+            res
+        }
+    }
+}
+
+fn legacy_to_dot_string(graph : &GraphVizDiGraph) -> String {
+    let mut res = String::new();
+    res.push_str("digraph G {");
+    res.push_str("\ncompound=true;" );
+    for item in &graph.style {
+        res.push_str(&format!("\n{};",item.to_dot_string()) );
+    }
+    for item in &graph.items {
+        res.push_str("\n\t");
+        res.push_str(&legacy_item(item));
+    }
+    for edge in &graph.edges {
+        res.push_str("\n\t");
+        res.push_str(&legacy_edge(edge));
+    }
+    res.push_str("\n}");
+    res
+}
+
+fn dot_output(c : &mut Criterion) {
+    let graph = sample_graph(50,100);
+    assert_eq!(legacy_to_dot_string(&graph),graph.to_dot_string());
+    // ***
+    let mut group = c.benchmark_group("dot_output");
+    group.bench_function("legacy_to_dot_string", |b| {
+        b.iter(|| legacy_to_dot_string(black_box(&graph)))
+    });
+    group.bench_function("to_dot_string", |b| {
+        b.iter(|| black_box(&graph).to_dot_string())
+    });
+    group.bench_function("legacy_to_dot_string_then_write", |b| {
+        b.iter(|| io::Write::write_all(&mut io::sink(), legacy_to_dot_string(black_box(&graph)).as_bytes()))
+    });
+    group.bench_function("write_dot", |b| {
+        b.iter(|| black_box(&graph).write_dot(&mut io::sink()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, dot_output);
+criterion_main!(benches);
@@ -0,0 +1,262 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr, Type};
+use syn::meta::ParseNestedMeta;
+
+
+/**
+ Derive macro for the "DotNode" trait of the "graphviz_dot_builder" crate (with its "derive" feature).
+
+ #[derive(DotNode)]
+ #[dot(style(shape = Rectangle, fillcolor = lightblue))]
+ struct Task {
+     #[dot(id)]
+     name : String,
+     #[dot(label)]
+     title : String,
+     #[dot(edge_to(style = Dashed))]
+     depends_on : Vec<String>
+ }
+
+ - "#[dot(id)]" : the field (implementing Display) which is the id of the node (mandatory)
+ - "#[dot(label)]" : the field (implementing Display) which is the label of the node
+ - "#[dot(style(...))]" or "#[dot(style = "...")]" on the type : the DOT attributes of the node,
+   with the syntax of the "digraph!" macro
+ - "#[dot(edge_to)]" : a field holding the id of other nodes (directly or in an Option or in a collection),
+   towards which an edge is drawn, with optional DOT attributes "#[dot(edge_to(...))]" or "#[dot(edge_to = "...")]"
+ **/
+#[proc_macro_derive(DotNode, attributes(dot))]
+pub fn derive_dot_node(input : TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into()
+    }
+}
+
+/// DOT attributes given either in parenthesis or as a string
+fn dot_attributes(meta : &ParseNestedMeta) -> syn::Result<TokenStream2> {
+    if meta.input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in meta.input);
+        content.parse()
+    } else {
+        let literal : LitStr = meta.value()?.parse()?;
+        literal.parse()
+    }
+}
+
+/// how to get the ids of the targets of edges from a field, depending on its type
+fn edge_targets(field : &TokenStream2, ty : &Type) -> TokenStream2 {
+    let wrapper = match ty {
+        Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()),
+        Type::Reference(reference) => return edge_targets(field, &reference.elem),
+        Type::Slice(_) | Type::Array(_) => Some("Vec".to_string()),
+        _ => None
+    };
+    match wrapper.as_deref() {
+        Some("Option") => quote! {
+            #field.iter().map(|target| ::std::string::ToString::to_string(target)).collect::<Vec<String>>()
+        },
+        Some("Vec") | Some("VecDeque") | Some("HashSet") | Some("BTreeSet") | Some("LinkedList") => quote! {
+            #field.iter().map(|target| ::std::string::ToString::to_string(target)).collect::<Vec<String>>()
+        },
+        _ => quote! {
+            vec![::std::string::ToString::to_string(&#field)]
+        }
+    }
+}
+
+fn expand(input : &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut style = TokenStream2::new();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("dot")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("style") {
+                style = dot_attributes(&meta)?;
+                Ok(())
+            } else {
+                Err(meta.error("expected #[dot(style(...))]"))
+            }
+        })?;
+    }
+    // ***
+    let fields = match &input.data {
+        Data::Struct(data) => {
+            match &data.fields {
+                Fields::Named(fields) => &fields.named,
+                _ => return Err(syn::Error::new_spanned(&input.ident, "DotNode can only be derived for structs with named fields"))
+            }
+        },
+        _ => return Err(syn::Error::new_spanned(&input.ident, "DotNode can only be derived for structs with named fields"))
+    };
+    let mut id = None;
+    let mut label = None;
+    let mut edges = vec![];
+    for field in fields {
+        let name = field.ident.as_ref().unwrap();
+        let access = quote! { self.#name };
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("dot")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") {
+                    id = Some(access.clone());
+                    Ok(())
+                } else if meta.path.is_ident("label") {
+                    label = Some(access.clone());
+                    Ok(())
+                } else if meta.path.is_ident("edge_to") {
+                    let attributes = if meta.input.is_empty() || meta.input.peek(syn::Token![,]) {
+                        TokenStream2::new()
+                    } else {
+                        dot_attributes(&meta)?
+                    };
+                    edges.push((edge_targets(&access, &field.ty),attributes));
+                    Ok(())
+                } else {
+                    Err(meta.error("expected #[dot(id)], #[dot(label)] or #[dot(edge_to)]"))
+                }
+            })?;
+        }
+    }
+    let id = match id {
+        Some(id) => id,
+        None => return Err(syn::Error::new_spanned(&input.ident, "DotNode requires a field marked with #[dot(id)]"))
+    };
+    // ***
+    let label = label.map(|label| quote! {
+        .label(::std::string::ToString::to_string(&#label))
+    });
+    let edges = edges.iter().map(|(targets,attributes)| quote! {
+        for target in #targets {
+            edges.push(::graphviz_dot_builder::__dot_attributes!(edge
+                ::graphviz_dot_builder::builder::edge::GvEdgeBuilder::new(self.dot_node_id(),target) ; #attributes).build());
+        }
+    });
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::graphviz_dot_builder::traits::DotNode for #ident #ty_generics #where_clause {
+            fn dot_node_id(&self) -> String {
+                ::std::string::ToString::to_string(&#id)
+            }
+            fn to_dot_node(&self) -> ::graphviz_dot_builder::item::node::node::GraphVizNode {
+                ::graphviz_dot_builder::__dot_attributes!(node
+                    ::graphviz_dot_builder::builder::node::GvNodeBuilder::new(self.dot_node_id()) ; #style)
+                    #label
+                    .build()
+            }
+            #[allow(unused_mut)]
+            fn to_dot_edges(&self) -> Vec<::graphviz_dot_builder::edge::edge::GraphVizEdge> {
+                let mut edges = vec![];
+                #(#edges)*
+                edges
+            }
+        }
+    })
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn expand_str(input : &str) -> Result<String,String> {
+        let input : DeriveInput = syn::parse_str(input).unwrap();
+        expand(&input).map(|tokens| tokens.to_string()).map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn expansion() {
+        let expanded = expand_str(r#"
+            #[dot(style(shape = Rectangle))]
+            struct Task {
+                #[dot(id)]
+                name : String,
+                #[dot(label)]
+                title : String,
+                #[dot(edge_to(style = Dashed))]
+                depends_on : Vec<String>,
+                #[dot(edge_to)]
+                parent : Option<String>
+            }"#).unwrap();
+        let expected = quote! {
+            impl ::graphviz_dot_builder::traits::DotNode for Task {
+                fn dot_node_id(&self) -> String {
+                    ::std::string::ToString::to_string(&self.name)
+                }
+                fn to_dot_node(&self) -> ::graphviz_dot_builder::item::node::node::GraphVizNode {
+                    ::graphviz_dot_builder::__dot_attributes!(node
+                        ::graphviz_dot_builder::builder::node::GvNodeBuilder::new(self.dot_node_id()) ; shape = Rectangle)
+                        .label(::std::string::ToString::to_string(&self.title))
+                        .build()
+                }
+                #[allow(unused_mut)]
+                fn to_dot_edges(&self) -> Vec<::graphviz_dot_builder::edge::edge::GraphVizEdge> {
+                    let mut edges = vec![];
+                    for target in self.depends_on.iter().map(|target| ::std::string::ToString::to_string(target)).collect::<Vec<String>>() {
+                        edges.push(::graphviz_dot_builder::__dot_attributes!(edge
+                            ::graphviz_dot_builder::builder::edge::GvEdgeBuilder::new(self.dot_node_id(),target) ; style = Dashed).build());
+                    }
+                    for target in self.parent.iter().map(|target| ::std::string::ToString::to_string(target)).collect::<Vec<String>>() {
+                        edges.push(::graphviz_dot_builder::__dot_attributes!(edge
+                            ::graphviz_dot_builder::builder::edge::GvEdgeBuilder::new(self.dot_node_id(),target) ; ).build());
+                    }
+                    edges
+                }
+            }
+        };
+        assert_eq!(expanded,expected.to_string());
+    }
+
+    #[test]
+    fn string_attributes_and_single_targets() {
+        let expanded = expand_str(r#"
+            #[dot(style = "fillcolor = red")]
+            struct Step {
+                #[dot(id)]
+                id : u32,
+                #[dot(edge_to = "color = blue")]
+                next : u32
+            }"#).unwrap();
+        assert!(expanded.contains(&quote!(GvNodeBuilder::new(self.dot_node_id()) ; fillcolor = red).to_string()));
+        assert!(expanded.contains(&quote!(for target in vec![::std::string::ToString::to_string(&self.next)]).to_string()));
+        assert!(expanded.contains(&quote!(GvEdgeBuilder::new(self.dot_node_id(),target) ; color = blue).to_string()));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(expand_str("struct A { name : String }"),
+                   Err("DotNode requires a field marked with #[dot(id)]".to_string()));
+        assert_eq!(expand_str("struct A(String);"),
+                   Err("DotNode can only be derived for structs with named fields".to_string()));
+        assert_eq!(expand_str("enum A { B }"),
+                   Err("DotNode can only be derived for structs with named fields".to_string()));
+        assert_eq!(expand_str("struct A { #[dot(key)] name : String }"),
+                   Err("expected #[dot(id)], #[dot(label)] or #[dot(edge_to)]".to_string()));
+        assert_eq!(expand_str("#[dot(shape)] struct A { #[dot(id)] name : String }"),
+                   Err("expected #[dot(style(...))]".to_string()));
+    }
+
+}
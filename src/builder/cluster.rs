@@ -0,0 +1,186 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use crate::builder::node::set_node_style_item;
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::{GraphvizNodeStyleItem, GvNodeStyleKind};
+
+
+/**
+ Builder for a cluster, which can contain nodes, edges and other clusters :
+ GvClusterBuilder::new("c1").label("C").node(GvNodeBuilder::new("a")).cluster("c2", |c| { c.node(GvNodeBuilder::new("b")) })
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvClusterBuilder {
+    cluster : GraphVizCluster
+}
+
+impl GvClusterBuilder {
+
+    pub fn new(id : impl Into<String>) -> GvClusterBuilder {
+        GvClusterBuilder{cluster:GraphVizCluster::new(id.into(),vec![],vec![],vec![])}
+    }
+
+    /// sets a style item, replacing any item of the same kind
+    pub fn item(mut self, item : GraphvizNodeStyleItem) -> Self {
+        set_node_style_item(&mut self.cluster.style,item);
+        self
+    }
+
+    pub fn label(self, label : impl Into<String>) -> Self {
+        self.item(GraphvizNodeStyleItem::Label(label.into()))
+    }
+
+    /// adds a kind of style to the style of the cluster (e.g. dashed, rounded, ...)
+    pub fn style(self, kind : GvNodeStyleKind) -> Self {
+        self.item(GraphvizNodeStyleItem::Style(vec![kind]))
+    }
+
+    pub fn color(self, color : GraphvizColor) -> Self {
+        self.item(GraphvizNodeStyleItem::Color(color))
+    }
+
+    pub fn fill(self, color : GraphvizColor) -> Self {
+        self.item(GraphvizNodeStyleItem::FillColor(color))
+    }
+
+    pub fn font_color(self, color : GraphvizColor) -> Self {
+        self.item(GraphvizNodeStyleItem::FontColor(color))
+    }
+
+    pub fn font_size(self, size : u32) -> Self {
+        self.item(GraphvizNodeStyleItem::FontSize(size))
+    }
+
+    pub fn font_name(self, name : impl Into<String>) -> Self {
+        self.item(GraphvizNodeStyleItem::FontName(name.into()))
+    }
+
+    pub fn pen_width(self, width : u32) -> Self {
+        self.item(GraphvizNodeStyleItem::PenWidth(width))
+    }
+
+    pub fn node(mut self, node : impl Into<GraphVizNode>) -> Self {
+        self.cluster.items.push(Box::new(GraphVizGraphItem::Node(node.into())));
+        self
+    }
+
+    pub fn edge(mut self, edge : impl Into<GraphVizEdge>) -> Self {
+        self.cluster.edges.push(edge.into());
+        self
+    }
+
+    /// adds a nested cluster, built by the given function
+    pub fn cluster<F>(mut self, id : impl Into<String>, build : F) -> Self
+        where F : FnOnce(GvClusterBuilder) -> GvClusterBuilder {
+        let nested = build(GvClusterBuilder::new(id)).build();
+        self.cluster.items.push(Box::new(GraphVizGraphItem::Cluster(nested)));
+        self
+    }
+
+    pub fn build(self) -> GraphVizCluster {
+        self.cluster
+    }
+
+}
+
+impl From<GvClusterBuilder> for GraphVizCluster {
+    fn from(builder : GvClusterBuilder) -> GraphVizCluster {
+        builder.build()
+    }
+}
+
+impl From<GvClusterBuilder> for GraphVizGraphItem {
+    fn from(builder : GvClusterBuilder) -> GraphVizGraphItem {
+        GraphVizGraphItem::Cluster(builder.build())
+    }
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::builder::edge::GvEdgeBuilder;
+    use crate::builder::node::GvNodeBuilder;
+    use crate::traits::DotTranslatable;
+
+    #[test]
+    fn nested_clusters() {
+        let cluster = GvClusterBuilder::new("c1")
+            .label("C")
+            .style(GvNodeStyleKind::Rounded)
+            .fill(GraphvizColor::yellow)
+            .color(GraphvizColor::black)
+            .font_color(GraphvizColor::red)
+            .font_size(8)
+            .font_name("Arial")
+            .pen_width(2)
+            .node(GvNodeBuilder::new("a"))
+            .cluster("c2", |c2| {
+                c2.node(GvNodeBuilder::new("b")).edge(GvEdgeBuilder::new("b","b"))
+            })
+            .edge(GvEdgeBuilder::new("a","b").lhead("c2"))
+            .build();
+        let nested = GraphVizCluster::new("c2".to_string(),
+                                          vec![],
+                                          vec![Box::new(GraphVizGraphItem::Node(GraphVizNode::new("b".to_string(),vec![])))],
+                                          vec![GraphVizEdge::new("b".to_string(),None,"b".to_string(),None,vec![])]);
+        let expected = GraphVizCluster::new("c1".to_string(),
+                                            vec![GraphvizNodeStyleItem::Label("C".to_string()),
+                                                 GraphvizNodeStyleItem::Style(vec![GvNodeStyleKind::Rounded,GvNodeStyleKind::Filled]),
+                                                 GraphvizNodeStyleItem::FillColor(GraphvizColor::yellow),
+                                                 GraphvizNodeStyleItem::Color(GraphvizColor::black),
+                                                 GraphvizNodeStyleItem::FontColor(GraphvizColor::red),
+                                                 GraphvizNodeStyleItem::FontSize(8),
+                                                 GraphvizNodeStyleItem::FontName("Arial".to_string()),
+                                                 GraphvizNodeStyleItem::PenWidth(2)],
+                                            vec![Box::new(GraphVizGraphItem::Node(GraphVizNode::new("a".to_string(),vec![]))),
+                                                 Box::new(GraphVizGraphItem::Cluster(nested))],
+                                            vec![GraphVizEdge::new("a".to_string(),None,"b".to_string(),Some("c2".to_string()),vec![])]);
+        assert!(cluster == expected);
+        let dot = [
+            "subgraph cluster_c1 {",
+            "label=\"C\";",
+            "style=\"rounded,filled\";",
+            "style=filled;fillcolor=yellow;",
+            "color=black;",
+            "fontcolor=red;",
+            "fontsize=8;",
+            "fontname=\"Arial\";",
+            "penwidth=2;",
+            "\ta;",
+            "\tsubgraph cluster_c2 {",
+            "\tb;",
+            "\tb->b;",
+            "}",
+            "\ta->b [lhead=cluster_c2];",
+            "}"
+        ];
+        assert_eq!(cluster.to_dot_string(),dot.join("\n"));
+    }
+
+}
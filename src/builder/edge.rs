@@ -0,0 +1,177 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use crate::builder::set_style_item;
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::{GraphvizEdgeStyle, GraphvizEdgeStyleItem, GvArrowHeadStyle, GvEdgeLineStyle};
+
+
+/**
+ Builder for an edge :
+ GvEdgeBuilder::new("a","b").label("x").lhead("c1")
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvEdgeBuilder {
+    origin_node_id : String,
+    origin_cluster : Option<String>,
+    target_node_id : String,
+    target_cluster : Option<String>,
+    style : GraphvizEdgeStyle
+}
+
+impl GvEdgeBuilder {
+
+    pub fn new(origin_node_id : impl Into<String>, target_node_id : impl Into<String>) -> GvEdgeBuilder {
+        GvEdgeBuilder{origin_node_id:origin_node_id.into(),
+            origin_cluster:None,
+            target_node_id:target_node_id.into(),
+            target_cluster:None,
+            style:vec![]}
+    }
+
+    /// sets a style item, replacing any item of the same kind
+    pub fn item(mut self, item : GraphvizEdgeStyleItem) -> Self {
+        set_style_item(&mut self.style,item);
+        self
+    }
+
+    /// the edge starts at the border of the given cluster
+    pub fn ltail(mut self, cluster_id : impl Into<String>) -> Self {
+        self.origin_cluster = Some(cluster_id.into());
+        self
+    }
+
+    /// the edge ends at the border of the given cluster
+    pub fn lhead(mut self, cluster_id : impl Into<String>) -> Self {
+        self.target_cluster = Some(cluster_id.into());
+        self
+    }
+
+    pub fn label(self, label : impl Into<String>) -> Self {
+        self.item(GraphvizEdgeStyleItem::Label(label.into()))
+    }
+
+    pub fn line_style(self, line_style : GvEdgeLineStyle) -> Self {
+        self.item(GraphvizEdgeStyleItem::LineStyle(line_style))
+    }
+
+    pub fn head(self, arrow : GvArrowHeadStyle) -> Self {
+        self.item(GraphvizEdgeStyleItem::Head(arrow))
+    }
+
+    pub fn tail(self, arrow : GvArrowHeadStyle) -> Self {
+        self.item(GraphvizEdgeStyleItem::Tail(arrow))
+    }
+
+    pub fn color(self, color : GraphvizColor) -> Self {
+        self.item(GraphvizEdgeStyleItem::Color(color))
+    }
+
+    pub fn font_color(self, color : GraphvizColor) -> Self {
+        self.item(GraphvizEdgeStyleItem::FontColor(color))
+    }
+
+    pub fn font_size(self, size : u32) -> Self {
+        self.item(GraphvizEdgeStyleItem::FontSize(size))
+    }
+
+    pub fn arrow_size(self, size : u32) -> Self {
+        self.item(GraphvizEdgeStyleItem::ArrowSize(size))
+    }
+
+    pub fn pen_width(self, width : u32) -> Self {
+        self.item(GraphvizEdgeStyleItem::PenWidth(width))
+    }
+
+    pub fn constraint(self, constraint : bool) -> Self {
+        self.item(GraphvizEdgeStyleItem::Constraint(constraint))
+    }
+
+    pub fn build(self) -> GraphVizEdge {
+        GraphVizEdge::new(self.origin_node_id,
+                          self.origin_cluster,
+                          self.target_node_id,
+                          self.target_cluster,
+                          self.style)
+    }
+
+}
+
+impl From<GvEdgeBuilder> for GraphVizEdge {
+    fn from(builder : GvEdgeBuilder) -> GraphVizEdge {
+        builder.build()
+    }
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::edge::style::GvArrowHeadSide;
+    use crate::traits::DotTranslatable;
+
+    #[test]
+    fn every_attribute() {
+        let edge = GvEdgeBuilder::new("a","b")
+            .label("x")
+            .line_style(GvEdgeLineStyle::Dashed)
+            .head(GvArrowHeadStyle::NoArrow)
+            .tail(GvArrowHeadStyle::Vee(GvArrowHeadSide::Both))
+            .color(GraphvizColor::red)
+            .font_color(GraphvizColor::blue)
+            .font_size(9)
+            .arrow_size(2)
+            .pen_width(3)
+            .constraint(false)
+            .ltail("c1")
+            .lhead("c2")
+            .color(GraphvizColor::green)
+            .build();
+        let expected = GraphVizEdge::new("a".to_string(),
+                                         Some("c1".to_string()),
+                                         "b".to_string(),
+                                         Some("c2".to_string()),
+                                         vec![GraphvizEdgeStyleItem::Label("x".to_string()),
+                                              GraphvizEdgeStyleItem::LineStyle(GvEdgeLineStyle::Dashed),
+                                              GraphvizEdgeStyleItem::Head(GvArrowHeadStyle::NoArrow),
+                                              GraphvizEdgeStyleItem::Tail(GvArrowHeadStyle::Vee(GvArrowHeadSide::Both)),
+                                              GraphvizEdgeStyleItem::Color(GraphvizColor::green),
+                                              GraphvizEdgeStyleItem::FontColor(GraphvizColor::blue),
+                                              GraphvizEdgeStyleItem::FontSize(9),
+                                              GraphvizEdgeStyleItem::ArrowSize(2),
+                                              GraphvizEdgeStyleItem::PenWidth(3),
+                                              GraphvizEdgeStyleItem::Constraint(false)]);
+        assert!(edge == expected);
+        assert_eq!(edge.to_dot_string(),
+                   "a->b [label=\"x\",style=dashed,arrowhead=none,arrowtail=vee,color=green,fontcolor=blue,fontsize=9,arrowsize=2,penwidth=3,constraint=false,ltail=cluster_c1,lhead=cluster_c2];");
+    }
+
+    #[test]
+    fn plain_edge() {
+        let edge : GraphVizEdge = GvEdgeBuilder::new("a","b").into();
+        assert!(edge == GraphVizEdge::new("a".to_string(),None,"b".to_string(),None,vec![]));
+        assert_eq!(edge.to_dot_string(),"a->b;");
+    }
+
+}
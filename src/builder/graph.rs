@@ -0,0 +1,157 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use crate::builder::cluster::GvClusterBuilder;
+use crate::builder::set_style_item;
+use crate::edge::edge::GraphVizEdge;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::style::{GraphvizGraphStyleItem, GraphvizSplines, GvGraphRankDir};
+use crate::item::node::node::GraphVizNode;
+use crate::traits::DotBuildable;
+
+
+/**
+ Builder for a whole graph :
+ GvGraphBuilder::new().rankdir(GvGraphRankDir::LR).node(GvNodeBuilder::new("a")).cluster("c1", |c| { c.node(GvNodeBuilder::new("b")) }).edge(GvEdgeBuilder::new("a","b"))
+ **/
+pub struct GvGraphBuilder {
+    graph : GraphVizDiGraph
+}
+
+impl GvGraphBuilder {
+
+    pub fn new() -> GvGraphBuilder {
+        GvGraphBuilder{graph:GraphVizDiGraph::new(vec![])}
+    }
+
+    /// sets a style item, replacing any item of the same kind
+    pub fn item(mut self, item : GraphvizGraphStyleItem) -> Self {
+        set_style_item(&mut self.graph.style,item);
+        self
+    }
+
+    pub fn rankdir(self, rankdir : GvGraphRankDir) -> Self {
+        self.item(GraphvizGraphStyleItem::Rankdir(rankdir))
+    }
+
+    /// separation between nodes, as "unit.decimal" inches
+    pub fn node_sep(self, unit : u32, decimal : u32) -> Self {
+        self.item(GraphvizGraphStyleItem::NodeSep(unit,decimal))
+    }
+
+    pub fn concentrate(self, concentrate : bool) -> Self {
+        self.item(GraphvizGraphStyleItem::Concentrate(concentrate))
+    }
+
+    pub fn splines(self, splines : GraphvizSplines) -> Self {
+        self.item(GraphvizGraphStyleItem::Splines(splines))
+    }
+
+    pub fn node(mut self, node : impl Into<GraphVizNode>) -> Self {
+        self.graph.node(node);
+        self
+    }
+
+    pub fn edge(mut self, edge : impl Into<GraphVizEdge>) -> Self {
+        self.graph.edge(edge);
+        self
+    }
+
+    /// adds a cluster, built by the given function
+    pub fn cluster<F>(mut self, id : impl Into<String>, build : F) -> Self
+        where F : FnOnce(GvClusterBuilder) -> GvClusterBuilder {
+        self.graph.cluster(id,build);
+        self
+    }
+
+    pub fn build(self) -> GraphVizDiGraph {
+        self.graph
+    }
+
+}
+
+impl Default for GvGraphBuilder {
+    fn default() -> Self {
+        GvGraphBuilder::new()
+    }
+}
+
+impl From<GvGraphBuilder> for GraphVizDiGraph {
+    fn from(builder : GvGraphBuilder) -> GraphVizDiGraph {
+        builder.build()
+    }
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::builder::edge::GvEdgeBuilder;
+    use crate::builder::node::GvNodeBuilder;
+    use crate::item::cluster::GraphVizCluster;
+    use crate::item::item::GraphVizGraphItem;
+    use crate::traits::DotTranslatable;
+
+    #[test]
+    fn graph_style_and_elements() {
+        let graph = GvGraphBuilder::new()
+            .rankdir(GvGraphRankDir::LR)
+            .node_sep(1,5)
+            .concentrate(true)
+            .splines(GraphvizSplines::Ortho)
+            .rankdir(GvGraphRankDir::BT)
+            .node(GvNodeBuilder::new("a"))
+            .cluster("c1", |c1| c1.node(GvNodeBuilder::new("b")))
+            .edge(GvEdgeBuilder::new("a","b"))
+            .build();
+        assert!(graph.style == vec![GraphvizGraphStyleItem::Rankdir(GvGraphRankDir::BT),
+                                    GraphvizGraphStyleItem::NodeSep(1,5),
+                                    GraphvizGraphStyleItem::Concentrate(true),
+                                    GraphvizGraphStyleItem::Splines(GraphvizSplines::Ortho)]);
+        let cluster = GraphVizCluster::new("c1".to_string(),
+                                           vec![],
+                                           vec![Box::new(GraphVizGraphItem::Node(GraphVizNode::new("b".to_string(),vec![])))],
+                                           vec![]);
+        assert!(graph.items == vec![GraphVizGraphItem::Node(GraphVizNode::new("a".to_string(),vec![])),
+                                    GraphVizGraphItem::Cluster(cluster)]);
+        assert!(graph.edges == vec![GraphVizEdge::new("a".to_string(),None,"b".to_string(),None,vec![])]);
+        let dot = [
+            "digraph G {",
+            "compound=true;",
+            "rankdir=bt;",
+            "nodesep=1.5;",
+            "concentrate=true;",
+            "splines=ortho;",
+            "\ta;",
+            "\tsubgraph cluster_c1 {",
+            "\tb;",
+            "}",
+            "\ta->b;",
+            "}"
+        ];
+        assert_eq!(graph.to_dot_string(),dot.join("\n"));
+        let default : GraphVizDiGraph = GvGraphBuilder::default().into();
+        assert_eq!(default.to_dot_string(),"digraph G {\ncompound=true;\n}");
+    }
+
+}
@@ -0,0 +1,43 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+/*
+ Chainable builders for the elements of a graph.
+ Every builder can be converted "into" the element it builds,
+ so that it can be given directly to the "node", "edge" and "cluster" methods of the "DotBuildable" trait.
+ */
+
+pub mod node;
+pub mod edge;
+pub mod cluster;
+pub mod graph;
+
+
+use std::mem::discriminant;
+
+
+/// sets a style item, replacing any item of the same kind
+pub(crate) fn set_style_item<T>(style : &mut Vec<T>, item : T) {
+    match style.iter_mut().find(|existing| discriminant(*existing) == discriminant(&item)) {
+        Some(existing) => {
+            *existing = item;
+        },
+        None => {
+            style.push(item);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use crate::builder::set_style_item;
+use crate::colors::GraphvizColor;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::{GraphvizNodeStyle, GraphvizNodeStyleItem, GvNodeShape, GvNodeStyleKind};
+
+
+/**
+ Builder for a node :
+ GvNodeBuilder::new("a").label("A").shape(GvNodeShape::Rectangle).fill(GraphvizColor::red)
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvNodeBuilder {
+    id : String,
+    style : GraphvizNodeStyle
+}
+
+impl GvNodeBuilder {
+
+    pub fn new(id : impl Into<String>) -> GvNodeBuilder {
+        GvNodeBuilder{id:id.into(),style:vec![]}
+    }
+
+    /// sets a style item, replacing any item of the same kind
+    pub fn item(mut self, item : GraphvizNodeStyleItem) -> Self {
+        set_node_style_item(&mut self.style,item);
+        self
+    }
+
+    pub fn label(self, label : impl Into<String>) -> Self {
+        self.item(GraphvizNodeStyleItem::Label(label.into()))
+    }
+
+    pub fn shape(self, shape : GvNodeShape) -> Self {
+        self.item(GraphvizNodeStyleItem::Shape(shape))
+    }
+
+    /// adds a kind of style to the style of the node (e.g. dashed, rounded, ...)
+    pub fn style(self, kind : GvNodeStyleKind) -> Self {
+        self.item(GraphvizNodeStyleItem::Style(vec![kind]))
+    }
+
+    pub fn image(self, path : impl Into<String>) -> Self {
+        self.item(GraphvizNodeStyleItem::Image(path.into()))
+    }
+
+    pub fn color(self, color : GraphvizColor) -> Self {
+        self.item(GraphvizNodeStyleItem::Color(color))
+    }
+
+    pub fn fill(self, color : GraphvizColor) -> Self {
+        self.item(GraphvizNodeStyleItem::FillColor(color))
+    }
+
+    pub fn font_color(self, color : GraphvizColor) -> Self {
+        self.item(GraphvizNodeStyleItem::FontColor(color))
+    }
+
+    pub fn font_size(self, size : u32) -> Self {
+        self.item(GraphvizNodeStyleItem::FontSize(size))
+    }
+
+    pub fn font_name(self, name : impl Into<String>) -> Self {
+        self.item(GraphvizNodeStyleItem::FontName(name.into()))
+    }
+
+    pub fn height(self, height : u32) -> Self {
+        self.item(GraphvizNodeStyleItem::Height(height))
+    }
+
+    pub fn width(self, width : u32) -> Self {
+        self.item(GraphvizNodeStyleItem::Width(width))
+    }
+
+    pub fn peripheries(self, peripheries : u32) -> Self {
+        self.item(GraphvizNodeStyleItem::Peripheries(peripheries))
+    }
+
+    pub fn pen_width(self, width : u32) -> Self {
+        self.item(GraphvizNodeStyleItem::PenWidth(width))
+    }
+
+    pub fn build(self) -> GraphVizNode {
+        GraphVizNode::new(self.id,self.style)
+    }
+
+}
+
+/// sets a node style item, the kinds of style of a "Style" item being added to the existing ones
+pub(crate) fn set_node_style_item(style : &mut GraphvizNodeStyle, item : GraphvizNodeStyleItem) {
+    match item {
+        GraphvizNodeStyleItem::Style(kinds) => {
+            add_style_kinds(style,kinds);
+        },
+        GraphvizNodeStyleItem::FillColor(_) => {
+            set_style_item(style,item);
+            // the fill color is only drawn if "filled" is in the style, which the "style=..." attribute would override
+            if style.iter().any(|existing| matches!(existing, GraphvizNodeStyleItem::Style(_))) {
+                add_style_kinds(style,vec![GvNodeStyleKind::Filled]);
+            }
+        },
+        _ => {
+            set_style_item(style,item);
+        }
+    }
+}
+
+fn add_style_kinds(style : &mut GraphvizNodeStyle, mut kinds : Vec<GvNodeStyleKind>) {
+    if style.iter().any(|existing| matches!(existing, GraphvizNodeStyleItem::FillColor(_)))
+        && !kinds.contains(&GvNodeStyleKind::Filled) {
+        kinds.push(GvNodeStyleKind::Filled);
+    }
+    for existing in style.iter_mut() {
+        if let GraphvizNodeStyleItem::Style(existing_kinds) = existing {
+            for kind in kinds {
+                if !existing_kinds.contains(&kind) {
+                    existing_kinds.push(kind);
+                }
+            }
+            return;
+        }
+    }
+    style.push(GraphvizNodeStyleItem::Style(kinds));
+}
+
+impl From<GvNodeBuilder> for GraphVizNode {
+    fn from(builder : GvNodeBuilder) -> GraphVizNode {
+        builder.build()
+    }
+}
+
+impl From<GvNodeBuilder> for GraphVizGraphItem {
+    fn from(builder : GvNodeBuilder) -> GraphVizGraphItem {
+        GraphVizGraphItem::Node(builder.build())
+    }
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::traits::DotTranslatable;
+
+    #[test]
+    fn items_of_the_same_kind_are_replaced() {
+        let node = GvNodeBuilder::new("a")
+            .label("A")
+            .shape(GvNodeShape::Rectangle)
+            .style(GvNodeStyleKind::Dashed)
+            .fill(GraphvizColor::red)
+            .color(GraphvizColor::blue)
+            .label("B")
+            .build();
+        let expected = GraphVizNode::new("a".to_string(),vec![
+            GraphvizNodeStyleItem::Label("B".to_string()),
+            GraphvizNodeStyleItem::Shape(GvNodeShape::Rectangle),
+            GraphvizNodeStyleItem::Style(vec![GvNodeStyleKind::Dashed,GvNodeStyleKind::Filled]),
+            GraphvizNodeStyleItem::FillColor(GraphvizColor::red),
+            GraphvizNodeStyleItem::Color(GraphvizColor::blue)
+        ]);
+        assert!(node == expected);
+        assert_eq!(node.to_dot_string(),"a [label=\"B\",shape=rectangle,style=\"dashed,filled\",style=filled;fillcolor=red,color=blue];");
+    }
+
+    #[test]
+    fn style_kinds_are_added() {
+        let node = GvNodeBuilder::new("a")
+            .fill(GraphvizColor::red)
+            .style(GvNodeStyleKind::Rounded)
+            .style(GvNodeStyleKind::Rounded)
+            .style(GvNodeStyleKind::Bold)
+            .build();
+        let expected = GraphVizNode::new("a".to_string(),vec![
+            GraphvizNodeStyleItem::FillColor(GraphvizColor::red),
+            GraphvizNodeStyleItem::Style(vec![GvNodeStyleKind::Rounded,GvNodeStyleKind::Filled,GvNodeStyleKind::Bold])
+        ]);
+        assert!(node == expected);
+    }
+
+    #[test]
+    fn every_attribute() {
+        let node : GraphVizNode = GvNodeBuilder::new("a")
+            .image("a.png")
+            .font_color(GraphvizColor::green)
+            .font_size(10)
+            .font_name("Courier")
+            .height(1)
+            .width(2)
+            .peripheries(2)
+            .pen_width(3)
+            .into();
+        assert_eq!(node.to_dot_string(),
+                   "a [imagescale=true;image=\"a.png\",fontcolor=green,fontsize=10,fontname=\"Courier\",height=1,width=2,peripheries=2,penwidth=3];");
+        let item : GraphVizGraphItem = GvNodeBuilder::new("b").into();
+        assert!(item == GraphVizGraphItem::Node(GraphVizNode::new("b".to_string(),vec![])));
+    }
+
+}
@@ -15,12 +15,15 @@ limitations under the License.
 */
 
 
-use strum_macros::IntoStaticStr;
-use crate::traits::DotTranslatable;
+use strum_macros::{EnumString, IntoStaticStr};
+use std::fmt;
+use crate::traits::{write_lowercase, DotTranslatable};
 
 
 #[allow(non_camel_case_types)]
-#[derive(IntoStaticStr,Eq,PartialEq,Clone)]
+#[derive(IntoStaticStr,EnumString,Eq,PartialEq,Clone)]
+#[strum(ascii_case_insensitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GraphvizColor {
     aliceblue,antiquewhite,antiquewhite1,antiquewhite2,antiquewhite3,
     antiquewhite4,aquamarine,aquamarine1,aquamarine2,aquamarine3,
@@ -156,8 +159,684 @@ pub enum GraphvizColor {
 }
 
 impl DotTranslatable for GraphvizColor {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         let as_static_str : &'static str = self.into();
-        as_static_str.to_string().to_lowercase()
+        write_lowercase(writer, as_static_str)
     }
 }
+
+
+impl GraphvizColor {
+
+    /**
+     Red, green and blue components of the color as defined in the X11 color scheme used by Graphviz.
+     Returns None for "invis", "none" and "transparent".
+     **/
+    pub fn to_rgb(&self) -> Option<(u8,u8,u8)> {
+        match self {
+            GraphvizColor::aliceblue => Some((240,248,255)),
+            GraphvizColor::antiquewhite => Some((250,235,215)),
+            GraphvizColor::antiquewhite1 => Some((255,239,219)),
+            GraphvizColor::antiquewhite2 => Some((238,223,204)),
+            GraphvizColor::antiquewhite3 => Some((205,192,176)),
+            GraphvizColor::antiquewhite4 => Some((139,131,120)),
+            GraphvizColor::aquamarine => Some((127,255,212)),
+            GraphvizColor::aquamarine1 => Some((127,255,212)),
+            GraphvizColor::aquamarine2 => Some((118,238,198)),
+            GraphvizColor::aquamarine3 => Some((102,205,170)),
+            GraphvizColor::aquamarine4 => Some((69,139,116)),
+            GraphvizColor::azure => Some((240,255,255)),
+            GraphvizColor::azure1 => Some((240,255,255)),
+            GraphvizColor::azure2 => Some((224,238,238)),
+            GraphvizColor::azure3 => Some((193,205,205)),
+            GraphvizColor::azure4 => Some((131,139,139)),
+            GraphvizColor::beige => Some((245,245,220)),
+            GraphvizColor::bisque => Some((255,228,196)),
+            GraphvizColor::bisque1 => Some((255,228,196)),
+            GraphvizColor::bisque2 => Some((238,213,183)),
+            GraphvizColor::bisque3 => Some((205,183,158)),
+            GraphvizColor::bisque4 => Some((139,125,107)),
+            GraphvizColor::black => Some((0,0,0)),
+            GraphvizColor::blanchedalmond => Some((255,235,205)),
+            GraphvizColor::blue => Some((0,0,255)),
+            GraphvizColor::blue1 => Some((0,0,255)),
+            GraphvizColor::blue2 => Some((0,0,238)),
+            GraphvizColor::blue3 => Some((0,0,205)),
+            GraphvizColor::blue4 => Some((0,0,139)),
+            GraphvizColor::blueviolet => Some((138,43,226)),
+            GraphvizColor::brown => Some((165,42,42)),
+            GraphvizColor::brown1 => Some((255,64,64)),
+            GraphvizColor::brown2 => Some((238,59,59)),
+            GraphvizColor::brown3 => Some((205,51,51)),
+            GraphvizColor::brown4 => Some((139,35,35)),
+            GraphvizColor::burlywood => Some((222,184,135)),
+            GraphvizColor::burlywood1 => Some((255,211,155)),
+            GraphvizColor::burlywood2 => Some((238,197,145)),
+            GraphvizColor::burlywood3 => Some((205,170,125)),
+            GraphvizColor::burlywood4 => Some((139,115,85)),
+            GraphvizColor::cadetblue => Some((95,158,160)),
+            GraphvizColor::cadetblue1 => Some((152,245,255)),
+            GraphvizColor::cadetblue2 => Some((142,229,238)),
+            GraphvizColor::cadetblue3 => Some((122,197,205)),
+            GraphvizColor::cadetblue4 => Some((83,134,139)),
+            GraphvizColor::chartreuse => Some((127,255,0)),
+            GraphvizColor::chartreuse1 => Some((127,255,0)),
+            GraphvizColor::chartreuse2 => Some((118,238,0)),
+            GraphvizColor::chartreuse3 => Some((102,205,0)),
+            GraphvizColor::chartreuse4 => Some((69,139,0)),
+            GraphvizColor::chocolate => Some((210,105,30)),
+            GraphvizColor::chocolate1 => Some((255,127,36)),
+            GraphvizColor::chocolate2 => Some((238,118,33)),
+            GraphvizColor::chocolate3 => Some((205,102,29)),
+            GraphvizColor::chocolate4 => Some((139,69,19)),
+            GraphvizColor::coral => Some((255,127,80)),
+            GraphvizColor::coral1 => Some((255,114,86)),
+            GraphvizColor::coral2 => Some((238,106,80)),
+            GraphvizColor::coral3 => Some((205,91,69)),
+            GraphvizColor::coral4 => Some((139,62,47)),
+            GraphvizColor::cornflowerblue => Some((100,149,237)),
+            GraphvizColor::cornsilk => Some((255,248,220)),
+            GraphvizColor::cornsilk1 => Some((255,248,220)),
+            GraphvizColor::cornsilk2 => Some((238,232,205)),
+            GraphvizColor::cornsilk3 => Some((205,200,177)),
+            GraphvizColor::cornsilk4 => Some((139,136,120)),
+            GraphvizColor::crimson => Some((220,20,60)),
+            GraphvizColor::cyan => Some((0,255,255)),
+            GraphvizColor::cyan1 => Some((0,255,255)),
+            GraphvizColor::cyan2 => Some((0,238,238)),
+            GraphvizColor::cyan3 => Some((0,205,205)),
+            GraphvizColor::cyan4 => Some((0,139,139)),
+            GraphvizColor::darkgoldenrod => Some((184,134,11)),
+            GraphvizColor::darkgoldenrod1 => Some((255,185,15)),
+            GraphvizColor::darkgoldenrod2 => Some((238,173,14)),
+            GraphvizColor::darkgoldenrod3 => Some((205,149,12)),
+            GraphvizColor::darkgoldenrod4 => Some((139,101,8)),
+            GraphvizColor::darkgreen => Some((0,100,0)),
+            GraphvizColor::darkkhaki => Some((189,183,107)),
+            GraphvizColor::darkolivegreen => Some((85,107,47)),
+            GraphvizColor::darkolivegreen1 => Some((202,255,112)),
+            GraphvizColor::darkolivegreen2 => Some((188,238,104)),
+            GraphvizColor::darkolivegreen3 => Some((162,205,90)),
+            GraphvizColor::darkolivegreen4 => Some((110,139,61)),
+            GraphvizColor::darkorange => Some((255,140,0)),
+            GraphvizColor::darkorange1 => Some((255,127,0)),
+            GraphvizColor::darkorange2 => Some((238,118,0)),
+            GraphvizColor::darkorange3 => Some((205,102,0)),
+            GraphvizColor::darkorange4 => Some((139,69,0)),
+            GraphvizColor::darkorchid => Some((153,50,204)),
+            GraphvizColor::darkorchid1 => Some((191,62,255)),
+            GraphvizColor::darkorchid2 => Some((178,58,238)),
+            GraphvizColor::darkorchid3 => Some((154,50,205)),
+            GraphvizColor::darkorchid4 => Some((104,34,139)),
+            GraphvizColor::darksalmon => Some((233,150,122)),
+            GraphvizColor::darkseagreen => Some((143,188,143)),
+            GraphvizColor::darkseagreen1 => Some((193,255,193)),
+            GraphvizColor::darkseagreen2 => Some((180,238,180)),
+            GraphvizColor::darkseagreen3 => Some((155,205,155)),
+            GraphvizColor::darkseagreen4 => Some((105,139,105)),
+            GraphvizColor::darkslateblue => Some((72,61,139)),
+            GraphvizColor::darkslategray => Some((47,79,79)),
+            GraphvizColor::darkslategray1 => Some((151,255,255)),
+            GraphvizColor::darkslategray2 => Some((141,238,238)),
+            GraphvizColor::darkslategray3 => Some((121,205,205)),
+            GraphvizColor::darkslategray4 => Some((82,139,139)),
+            GraphvizColor::darkslategrey => Some((47,79,79)),
+            GraphvizColor::darkturquoise => Some((0,206,209)),
+            GraphvizColor::darkviolet => Some((148,0,211)),
+            GraphvizColor::deeppink => Some((255,20,147)),
+            GraphvizColor::deeppink1 => Some((255,20,147)),
+            GraphvizColor::deeppink2 => Some((238,18,137)),
+            GraphvizColor::deeppink3 => Some((205,16,118)),
+            GraphvizColor::deeppink4 => Some((139,10,80)),
+            GraphvizColor::deepskyblue => Some((0,191,255)),
+            GraphvizColor::deepskyblue1 => Some((0,191,255)),
+            GraphvizColor::deepskyblue2 => Some((0,178,238)),
+            GraphvizColor::deepskyblue3 => Some((0,154,205)),
+            GraphvizColor::deepskyblue4 => Some((0,104,139)),
+            GraphvizColor::dimgray => Some((105,105,105)),
+            GraphvizColor::dimgrey => Some((105,105,105)),
+            GraphvizColor::dodgerblue => Some((30,144,255)),
+            GraphvizColor::dodgerblue1 => Some((30,144,255)),
+            GraphvizColor::dodgerblue2 => Some((28,134,238)),
+            GraphvizColor::dodgerblue3 => Some((24,116,205)),
+            GraphvizColor::dodgerblue4 => Some((16,78,139)),
+            GraphvizColor::firebrick => Some((178,34,34)),
+            GraphvizColor::firebrick1 => Some((255,48,48)),
+            GraphvizColor::firebrick2 => Some((238,44,44)),
+            GraphvizColor::firebrick3 => Some((205,38,38)),
+            GraphvizColor::firebrick4 => Some((139,26,26)),
+            GraphvizColor::floralwhite => Some((255,250,240)),
+            GraphvizColor::forestgreen => Some((34,139,34)),
+            GraphvizColor::gainsboro => Some((220,220,220)),
+            GraphvizColor::ghostwhite => Some((248,248,255)),
+            GraphvizColor::gold => Some((255,215,0)),
+            GraphvizColor::gold1 => Some((255,215,0)),
+            GraphvizColor::gold2 => Some((238,201,0)),
+            GraphvizColor::gold3 => Some((205,173,0)),
+            GraphvizColor::gold4 => Some((139,117,0)),
+            GraphvizColor::goldenrod => Some((218,165,32)),
+            GraphvizColor::goldenrod1 => Some((255,193,37)),
+            GraphvizColor::goldenrod2 => Some((238,180,34)),
+            GraphvizColor::goldenrod3 => Some((205,155,29)),
+            GraphvizColor::goldenrod4 => Some((139,105,20)),
+            GraphvizColor::gray => Some((192,192,192)),
+            GraphvizColor::gray0 => Some((0,0,0)),
+            GraphvizColor::gray1 => Some((3,3,3)),
+            GraphvizColor::gray10 => Some((26,26,26)),
+            GraphvizColor::gray100 => Some((255,255,255)),
+            GraphvizColor::gray11 => Some((28,28,28)),
+            GraphvizColor::gray12 => Some((31,31,31)),
+            GraphvizColor::gray13 => Some((33,33,33)),
+            GraphvizColor::gray14 => Some((36,36,36)),
+            GraphvizColor::gray15 => Some((38,38,38)),
+            GraphvizColor::gray16 => Some((41,41,41)),
+            GraphvizColor::gray17 => Some((43,43,43)),
+            GraphvizColor::gray18 => Some((46,46,46)),
+            GraphvizColor::gray19 => Some((48,48,48)),
+            GraphvizColor::gray2 => Some((5,5,5)),
+            GraphvizColor::gray20 => Some((51,51,51)),
+            GraphvizColor::gray21 => Some((54,54,54)),
+            GraphvizColor::gray22 => Some((56,56,56)),
+            GraphvizColor::gray23 => Some((59,59,59)),
+            GraphvizColor::gray24 => Some((61,61,61)),
+            GraphvizColor::gray25 => Some((64,64,64)),
+            GraphvizColor::gray26 => Some((66,66,66)),
+            GraphvizColor::gray27 => Some((69,69,69)),
+            GraphvizColor::gray28 => Some((71,71,71)),
+            GraphvizColor::gray29 => Some((74,74,74)),
+            GraphvizColor::gray3 => Some((8,8,8)),
+            GraphvizColor::gray30 => Some((77,77,77)),
+            GraphvizColor::gray31 => Some((79,79,79)),
+            GraphvizColor::gray32 => Some((82,82,82)),
+            GraphvizColor::gray33 => Some((84,84,84)),
+            GraphvizColor::gray34 => Some((87,87,87)),
+            GraphvizColor::gray35 => Some((89,89,89)),
+            GraphvizColor::gray36 => Some((92,92,92)),
+            GraphvizColor::gray37 => Some((94,94,94)),
+            GraphvizColor::gray38 => Some((97,97,97)),
+            GraphvizColor::gray39 => Some((99,99,99)),
+            GraphvizColor::gray4 => Some((10,10,10)),
+            GraphvizColor::gray40 => Some((102,102,102)),
+            GraphvizColor::gray41 => Some((105,105,105)),
+            GraphvizColor::gray42 => Some((107,107,107)),
+            GraphvizColor::gray43 => Some((110,110,110)),
+            GraphvizColor::gray44 => Some((112,112,112)),
+            GraphvizColor::gray45 => Some((115,115,115)),
+            GraphvizColor::gray46 => Some((117,117,117)),
+            GraphvizColor::gray47 => Some((120,120,120)),
+            GraphvizColor::gray48 => Some((122,122,122)),
+            GraphvizColor::gray49 => Some((125,125,125)),
+            GraphvizColor::gray5 => Some((13,13,13)),
+            GraphvizColor::gray50 => Some((127,127,127)),
+            GraphvizColor::gray51 => Some((130,130,130)),
+            GraphvizColor::gray52 => Some((133,133,133)),
+            GraphvizColor::gray53 => Some((135,135,135)),
+            GraphvizColor::gray54 => Some((138,138,138)),
+            GraphvizColor::gray55 => Some((140,140,140)),
+            GraphvizColor::gray56 => Some((143,143,143)),
+            GraphvizColor::gray57 => Some((145,145,145)),
+            GraphvizColor::gray58 => Some((148,148,148)),
+            GraphvizColor::gray59 => Some((150,150,150)),
+            GraphvizColor::gray6 => Some((15,15,15)),
+            GraphvizColor::gray60 => Some((153,153,153)),
+            GraphvizColor::gray61 => Some((156,156,156)),
+            GraphvizColor::gray62 => Some((158,158,158)),
+            GraphvizColor::gray63 => Some((161,161,161)),
+            GraphvizColor::gray64 => Some((163,163,163)),
+            GraphvizColor::gray65 => Some((166,166,166)),
+            GraphvizColor::gray66 => Some((168,168,168)),
+            GraphvizColor::gray67 => Some((171,171,171)),
+            GraphvizColor::gray68 => Some((173,173,173)),
+            GraphvizColor::gray69 => Some((176,176,176)),
+            GraphvizColor::gray7 => Some((18,18,18)),
+            GraphvizColor::gray70 => Some((179,179,179)),
+            GraphvizColor::gray71 => Some((181,181,181)),
+            GraphvizColor::gray72 => Some((184,184,184)),
+            GraphvizColor::gray73 => Some((186,186,186)),
+            GraphvizColor::gray74 => Some((189,189,189)),
+            GraphvizColor::gray75 => Some((191,191,191)),
+            GraphvizColor::gray76 => Some((194,194,194)),
+            GraphvizColor::gray77 => Some((196,196,196)),
+            GraphvizColor::gray78 => Some((199,199,199)),
+            GraphvizColor::gray79 => Some((201,201,201)),
+            GraphvizColor::gray8 => Some((20,20,20)),
+            GraphvizColor::gray80 => Some((204,204,204)),
+            GraphvizColor::gray81 => Some((207,207,207)),
+            GraphvizColor::gray82 => Some((209,209,209)),
+            GraphvizColor::gray83 => Some((212,212,212)),
+            GraphvizColor::gray84 => Some((214,214,214)),
+            GraphvizColor::gray85 => Some((217,217,217)),
+            GraphvizColor::gray86 => Some((219,219,219)),
+            GraphvizColor::gray87 => Some((222,222,222)),
+            GraphvizColor::gray88 => Some((224,224,224)),
+            GraphvizColor::gray89 => Some((227,227,227)),
+            GraphvizColor::gray9 => Some((23,23,23)),
+            GraphvizColor::gray90 => Some((229,229,229)),
+            GraphvizColor::gray91 => Some((232,232,232)),
+            GraphvizColor::gray92 => Some((235,235,235)),
+            GraphvizColor::gray93 => Some((237,237,237)),
+            GraphvizColor::gray94 => Some((240,240,240)),
+            GraphvizColor::gray95 => Some((242,242,242)),
+            GraphvizColor::gray96 => Some((245,245,245)),
+            GraphvizColor::gray97 => Some((247,247,247)),
+            GraphvizColor::gray98 => Some((250,250,250)),
+            GraphvizColor::gray99 => Some((252,252,252)),
+            GraphvizColor::green => Some((0,255,0)),
+            GraphvizColor::green1 => Some((0,255,0)),
+            GraphvizColor::green2 => Some((0,238,0)),
+            GraphvizColor::green3 => Some((0,205,0)),
+            GraphvizColor::green4 => Some((0,139,0)),
+            GraphvizColor::greenyellow => Some((173,255,47)),
+            GraphvizColor::grey => Some((192,192,192)),
+            GraphvizColor::grey0 => Some((0,0,0)),
+            GraphvizColor::grey1 => Some((3,3,3)),
+            GraphvizColor::grey10 => Some((26,26,26)),
+            GraphvizColor::grey100 => Some((255,255,255)),
+            GraphvizColor::grey11 => Some((28,28,28)),
+            GraphvizColor::grey12 => Some((31,31,31)),
+            GraphvizColor::grey13 => Some((33,33,33)),
+            GraphvizColor::grey14 => Some((36,36,36)),
+            GraphvizColor::grey15 => Some((38,38,38)),
+            GraphvizColor::grey16 => Some((41,41,41)),
+            GraphvizColor::grey17 => Some((43,43,43)),
+            GraphvizColor::grey18 => Some((46,46,46)),
+            GraphvizColor::grey19 => Some((48,48,48)),
+            GraphvizColor::grey2 => Some((5,5,5)),
+            GraphvizColor::grey20 => Some((51,51,51)),
+            GraphvizColor::grey21 => Some((54,54,54)),
+            GraphvizColor::grey22 => Some((56,56,56)),
+            GraphvizColor::grey23 => Some((59,59,59)),
+            GraphvizColor::grey24 => Some((61,61,61)),
+            GraphvizColor::grey25 => Some((64,64,64)),
+            GraphvizColor::grey26 => Some((66,66,66)),
+            GraphvizColor::grey27 => Some((69,69,69)),
+            GraphvizColor::grey28 => Some((71,71,71)),
+            GraphvizColor::grey29 => Some((74,74,74)),
+            GraphvizColor::grey3 => Some((8,8,8)),
+            GraphvizColor::grey30 => Some((77,77,77)),
+            GraphvizColor::grey31 => Some((79,79,79)),
+            GraphvizColor::grey32 => Some((82,82,82)),
+            GraphvizColor::grey33 => Some((84,84,84)),
+            GraphvizColor::grey34 => Some((87,87,87)),
+            GraphvizColor::grey35 => Some((89,89,89)),
+            GraphvizColor::grey36 => Some((92,92,92)),
+            GraphvizColor::grey37 => Some((94,94,94)),
+            GraphvizColor::grey38 => Some((97,97,97)),
+            GraphvizColor::grey39 => Some((99,99,99)),
+            GraphvizColor::grey4 => Some((10,10,10)),
+            GraphvizColor::grey40 => Some((102,102,102)),
+            GraphvizColor::grey41 => Some((105,105,105)),
+            GraphvizColor::grey42 => Some((107,107,107)),
+            GraphvizColor::grey43 => Some((110,110,110)),
+            GraphvizColor::grey44 => Some((112,112,112)),
+            GraphvizColor::grey45 => Some((115,115,115)),
+            GraphvizColor::grey46 => Some((117,117,117)),
+            GraphvizColor::grey47 => Some((120,120,120)),
+            GraphvizColor::grey48 => Some((122,122,122)),
+            GraphvizColor::grey49 => Some((125,125,125)),
+            GraphvizColor::grey5 => Some((13,13,13)),
+            GraphvizColor::grey50 => Some((127,127,127)),
+            GraphvizColor::grey51 => Some((130,130,130)),
+            GraphvizColor::grey52 => Some((133,133,133)),
+            GraphvizColor::grey53 => Some((135,135,135)),
+            GraphvizColor::grey54 => Some((138,138,138)),
+            GraphvizColor::grey55 => Some((140,140,140)),
+            GraphvizColor::grey56 => Some((143,143,143)),
+            GraphvizColor::grey57 => Some((145,145,145)),
+            GraphvizColor::grey58 => Some((148,148,148)),
+            GraphvizColor::grey59 => Some((150,150,150)),
+            GraphvizColor::grey6 => Some((15,15,15)),
+            GraphvizColor::grey60 => Some((153,153,153)),
+            GraphvizColor::grey61 => Some((156,156,156)),
+            GraphvizColor::grey62 => Some((158,158,158)),
+            GraphvizColor::grey63 => Some((161,161,161)),
+            GraphvizColor::grey64 => Some((163,163,163)),
+            GraphvizColor::grey65 => Some((166,166,166)),
+            GraphvizColor::grey66 => Some((168,168,168)),
+            GraphvizColor::grey67 => Some((171,171,171)),
+            GraphvizColor::grey68 => Some((173,173,173)),
+            GraphvizColor::grey69 => Some((176,176,176)),
+            GraphvizColor::grey7 => Some((18,18,18)),
+            GraphvizColor::grey70 => Some((179,179,179)),
+            GraphvizColor::grey71 => Some((181,181,181)),
+            GraphvizColor::grey72 => Some((184,184,184)),
+            GraphvizColor::grey73 => Some((186,186,186)),
+            GraphvizColor::grey74 => Some((189,189,189)),
+            GraphvizColor::grey75 => Some((191,191,191)),
+            GraphvizColor::grey76 => Some((194,194,194)),
+            GraphvizColor::grey77 => Some((196,196,196)),
+            GraphvizColor::grey78 => Some((199,199,199)),
+            GraphvizColor::grey79 => Some((201,201,201)),
+            GraphvizColor::grey8 => Some((20,20,20)),
+            GraphvizColor::grey80 => Some((204,204,204)),
+            GraphvizColor::grey81 => Some((207,207,207)),
+            GraphvizColor::grey82 => Some((209,209,209)),
+            GraphvizColor::grey83 => Some((212,212,212)),
+            GraphvizColor::grey84 => Some((214,214,214)),
+            GraphvizColor::grey85 => Some((217,217,217)),
+            GraphvizColor::grey86 => Some((219,219,219)),
+            GraphvizColor::grey87 => Some((222,222,222)),
+            GraphvizColor::grey88 => Some((224,224,224)),
+            GraphvizColor::grey89 => Some((227,227,227)),
+            GraphvizColor::grey9 => Some((23,23,23)),
+            GraphvizColor::grey90 => Some((229,229,229)),
+            GraphvizColor::grey91 => Some((232,232,232)),
+            GraphvizColor::grey92 => Some((235,235,235)),
+            GraphvizColor::grey93 => Some((237,237,237)),
+            GraphvizColor::grey94 => Some((240,240,240)),
+            GraphvizColor::grey95 => Some((242,242,242)),
+            GraphvizColor::grey96 => Some((245,245,245)),
+            GraphvizColor::grey97 => Some((247,247,247)),
+            GraphvizColor::grey98 => Some((250,250,250)),
+            GraphvizColor::grey99 => Some((252,252,252)),
+            GraphvizColor::honeydew => Some((240,255,240)),
+            GraphvizColor::honeydew1 => Some((240,255,240)),
+            GraphvizColor::honeydew2 => Some((224,238,224)),
+            GraphvizColor::honeydew3 => Some((193,205,193)),
+            GraphvizColor::honeydew4 => Some((131,139,131)),
+            GraphvizColor::hotpink => Some((255,105,180)),
+            GraphvizColor::hotpink1 => Some((255,110,180)),
+            GraphvizColor::hotpink2 => Some((238,106,167)),
+            GraphvizColor::hotpink3 => Some((205,96,144)),
+            GraphvizColor::hotpink4 => Some((139,58,98)),
+            GraphvizColor::indianred => Some((205,92,92)),
+            GraphvizColor::indianred1 => Some((255,106,106)),
+            GraphvizColor::indianred2 => Some((238,99,99)),
+            GraphvizColor::indianred3 => Some((205,85,85)),
+            GraphvizColor::indianred4 => Some((139,58,58)),
+            GraphvizColor::indigo => Some((75,0,130)),
+            GraphvizColor::ivory => Some((255,255,240)),
+            GraphvizColor::ivory1 => Some((255,255,240)),
+            GraphvizColor::ivory2 => Some((238,238,224)),
+            GraphvizColor::ivory3 => Some((205,205,193)),
+            GraphvizColor::ivory4 => Some((139,139,131)),
+            GraphvizColor::khaki => Some((240,230,140)),
+            GraphvizColor::khaki1 => Some((255,246,143)),
+            GraphvizColor::khaki2 => Some((238,230,133)),
+            GraphvizColor::khaki3 => Some((205,198,115)),
+            GraphvizColor::khaki4 => Some((139,134,78)),
+            GraphvizColor::lavender => Some((230,230,250)),
+            GraphvizColor::lavenderblush => Some((255,240,245)),
+            GraphvizColor::lavenderblush1 => Some((255,240,245)),
+            GraphvizColor::lavenderblush2 => Some((238,224,229)),
+            GraphvizColor::lavenderblush3 => Some((205,193,197)),
+            GraphvizColor::lavenderblush4 => Some((139,131,134)),
+            GraphvizColor::lawngreen => Some((124,252,0)),
+            GraphvizColor::lemonchiffon => Some((255,250,205)),
+            GraphvizColor::lemonchiffon1 => Some((255,250,205)),
+            GraphvizColor::lemonchiffon2 => Some((238,233,191)),
+            GraphvizColor::lemonchiffon3 => Some((205,201,165)),
+            GraphvizColor::lemonchiffon4 => Some((139,137,112)),
+            GraphvizColor::lightblue => Some((173,216,230)),
+            GraphvizColor::lightblue1 => Some((191,239,255)),
+            GraphvizColor::lightblue2 => Some((178,223,238)),
+            GraphvizColor::lightblue3 => Some((154,192,205)),
+            GraphvizColor::lightblue4 => Some((104,131,139)),
+            GraphvizColor::lightcoral => Some((240,128,128)),
+            GraphvizColor::lightcyan => Some((224,255,255)),
+            GraphvizColor::lightcyan1 => Some((224,255,255)),
+            GraphvizColor::lightcyan2 => Some((209,238,238)),
+            GraphvizColor::lightcyan3 => Some((180,205,205)),
+            GraphvizColor::lightcyan4 => Some((122,139,139)),
+            GraphvizColor::lightgoldenrod => Some((238,221,130)),
+            GraphvizColor::lightgoldenrod1 => Some((255,236,139)),
+            GraphvizColor::lightgoldenrod2 => Some((238,220,130)),
+            GraphvizColor::lightgoldenrod3 => Some((205,190,112)),
+            GraphvizColor::lightgoldenrod4 => Some((139,129,76)),
+            GraphvizColor::lightgoldenrodyellow => Some((250,250,210)),
+            GraphvizColor::lightgray => Some((211,211,211)),
+            GraphvizColor::lightgrey => Some((211,211,211)),
+            GraphvizColor::lightpink => Some((255,182,193)),
+            GraphvizColor::lightpink1 => Some((255,174,185)),
+            GraphvizColor::lightpink2 => Some((238,162,173)),
+            GraphvizColor::lightpink3 => Some((205,140,149)),
+            GraphvizColor::lightpink4 => Some((139,95,101)),
+            GraphvizColor::lightsalmon => Some((255,160,122)),
+            GraphvizColor::lightsalmon1 => Some((255,160,122)),
+            GraphvizColor::lightsalmon2 => Some((238,149,114)),
+            GraphvizColor::lightsalmon3 => Some((205,129,98)),
+            GraphvizColor::lightsalmon4 => Some((139,87,66)),
+            GraphvizColor::lightseagreen => Some((32,178,170)),
+            GraphvizColor::lightskyblue => Some((135,206,250)),
+            GraphvizColor::lightskyblue1 => Some((176,226,255)),
+            GraphvizColor::lightskyblue2 => Some((164,211,238)),
+            GraphvizColor::lightskyblue3 => Some((141,182,205)),
+            GraphvizColor::lightskyblue4 => Some((96,123,139)),
+            GraphvizColor::lightslateblue => Some((132,112,255)),
+            GraphvizColor::lightslategray => Some((119,136,153)),
+            GraphvizColor::lightslategrey => Some((119,136,153)),
+            GraphvizColor::lightsteelblue => Some((176,196,222)),
+            GraphvizColor::lightsteelblue1 => Some((202,225,255)),
+            GraphvizColor::lightsteelblue2 => Some((188,210,238)),
+            GraphvizColor::lightsteelblue3 => Some((162,181,205)),
+            GraphvizColor::lightsteelblue4 => Some((110,123,139)),
+            GraphvizColor::lightyellow => Some((255,255,224)),
+            GraphvizColor::lightyellow1 => Some((255,255,224)),
+            GraphvizColor::lightyellow2 => Some((238,238,209)),
+            GraphvizColor::lightyellow3 => Some((205,205,180)),
+            GraphvizColor::lightyellow4 => Some((139,139,122)),
+            GraphvizColor::limegreen => Some((50,205,50)),
+            GraphvizColor::linen => Some((250,240,230)),
+            GraphvizColor::magenta => Some((255,0,255)),
+            GraphvizColor::magenta1 => Some((255,0,255)),
+            GraphvizColor::magenta2 => Some((238,0,238)),
+            GraphvizColor::magenta3 => Some((205,0,205)),
+            GraphvizColor::magenta4 => Some((139,0,139)),
+            GraphvizColor::maroon => Some((176,48,96)),
+            GraphvizColor::maroon1 => Some((255,52,179)),
+            GraphvizColor::maroon2 => Some((238,48,167)),
+            GraphvizColor::maroon3 => Some((205,41,144)),
+            GraphvizColor::maroon4 => Some((139,28,98)),
+            GraphvizColor::mediumaquamarine => Some((102,205,170)),
+            GraphvizColor::mediumblue => Some((0,0,205)),
+            GraphvizColor::mediumorchid => Some((186,85,211)),
+            GraphvizColor::mediumorchid1 => Some((224,102,255)),
+            GraphvizColor::mediumorchid2 => Some((209,95,238)),
+            GraphvizColor::mediumorchid3 => Some((180,82,205)),
+            GraphvizColor::mediumorchid4 => Some((122,55,139)),
+            GraphvizColor::mediumpurple => Some((147,112,219)),
+            GraphvizColor::mediumpurple1 => Some((171,130,255)),
+            GraphvizColor::mediumpurple2 => Some((159,121,238)),
+            GraphvizColor::mediumpurple3 => Some((137,104,205)),
+            GraphvizColor::mediumpurple4 => Some((93,71,139)),
+            GraphvizColor::mediumseagreen => Some((60,179,113)),
+            GraphvizColor::mediumslateblue => Some((123,104,238)),
+            GraphvizColor::mediumspringgreen => Some((0,250,154)),
+            GraphvizColor::mediumturquoise => Some((72,209,204)),
+            GraphvizColor::mediumvioletred => Some((199,21,133)),
+            GraphvizColor::midnightblue => Some((25,25,112)),
+            GraphvizColor::mintcream => Some((245,255,250)),
+            GraphvizColor::mistyrose => Some((255,228,225)),
+            GraphvizColor::mistyrose1 => Some((255,228,225)),
+            GraphvizColor::mistyrose2 => Some((238,213,210)),
+            GraphvizColor::mistyrose3 => Some((205,183,181)),
+            GraphvizColor::mistyrose4 => Some((139,125,123)),
+            GraphvizColor::moccasin => Some((255,228,181)),
+            GraphvizColor::navajowhite => Some((255,222,173)),
+            GraphvizColor::navajowhite1 => Some((255,222,173)),
+            GraphvizColor::navajowhite2 => Some((238,207,161)),
+            GraphvizColor::navajowhite3 => Some((205,179,139)),
+            GraphvizColor::navajowhite4 => Some((139,121,94)),
+            GraphvizColor::navy => Some((0,0,128)),
+            GraphvizColor::navyblue => Some((0,0,128)),
+            GraphvizColor::oldlace => Some((253,245,230)),
+            GraphvizColor::olivedrab => Some((107,142,35)),
+            GraphvizColor::olivedrab1 => Some((192,255,62)),
+            GraphvizColor::olivedrab2 => Some((179,238,58)),
+            GraphvizColor::olivedrab3 => Some((154,205,50)),
+            GraphvizColor::olivedrab4 => Some((105,139,34)),
+            GraphvizColor::orange => Some((255,165,0)),
+            GraphvizColor::orange1 => Some((255,165,0)),
+            GraphvizColor::orange2 => Some((238,154,0)),
+            GraphvizColor::orange3 => Some((205,133,0)),
+            GraphvizColor::orange4 => Some((139,90,0)),
+            GraphvizColor::orangered => Some((255,69,0)),
+            GraphvizColor::orangered1 => Some((255,69,0)),
+            GraphvizColor::orangered2 => Some((238,64,0)),
+            GraphvizColor::orangered3 => Some((205,55,0)),
+            GraphvizColor::orangered4 => Some((139,37,0)),
+            GraphvizColor::orchid => Some((218,112,214)),
+            GraphvizColor::orchid1 => Some((255,131,250)),
+            GraphvizColor::orchid2 => Some((238,122,233)),
+            GraphvizColor::orchid3 => Some((205,105,201)),
+            GraphvizColor::orchid4 => Some((139,71,137)),
+            GraphvizColor::palegoldenrod => Some((238,232,170)),
+            GraphvizColor::palegreen => Some((152,251,152)),
+            GraphvizColor::palegreen1 => Some((154,255,154)),
+            GraphvizColor::palegreen2 => Some((144,238,144)),
+            GraphvizColor::palegreen3 => Some((124,205,124)),
+            GraphvizColor::palegreen4 => Some((84,139,84)),
+            GraphvizColor::paleturquoise => Some((175,238,238)),
+            GraphvizColor::paleturquoise1 => Some((187,255,255)),
+            GraphvizColor::paleturquoise2 => Some((174,238,238)),
+            GraphvizColor::paleturquoise3 => Some((150,205,205)),
+            GraphvizColor::paleturquoise4 => Some((102,139,139)),
+            GraphvizColor::palevioletred => Some((219,112,147)),
+            GraphvizColor::palevioletred1 => Some((255,130,171)),
+            GraphvizColor::palevioletred2 => Some((238,121,159)),
+            GraphvizColor::palevioletred3 => Some((205,104,137)),
+            GraphvizColor::palevioletred4 => Some((139,71,93)),
+            GraphvizColor::papayawhip => Some((255,239,213)),
+            GraphvizColor::peachpuff => Some((255,218,185)),
+            GraphvizColor::peachpuff1 => Some((255,218,185)),
+            GraphvizColor::peachpuff2 => Some((238,203,173)),
+            GraphvizColor::peachpuff3 => Some((205,175,149)),
+            GraphvizColor::peachpuff4 => Some((139,119,101)),
+            GraphvizColor::peru => Some((205,133,63)),
+            GraphvizColor::pink => Some((255,192,203)),
+            GraphvizColor::pink1 => Some((255,181,197)),
+            GraphvizColor::pink2 => Some((238,169,184)),
+            GraphvizColor::pink3 => Some((205,145,158)),
+            GraphvizColor::pink4 => Some((139,99,108)),
+            GraphvizColor::plum => Some((221,160,221)),
+            GraphvizColor::plum1 => Some((255,187,255)),
+            GraphvizColor::plum2 => Some((238,174,238)),
+            GraphvizColor::plum3 => Some((205,150,205)),
+            GraphvizColor::plum4 => Some((139,102,139)),
+            GraphvizColor::powderblue => Some((176,224,230)),
+            GraphvizColor::purple => Some((160,32,240)),
+            GraphvizColor::purple1 => Some((155,48,255)),
+            GraphvizColor::purple2 => Some((145,44,238)),
+            GraphvizColor::purple3 => Some((125,38,205)),
+            GraphvizColor::purple4 => Some((85,26,139)),
+            GraphvizColor::red => Some((255,0,0)),
+            GraphvizColor::red1 => Some((255,0,0)),
+            GraphvizColor::red2 => Some((238,0,0)),
+            GraphvizColor::red3 => Some((205,0,0)),
+            GraphvizColor::red4 => Some((139,0,0)),
+            GraphvizColor::rosybrown => Some((188,143,143)),
+            GraphvizColor::rosybrown1 => Some((255,193,193)),
+            GraphvizColor::rosybrown2 => Some((238,180,180)),
+            GraphvizColor::rosybrown3 => Some((205,155,155)),
+            GraphvizColor::rosybrown4 => Some((139,105,105)),
+            GraphvizColor::royalblue => Some((65,105,225)),
+            GraphvizColor::royalblue1 => Some((72,118,255)),
+            GraphvizColor::royalblue2 => Some((67,110,238)),
+            GraphvizColor::royalblue3 => Some((58,95,205)),
+            GraphvizColor::royalblue4 => Some((39,64,139)),
+            GraphvizColor::saddlebrown => Some((139,69,19)),
+            GraphvizColor::salmon => Some((250,128,114)),
+            GraphvizColor::salmon1 => Some((255,140,105)),
+            GraphvizColor::salmon2 => Some((238,130,98)),
+            GraphvizColor::salmon3 => Some((205,112,84)),
+            GraphvizColor::salmon4 => Some((139,76,57)),
+            GraphvizColor::sandybrown => Some((244,164,96)),
+            GraphvizColor::seagreen => Some((46,139,87)),
+            GraphvizColor::seagreen1 => Some((84,255,159)),
+            GraphvizColor::seagreen2 => Some((78,238,148)),
+            GraphvizColor::seagreen3 => Some((67,205,128)),
+            GraphvizColor::seagreen4 => Some((46,139,87)),
+            GraphvizColor::seashell => Some((255,245,238)),
+            GraphvizColor::seashell1 => Some((255,245,238)),
+            GraphvizColor::seashell2 => Some((238,229,222)),
+            GraphvizColor::seashell3 => Some((205,197,191)),
+            GraphvizColor::seashell4 => Some((139,134,130)),
+            GraphvizColor::sienna => Some((160,82,45)),
+            GraphvizColor::sienna1 => Some((255,130,71)),
+            GraphvizColor::sienna2 => Some((238,121,66)),
+            GraphvizColor::sienna3 => Some((205,104,57)),
+            GraphvizColor::sienna4 => Some((139,71,38)),
+            GraphvizColor::skyblue => Some((135,206,235)),
+            GraphvizColor::skyblue1 => Some((135,206,255)),
+            GraphvizColor::skyblue2 => Some((126,192,238)),
+            GraphvizColor::skyblue3 => Some((108,166,205)),
+            GraphvizColor::skyblue4 => Some((74,112,139)),
+            GraphvizColor::slateblue => Some((106,90,205)),
+            GraphvizColor::slateblue1 => Some((131,111,255)),
+            GraphvizColor::slateblue2 => Some((122,103,238)),
+            GraphvizColor::slateblue3 => Some((105,89,205)),
+            GraphvizColor::slateblue4 => Some((71,60,139)),
+            GraphvizColor::slategray => Some((112,128,144)),
+            GraphvizColor::slategray1 => Some((198,226,255)),
+            GraphvizColor::slategray2 => Some((185,211,238)),
+            GraphvizColor::slategray3 => Some((159,182,205)),
+            GraphvizColor::slategray4 => Some((108,123,139)),
+            GraphvizColor::slategrey => Some((112,128,144)),
+            GraphvizColor::snow => Some((255,250,250)),
+            GraphvizColor::snow1 => Some((255,250,250)),
+            GraphvizColor::snow2 => Some((238,233,233)),
+            GraphvizColor::snow3 => Some((205,201,201)),
+            GraphvizColor::snow4 => Some((139,137,137)),
+            GraphvizColor::springgreen => Some((0,255,127)),
+            GraphvizColor::springgreen1 => Some((0,255,127)),
+            GraphvizColor::springgreen2 => Some((0,238,118)),
+            GraphvizColor::springgreen3 => Some((0,205,102)),
+            GraphvizColor::springgreen4 => Some((0,139,69)),
+            GraphvizColor::steelblue => Some((70,130,180)),
+            GraphvizColor::steelblue1 => Some((99,184,255)),
+            GraphvizColor::steelblue2 => Some((92,172,238)),
+            GraphvizColor::steelblue3 => Some((79,148,205)),
+            GraphvizColor::steelblue4 => Some((54,100,139)),
+            GraphvizColor::tan => Some((210,180,140)),
+            GraphvizColor::tan1 => Some((255,165,79)),
+            GraphvizColor::tan2 => Some((238,154,73)),
+            GraphvizColor::tan3 => Some((205,133,63)),
+            GraphvizColor::tan4 => Some((139,90,43)),
+            GraphvizColor::thistle => Some((216,191,216)),
+            GraphvizColor::thistle1 => Some((255,225,255)),
+            GraphvizColor::thistle2 => Some((238,210,238)),
+            GraphvizColor::thistle3 => Some((205,181,205)),
+            GraphvizColor::thistle4 => Some((139,123,139)),
+            GraphvizColor::tomato => Some((255,99,71)),
+            GraphvizColor::tomato1 => Some((255,99,71)),
+            GraphvizColor::tomato2 => Some((238,92,66)),
+            GraphvizColor::tomato3 => Some((205,79,57)),
+            GraphvizColor::tomato4 => Some((139,54,38)),
+            GraphvizColor::turquoise => Some((64,224,208)),
+            GraphvizColor::turquoise1 => Some((0,245,255)),
+            GraphvizColor::turquoise2 => Some((0,229,238)),
+            GraphvizColor::turquoise3 => Some((0,197,205)),
+            GraphvizColor::turquoise4 => Some((0,134,139)),
+            GraphvizColor::violet => Some((238,130,238)),
+            GraphvizColor::violetred => Some((208,32,144)),
+            GraphvizColor::violetred1 => Some((255,62,150)),
+            GraphvizColor::violetred2 => Some((238,58,140)),
+            GraphvizColor::violetred3 => Some((205,50,120)),
+            GraphvizColor::violetred4 => Some((139,34,82)),
+            GraphvizColor::wheat => Some((245,222,179)),
+            GraphvizColor::wheat1 => Some((255,231,186)),
+            GraphvizColor::wheat2 => Some((238,216,174)),
+            GraphvizColor::wheat3 => Some((205,186,150)),
+            GraphvizColor::wheat4 => Some((139,126,102)),
+            GraphvizColor::white => Some((255,255,255)),
+            GraphvizColor::whitesmoke => Some((245,245,245)),
+            GraphvizColor::yellow => Some((255,255,0)),
+            GraphvizColor::yellow1 => Some((255,255,0)),
+            GraphvizColor::yellow2 => Some((238,238,0)),
+            GraphvizColor::yellow3 => Some((205,205,0)),
+            GraphvizColor::yellow4 => Some((139,139,0)),
+            GraphvizColor::yellowgreen => Some((154,205,50)),
+            GraphvizColor::invis
+            | GraphvizColor::none
+            | GraphvizColor::transparent => None
+        }
+    }
+
+    /**
+     The color as an "#rrggbb" hexadecimal string, if it has RGB components.
+     **/
+    pub fn to_hex_string(&self) -> Option<String> {
+        self.to_rgb().map(|(r,g,b)| format!("#{:02x}{:02x}{:02x}", r, g, b))
+    }
+
+}
@@ -16,10 +16,13 @@ limitations under the License.
 
 
 
+use std::fmt;
 use crate::edge::style::GraphvizEdgeStyle;
-use crate::traits::DotTranslatable;
+use crate::item::cluster::{cluster_name, write_cluster_name};
+use crate::traits::{DotTranslatable, RenameableWith};
 
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GraphVizEdge {
     pub origin_node_id : String,
     pub origin_cluster : Option<String>,
@@ -42,14 +45,41 @@ impl GraphVizEdge {
             style}
     }
 
+    /// the DOT attributes of the edge, including its "ltail" and "lhead"
+    pub fn dot_attributes(&self) -> Vec<String> {
+        let mut style : Vec<String> = self.style.iter().map(
+            |item| item.to_dot_string()).collect();
+        match &self.origin_cluster {
+            None => {},
+            Some(cluster_id) => {
+                style.push(format!("ltail={}",cluster_name(cluster_id)));
+            }
+        }
+        match &self.target_cluster {
+            None => {},
+            Some(cluster_id) => {
+                style.push(format!("lhead={}",cluster_name(cluster_id)));
+            }
+        }
+        style
+    }
+
     pub fn rename_with_prefix(&self, prefix: &str) -> Self {
-        let new_origin_id = format!("{}{}",prefix,self.origin_node_id);
+        self.rename_with(&|id| format!("{}{}",prefix,id))
+    }
+}
+
+impl RenameableWith for GraphVizEdge {
+    fn rename_with_maps(&self,
+                        node_map : &dyn Fn(&str) -> String,
+                        cluster_map : &dyn Fn(&str) -> String) -> Self {
+        let new_origin_id = node_map(&self.origin_node_id);
         let new_origin_cluster = self.origin_cluster.as_ref()
-            .map(|cluster_id| format!("{}{}",prefix,cluster_id));
+            .map(|cluster_id| cluster_map(cluster_id));
         // ***
-        let new_target_id = format!("{}{}",prefix,self.target_node_id);
+        let new_target_id = node_map(&self.target_node_id);
         let new_target_cluster = self.target_cluster.as_ref()
-            .map(|cluster_id| format!("{}{}",prefix,cluster_id));
+            .map(|cluster_id| cluster_map(cluster_id));
         // ***
         GraphVizEdge::new(new_origin_id,
                                  new_origin_cluster,
@@ -60,27 +90,29 @@ impl GraphVizEdge {
 }
 
 impl DotTranslatable for GraphVizEdge {
-    fn to_dot_string(&self) -> String {
-        let mut style : Vec<String> = self.style.iter().map(
-            |item| item.to_dot_string()).collect();
-        match &self.origin_cluster {
-            None => {},
-            Some(cluster_id) => {
-                style.push(format!("ltail=cluster_{}",cluster_id));
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
+        write!(writer, "{}->{}", self.origin_node_id, self.target_node_id)?;
+        let has_attributes = !self.style.is_empty()
+            || self.origin_cluster.is_some()
+            || self.target_cluster.is_some();
+        if has_attributes {
+            let mut separator = " [";
+            for item in &self.style {
+                writer.write_str(separator)?;
+                item.write_dot_fmt(writer)?;
+                separator = ",";
             }
-        }
-        match &self.target_cluster {
-            None => {},
-            Some(cluster_id) => {
-                style.push(format!("lhead=cluster_{}",cluster_id));
+            if let Some(cluster_id) = &self.origin_cluster {
+                write!(writer, "{}ltail=", separator)?;
+                write_cluster_name(writer,cluster_id)?;
+                separator = ",";
             }
+            if let Some(cluster_id) = &self.target_cluster {
+                write!(writer, "{}lhead=", separator)?;
+                write_cluster_name(writer,cluster_id)?;
+            }
+            writer.write_char(']')?;
         }
-
-        // ***
-        if style.is_empty() {
-            format!("{}->{};", self.origin_node_id, self.target_node_id)
-        } else {
-            format!("{}->{} [{}];", self.origin_node_id, self.target_node_id, style.join(","))
-        }
+        writer.write_char(';')
     }
 }
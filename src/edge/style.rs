@@ -14,27 +14,32 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use strum_macros::IntoStaticStr;
+use std::fmt;
+use strum_macros::{EnumString, IntoStaticStr};
 
 use crate::colors::GraphvizColor;
-use crate::traits::DotTranslatable;
+use crate::traits::{write_lowercase, DotTranslatable};
 
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GvArrowHeadFill {
     Open,
     Filled,
 }
 
 impl DotTranslatable for GvArrowHeadFill {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         match *self {
-            GvArrowHeadFill::Open => "o".to_string(),
-            GvArrowHeadFill::Filled => "".to_string(),
+            GvArrowHeadFill::Open => writer.write_str("o"),
+            GvArrowHeadFill::Filled => Ok(()),
         }
     }
 }
 
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GvArrowHeadSide {
     Left,
     Right,
@@ -42,18 +47,21 @@ pub enum GvArrowHeadSide {
 }
 
 impl DotTranslatable for GvArrowHeadSide {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         match *self {
-            GvArrowHeadSide::Left  => "l".to_string(),
-            GvArrowHeadSide::Right => "r".to_string(),
-            GvArrowHeadSide::Both  => "".to_string(),
+            GvArrowHeadSide::Left  => writer.write_str("l"),
+            GvArrowHeadSide::Right => writer.write_str("r"),
+            GvArrowHeadSide::Both  => Ok(()),
         }
     }
 }
 
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GvArrowHeadStyle {
     /// No arrow will be displayed
+    #[cfg_attr(feature = "serde", serde(rename = "none"))]
     NoArrow,
     /// Arrow ending in a triangle
     Normal(GvArrowHeadFill, GvArrowHeadSide),
@@ -79,59 +87,48 @@ pub enum GvArrowHeadStyle {
 
 impl DotTranslatable for GvArrowHeadStyle {
 
-    fn to_dot_string(&self) -> String {
-        let mut res = String::new();
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
+        // sides are only written when not "Both" which is rendered as the empty string
         match self {
             GvArrowHeadStyle::Box(fill, side)
             | GvArrowHeadStyle::ICurve(fill, side)
             | GvArrowHeadStyle::Diamond(fill, side)
             | GvArrowHeadStyle::Inv(fill, side)
             | GvArrowHeadStyle::Normal(fill, side)=> {
-                res.push_str(&fill.to_dot_string());
-                match side {
-                    GvArrowHeadSide::Left
-                    | GvArrowHeadSide::Right => {
-                        res.push_str(&side.to_dot_string())
-                    },
-                    GvArrowHeadSide::Both => {},
-                };
+                fill.write_dot_fmt(writer)?;
+                side.write_dot_fmt(writer)?;
             },
             GvArrowHeadStyle::Dot(fill) => {
-                res.push_str(&fill.to_dot_string())
+                fill.write_dot_fmt(writer)?;
             },
             GvArrowHeadStyle::Crow(side)
             | GvArrowHeadStyle::Curve(side)
             | GvArrowHeadStyle::Tee(side)
             | GvArrowHeadStyle::Vee(side) => {
-                match side {
-                    GvArrowHeadSide::Left
-                    | GvArrowHeadSide::Right => {
-                        res.push_str(&side.to_dot_string())
-                    },
-                    GvArrowHeadSide::Both => {},
-                }
+                side.write_dot_fmt(writer)?;
             }
             GvArrowHeadStyle::NoArrow => {},
         };
         match self {
-            GvArrowHeadStyle::NoArrow         => res.push_str("none"),
-            GvArrowHeadStyle::Normal(_, _)    => res.push_str("normal"),
-            GvArrowHeadStyle::Box(_, _)       => res.push_str("box"),
-            GvArrowHeadStyle::Crow(_)         => res.push_str("crow"),
-            GvArrowHeadStyle::Curve(_)        => res.push_str("curve"),
-            GvArrowHeadStyle::ICurve(_, _)    => res.push_str("icurve"),
-            GvArrowHeadStyle::Diamond(_, _)   => res.push_str("diamond"),
-            GvArrowHeadStyle::Dot(_)          => res.push_str("dot"),
-            GvArrowHeadStyle::Inv(_, _)       => res.push_str("inv"),
-            GvArrowHeadStyle::Tee(_)          => res.push_str("tee"),
-            GvArrowHeadStyle::Vee(_)          => res.push_str("vee"),
-        };
-        // ***
-        res
+            GvArrowHeadStyle::NoArrow         => writer.write_str("none"),
+            GvArrowHeadStyle::Normal(_, _)    => writer.write_str("normal"),
+            GvArrowHeadStyle::Box(_, _)       => writer.write_str("box"),
+            GvArrowHeadStyle::Crow(_)         => writer.write_str("crow"),
+            GvArrowHeadStyle::Curve(_)        => writer.write_str("curve"),
+            GvArrowHeadStyle::ICurve(_, _)    => writer.write_str("icurve"),
+            GvArrowHeadStyle::Diamond(_, _)   => writer.write_str("diamond"),
+            GvArrowHeadStyle::Dot(_)          => writer.write_str("dot"),
+            GvArrowHeadStyle::Inv(_, _)       => writer.write_str("inv"),
+            GvArrowHeadStyle::Tee(_)          => writer.write_str("tee"),
+            GvArrowHeadStyle::Vee(_)          => writer.write_str("vee"),
+        }
     }
 }
 
-#[derive(IntoStaticStr,Eq,PartialEq,Clone)]
+#[derive(IntoStaticStr,EnumString,Eq,PartialEq,Clone)]
+#[strum(ascii_case_insensitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GvEdgeLineStyle {
     Solid,
     Dashed,
@@ -141,9 +138,9 @@ pub enum GvEdgeLineStyle {
 }
 
 impl DotTranslatable for GvEdgeLineStyle {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         let as_static_str : &'static str = self.into();
-        as_static_str.to_string().to_lowercase()
+        write_lowercase(writer, as_static_str)
     }
 }
 
@@ -154,49 +151,65 @@ The particular cases of LHead and LTail are not handled here:
 indeed, if used, they refer to clusters ids so that the arrow is not drawn
 between the origin and target nodes but rather between some clusters that may contain them.
 So that we can rename those clusters this is not handled here.
+It is non exhaustive : matches on it need a wildcard arm, so that later versions can support more DOT attributes.
  **/
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[non_exhaustive]
 pub enum GraphvizEdgeStyleItem {
+    #[cfg_attr(feature = "serde", serde(rename = "style"))]
     LineStyle(GvEdgeLineStyle),
     Label(String),
+    #[cfg_attr(feature = "serde", serde(rename = "arrowhead"))]
     Head(GvArrowHeadStyle),
+    #[cfg_attr(feature = "serde", serde(rename = "arrowtail"))]
     Tail(GvArrowHeadStyle),
     Color(GraphvizColor),
     FontColor(GraphvizColor),
     ArrowSize(u32),
     FontSize(u32),
+    PenWidth(u32),
     Constraint(bool)
 }
 
 impl DotTranslatable for GraphvizEdgeStyleItem {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         match self {
             GraphvizEdgeStyleItem::LineStyle(ref line_style) => {
-                format!("style={:}",line_style.to_dot_string())
+                writer.write_str("style=")?;
+                line_style.write_dot_fmt(writer)
             },
             GraphvizEdgeStyleItem::Label(ref label) => {
-                format!("label=\"{:}\"",label)
+                write!(writer, "label=\"{:}\"",label)
             },
             GraphvizEdgeStyleItem::Head(arrow_head_style) => {
-                format!("arrowhead={:}",arrow_head_style.to_dot_string())
+                writer.write_str("arrowhead=")?;
+                arrow_head_style.write_dot_fmt(writer)
             },
             GraphvizEdgeStyleItem::Tail(arrow_head_style) => {
-                format!("arrowtail={:}",arrow_head_style.to_dot_string())
+                writer.write_str("arrowtail=")?;
+                arrow_head_style.write_dot_fmt(writer)
             },
             GraphvizEdgeStyleItem::Color(graphviz_color) => {
-                format!("color={:}",graphviz_color.to_dot_string())
+                writer.write_str("color=")?;
+                graphviz_color.write_dot_fmt(writer)
             },
             GraphvizEdgeStyleItem::FontColor(graphviz_color) => {
-                format!("fontcolor={:}",graphviz_color.to_dot_string())
+                writer.write_str("fontcolor=")?;
+                graphviz_color.write_dot_fmt(writer)
             },
             GraphvizEdgeStyleItem::ArrowSize(size) => {
-                format!("arrowsize={:}",size)
+                write!(writer, "arrowsize={:}",size)
             },
             GraphvizEdgeStyleItem::FontSize(size) => {
-                format!("fontsize={:}",size)
+                write!(writer, "fontsize={:}",size)
+            },
+            GraphvizEdgeStyleItem::PenWidth(width) => {
+                write!(writer, "penwidth={:}",width)
             },
             GraphvizEdgeStyleItem::Constraint(constraint) => {
-                format!("constraint={}",constraint)
+                write!(writer, "constraint={}",constraint)
             }
         }
     }
@@ -205,3 +218,27 @@ impl DotTranslatable for GraphvizEdgeStyleItem {
 pub type GraphvizEdgeStyle = Vec<GraphvizEdgeStyleItem>;
 
 
+
+
+
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn serialized_with_dot_names() {
+        let style = vec![
+            GraphvizEdgeStyleItem::LineStyle(GvEdgeLineStyle::Dashed),
+            GraphvizEdgeStyleItem::Head(GvArrowHeadStyle::NoArrow),
+            GraphvizEdgeStyleItem::Tail(GvArrowHeadStyle::Normal(GvArrowHeadFill::Open,GvArrowHeadSide::Left)),
+            GraphvizEdgeStyleItem::Color(GraphvizColor::lightblue)
+        ];
+        let json = serde_json::to_string(&style).unwrap();
+        assert_eq!(json,"[{\"style\":\"dashed\"},{\"arrowhead\":\"none\"},{\"arrowtail\":{\"normal\":[\"open\",\"left\"]}},{\"color\":\"lightblue\"}]");
+        let read : GraphvizEdgeStyle = serde_json::from_str(&json).unwrap();
+        assert!(read == style);
+    }
+
+}
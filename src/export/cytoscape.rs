@@ -0,0 +1,452 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::{GraphvizEdgeStyleItem, GvArrowHeadFill, GvArrowHeadStyle, GvEdgeLineStyle};
+use crate::export::{cluster_node_id, escaped_node_id, json_string, GvExport, GvExportElement, GvExportWarning};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::style::{GraphvizNodeStyle, GraphvizNodeStyleItem, GvNodeShape, GvNodeStyleKind};
+use crate::traits::DotTranslatable;
+
+
+fn css_color(color : &GraphvizColor) -> String {
+    color.to_hex_string().unwrap_or_else(|| "transparent".to_string())
+}
+
+/// Cytoscape.js node shape closest to a Graphviz node shape
+fn cytoscape_shape(shape : &GvNodeShape) -> Option<&'static str> {
+    match shape {
+        GvNodeShape::Ellipse
+        | GvNodeShape::Circle
+        | GvNodeShape::DoubleCircle
+        | GvNodeShape::Point => Some("ellipse"),
+        GvNodeShape::Triangle => Some("triangle"),
+        GvNodeShape::Diamond => Some("diamond"),
+        GvNodeShape::Parallelogram => Some("rhomboid"),
+        GvNodeShape::Pentagon => Some("pentagon"),
+        GvNodeShape::Hexagon => Some("hexagon"),
+        GvNodeShape::Septagon => Some("heptagon"),
+        GvNodeShape::Octagon => Some("octagon"),
+        GvNodeShape::Rectangle
+        | GvNodeShape::Square
+        | GvNodeShape::PlainText => Some("rectangle"),
+        GvNodeShape::Star => Some("star"),
+        _ => None
+    }
+}
+
+/// Cytoscape.js arrow shape closest to a Graphviz arrowhead
+fn cytoscape_arrow(arrow : &GvArrowHeadStyle) -> Option<&'static str> {
+    match arrow {
+        GvArrowHeadStyle::NoArrow => Some("none"),
+        GvArrowHeadStyle::Normal(_,_) => Some("triangle"),
+        GvArrowHeadStyle::Box(_,_) => Some("square"),
+        GvArrowHeadStyle::Diamond(_,_) => Some("diamond"),
+        GvArrowHeadStyle::Dot(_) => Some("circle"),
+        GvArrowHeadStyle::Tee(_) => Some("tee"),
+        GvArrowHeadStyle::Vee(_) => Some("vee"),
+        _ => None
+    }
+}
+
+struct CytoscapeWriter {
+    /// one JSON object per node or compound node
+    nodes : Vec<String>,
+    edges : Vec<String>,
+    /// style properties of each generated class
+    classes : Vec<Vec<(&'static str,String)>>,
+    warnings : Vec<GvExportWarning>
+}
+
+impl CytoscapeWriter {
+
+    fn warn(&mut self, element : &GvExportElement, feature : String) {
+        self.warnings.push(GvExportWarning::new(element.clone(),feature));
+    }
+
+    /// name of the class holding these style properties, if any
+    fn class_of(&mut self, properties : Vec<(&'static str,String)>) -> Option<String> {
+        if properties.is_empty() {
+            return None;
+        }
+        let index = match self.classes.iter().position(|c| *c == properties) {
+            Some(index) => index,
+            None => {
+                self.classes.push(properties);
+                self.classes.len() - 1
+            }
+        };
+        Some(format!("gvstyle{}", index))
+    }
+
+    fn node_properties(&mut self,
+                       element : &GvExportElement,
+                       style : &GraphvizNodeStyle) -> (Option<String>,Vec<(&'static str,String)>) {
+        let mut label = None;
+        let mut properties = vec![];
+        for item in style {
+            match item {
+                GraphvizNodeStyleItem::Label(lab) => {
+                    label = Some(lab.clone());
+                },
+                GraphvizNodeStyleItem::Shape(shape) => {
+                    match cytoscape_shape(shape) {
+                        Some(sh) => {
+                            properties.push(("shape",sh.to_string()));
+                        },
+                        None => {
+                            properties.push(("shape","rectangle".to_string()));
+                            self.warn(element,item.to_dot_string());
+                        }
+                    }
+                    match shape {
+                        GvNodeShape::DoubleCircle => {
+                            properties.push(("border-style","double".to_string()));
+                        },
+                        GvNodeShape::PlainText => {
+                            properties.push(("background-opacity","0".to_string()));
+                            properties.push(("border-width","0".to_string()));
+                        },
+                        _ => {}
+                    }
+                },
+                GraphvizNodeStyleItem::Style(kinds) => {
+                    for kind in kinds {
+                        match kind {
+                            GvNodeStyleKind::Solid
+                            | GvNodeStyleKind::Filled => {},
+                            GvNodeStyleKind::Dashed => {
+                                properties.push(("border-style","dashed".to_string()));
+                            },
+                            GvNodeStyleKind::Dotted => {
+                                properties.push(("border-style","dotted".to_string()));
+                            },
+                            GvNodeStyleKind::Bold => {
+                                properties.push(("border-width","2".to_string()));
+                            },
+                            GvNodeStyleKind::Rounded => {
+                                properties.push(("shape","round-rectangle".to_string()));
+                            },
+                            GvNodeStyleKind::Invis => {
+                                properties.push(("visibility","hidden".to_string()));
+                            },
+                            _ => {
+                                self.warn(element,format!("style={}",kind.to_dot_string()));
+                            }
+                        }
+                    }
+                },
+                GraphvizNodeStyleItem::Image(path) => {
+                    properties.push(("background-image",path.clone()));
+                    properties.push(("background-fit","contain".to_string()));
+                },
+                GraphvizNodeStyleItem::Color(color) => {
+                    properties.push(("border-color",css_color(color)));
+                },
+                GraphvizNodeStyleItem::FillColor(color) => {
+                    properties.push(("background-color",css_color(color)));
+                },
+                GraphvizNodeStyleItem::FontColor(color) => {
+                    properties.push(("color",css_color(color)));
+                },
+                GraphvizNodeStyleItem::FontSize(size) => {
+                    properties.push(("font-size",size.to_string()));
+                },
+                GraphvizNodeStyleItem::FontName(name) => {
+                    properties.push(("font-family",name.clone()));
+                },
+                GraphvizNodeStyleItem::Height(height) => {
+                    // Graphviz sizes are in inches
+                    properties.push(("height",(height * 72).to_string()));
+                },
+                GraphvizNodeStyleItem::Width(width) => {
+                    properties.push(("width",(width * 72).to_string()));
+                },
+                GraphvizNodeStyleItem::Peripheries(2) => {
+                    properties.push(("border-style","double".to_string()));
+                },
+                GraphvizNodeStyleItem::PenWidth(width) => {
+                    properties.push(("border-width",width.to_string()));
+                },
+                GraphvizNodeStyleItem::Peripheries(_) => {
+                    self.warn(element,item.to_dot_string());
+                }
+            }
+        }
+        (label,properties)
+    }
+
+    fn write_element(&mut self, data : Vec<(&str,String)>, class : Option<String>) -> String {
+        let data : Vec<String> = data.iter()
+            .map(|(key,value)| format!("{}:{}", json_string(key), json_string(value))).collect();
+        match class {
+            None => format!("{{\"data\":{{{}}}}}", data.join(",")),
+            Some(class) => format!("{{\"data\":{{{}}},\"classes\":{}}}", data.join(","), json_string(&class))
+        }
+    }
+
+    fn write_items(&mut self, items : &mut dyn Iterator<Item=&GraphVizGraphItem>, parent : Option<&str>) {
+        for item in items {
+            let (element,id,style) = match item {
+                GraphVizGraphItem::Node(node) => {
+                    (GvExportElement::Node(node.id.clone()),escaped_node_id(&node.id),&node.style)
+                },
+                GraphVizGraphItem::Cluster(cluster) => {
+                    (GvExportElement::Cluster(cluster.id.clone()),cluster_node_id(&cluster.id),&cluster.style)
+                }
+            };
+            let (label,properties) = self.node_properties(&element,style);
+            let class = self.class_of(properties);
+            let mut data = vec![("id",id.clone())];
+            if let Some(parent) = parent {
+                data.push(("parent",parent.to_string()));
+            }
+            data.push(("label",label.unwrap_or_else(|| match item {
+                GraphVizGraphItem::Node(node) => node.id.clone(),
+                GraphVizGraphItem::Cluster(cluster) => cluster.id.clone()
+            })));
+            let json = self.write_element(data,class);
+            self.nodes.push(json);
+            if let GraphVizGraphItem::Cluster(cluster) = item {
+                self.write_items(&mut cluster.items.iter().map(|item| item.as_ref()),Some(&id));
+            }
+        }
+    }
+
+    fn write_edge(&mut self, index : usize, edge : &GraphVizEdge) {
+        let element = GvExportElement::Edge(edge.origin_node_id.clone(),edge.target_node_id.clone());
+        let mut label = None;
+        let mut properties = vec![];
+        for item in &edge.style {
+            match item {
+                GraphvizEdgeStyleItem::Label(lab) => {
+                    label = Some(lab.clone());
+                },
+                GraphvizEdgeStyleItem::LineStyle(line) => {
+                    match line {
+                        GvEdgeLineStyle::Solid => {},
+                        GvEdgeLineStyle::Dashed => properties.push(("line-style","dashed".to_string())),
+                        GvEdgeLineStyle::Dotted => properties.push(("line-style","dotted".to_string())),
+                        GvEdgeLineStyle::Bold => properties.push(("width","3".to_string())),
+                        GvEdgeLineStyle::Invis => properties.push(("visibility","hidden".to_string()))
+                    }
+                },
+                GraphvizEdgeStyleItem::Head(arrow) => {
+                    match cytoscape_arrow(arrow) {
+                        Some(shape) => {
+                            properties.push(("target-arrow-shape",shape.to_string()));
+                        },
+                        None => {
+                            self.warn(&element,item.to_dot_string());
+                        }
+                    }
+                    match arrow {
+                        GvArrowHeadStyle::Normal(GvArrowHeadFill::Open,_)
+                        | GvArrowHeadStyle::Box(GvArrowHeadFill::Open,_)
+                        | GvArrowHeadStyle::Diamond(GvArrowHeadFill::Open,_)
+                        | GvArrowHeadStyle::Dot(GvArrowHeadFill::Open) => {
+                            properties.push(("target-arrow-fill","hollow".to_string()));
+                        },
+                        _ => {}
+                    }
+                },
+                GraphvizEdgeStyleItem::Color(color) => {
+                    properties.push(("line-color",css_color(color)));
+                    properties.push(("target-arrow-color",css_color(color)));
+                },
+                GraphvizEdgeStyleItem::FontColor(color) => {
+                    properties.push(("color",css_color(color)));
+                },
+                GraphvizEdgeStyleItem::ArrowSize(size) => {
+                    properties.push(("arrow-scale",size.to_string()));
+                },
+                GraphvizEdgeStyleItem::PenWidth(width) => {
+                    properties.push(("width",width.to_string()));
+                },
+                GraphvizEdgeStyleItem::FontSize(size) => {
+                    properties.push(("font-size",size.to_string()));
+                },
+                GraphvizEdgeStyleItem::Tail(_)
+                | GraphvizEdgeStyleItem::Constraint(_) => {
+                    self.warn(&element,item.to_dot_string());
+                }
+            }
+        }
+        let class = self.class_of(properties);
+        // edges using lhead/ltail are attached to the compound nodes of the clusters
+        let source = match &edge.origin_cluster {
+            None => escaped_node_id(&edge.origin_node_id),
+            Some(cluster_id) => cluster_node_id(cluster_id)
+        };
+        let target = match &edge.target_cluster {
+            None => escaped_node_id(&edge.target_node_id),
+            Some(cluster_id) => cluster_node_id(cluster_id)
+        };
+        let mut data = vec![("id",format!("edge_{}", index)),("source",source),("target",target)];
+        if let Some(label) = label {
+            data.push(("label",label));
+        }
+        let json = self.write_element(data,class);
+        self.edges.push(json);
+    }
+
+}
+
+fn style_rule(selector : &str, properties : &[(&str,String)]) -> String {
+    let properties : Vec<String> = properties.iter()
+        .map(|(key,value)| format!("{}:{}", json_string(key), json_string(value))).collect();
+    format!("{{\"selector\":{},\"style\":{{{}}}}}", json_string(selector), properties.join(","))
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Translates the graph into a Cytoscape.js JSON object with the "elements" and the "style" to
+     give to the "cytoscape" constructor.
+     Clusters become compound nodes (with "cluster_<id>" ids) and the style of each element is expressed as a class of the style sheet.
+     Edges have "edge_<index>" ids and the ids of nodes starting with "cluster_", "node_" or "edge_" are escaped with "node_",
+     as nodes and edges share the same ids in Cytoscape.js.
+     Every style item that Cytoscape.js cannot represent is listed in the warnings of the result.
+     **/
+    pub fn to_cytoscape(&self) -> GvExport {
+        let mut writer = CytoscapeWriter{nodes:vec![],edges:vec![],classes:vec![],warnings:vec![]};
+        for item in &self.style {
+            writer.warn(&GvExportElement::Graph,item.to_dot_string());
+        }
+        writer.write_items(&mut self.items.iter(),None);
+        for (index,edge) in self.all_edges().enumerate() {
+            writer.write_edge(index,edge);
+        }
+        // ***
+        let mut rules = vec![
+            style_rule("node",&[("label","data(label)".to_string()),
+                ("shape","ellipse".to_string()),
+                ("text-valign","center".to_string()),
+                ("text-halign","center".to_string())]),
+            style_rule(":parent",&[("shape","rectangle".to_string()),
+                ("text-valign","top".to_string())]),
+            style_rule("edge",&[("label","data(label)".to_string()),
+                ("curve-style","bezier".to_string()),
+                ("target-arrow-shape","triangle".to_string())])
+        ];
+        for (index,properties) in writer.classes.iter().enumerate() {
+            rules.push(style_rule(&format!(".gvstyle{}", index),properties));
+        }
+        let mut source = String::new();
+        source.push_str("{\n  \"elements\": {\n    \"nodes\": [");
+        source.push_str(&writer.nodes.iter().map(|n| format!("\n      {}", n)).collect::<Vec<String>>().join(","));
+        source.push_str("\n    ],\n    \"edges\": [");
+        source.push_str(&writer.edges.iter().map(|e| format!("\n      {}", e)).collect::<Vec<String>>().join(","));
+        source.push_str("\n    ]\n  },\n  \"style\": [");
+        source.push_str(&rules.iter().map(|r| format!("\n    {}", r)).collect::<Vec<String>>().join(","));
+        source.push_str("\n  ]\n}\n");
+        GvExport{source,warnings:writer.warnings}
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::edge::style::GvArrowHeadSide;
+
+    #[test]
+    fn elements_and_style_sheet() {
+        let graph = crate::digraph!{
+            rankdir = LR;
+            a [label = "A", shape = Rectangle, fillcolor = red];
+            subgraph c1 {
+                label = "C1";
+                b [shape = Rectangle, fillcolor = red];
+                c [shape = Star, style = Dashed];
+                b -> c [style = Dotted, color = blue];
+            }
+            a -> b [label = "x", arrowhead = (GvArrowHeadStyle::Normal(GvArrowHeadFill::Open,GvArrowHeadSide::Both))];
+            a -> c [lhead = c1, constraint = false];
+        };
+        let expected = [
+            "{",
+            "  \"elements\": {",
+            "    \"nodes\": [",
+            "      {\"data\":{\"id\":\"a\",\"label\":\"A\"},\"classes\":\"gvstyle0\"},",
+            "      {\"data\":{\"id\":\"cluster_c1\",\"label\":\"C1\"}},",
+            "      {\"data\":{\"id\":\"b\",\"parent\":\"cluster_c1\",\"label\":\"b\"},\"classes\":\"gvstyle0\"},",
+            "      {\"data\":{\"id\":\"c\",\"parent\":\"cluster_c1\",\"label\":\"c\"},\"classes\":\"gvstyle1\"}",
+            "    ],",
+            "    \"edges\": [",
+            "      {\"data\":{\"id\":\"edge_0\",\"source\":\"a\",\"target\":\"b\",\"label\":\"x\"},\"classes\":\"gvstyle2\"},",
+            "      {\"data\":{\"id\":\"edge_1\",\"source\":\"a\",\"target\":\"cluster_c1\"}},",
+            "      {\"data\":{\"id\":\"edge_2\",\"source\":\"b\",\"target\":\"c\"},\"classes\":\"gvstyle3\"}",
+            "    ]",
+            "  },",
+            "  \"style\": [",
+            "    {\"selector\":\"node\",\"style\":{\"label\":\"data(label)\",\"shape\":\"ellipse\",\"text-valign\":\"center\",\"text-halign\":\"center\"}},",
+            "    {\"selector\":\":parent\",\"style\":{\"shape\":\"rectangle\",\"text-valign\":\"top\"}},",
+            "    {\"selector\":\"edge\",\"style\":{\"label\":\"data(label)\",\"curve-style\":\"bezier\",\"target-arrow-shape\":\"triangle\"}},",
+            "    {\"selector\":\".gvstyle0\",\"style\":{\"shape\":\"rectangle\",\"background-color\":\"#ff0000\"}},",
+            "    {\"selector\":\".gvstyle1\",\"style\":{\"shape\":\"star\",\"border-style\":\"dashed\"}},",
+            "    {\"selector\":\".gvstyle2\",\"style\":{\"target-arrow-shape\":\"triangle\",\"target-arrow-fill\":\"hollow\"}},",
+            "    {\"selector\":\".gvstyle3\",\"style\":{\"line-style\":\"dotted\",\"line-color\":\"#0000ff\",\"target-arrow-color\":\"#0000ff\"}}",
+            "  ]",
+            "}",
+            ""
+        ];
+        let export = graph.to_cytoscape();
+        assert_eq!(export.source,expected.join("\n"));
+        let warnings : Vec<String> = export.warnings.iter().map(|warning| warning.to_string()).collect();
+        assert_eq!(warnings,vec!["graph : unsupported rankdir=lr".to_string(),
+                                 "edge a->c : unsupported constraint=false".to_string()]);
+    }
+
+    #[test]
+    fn ids_are_distinct() {
+        let graph = crate::digraph!{
+            e0;
+            edge_0;
+            cluster_c1;
+            subgraph c1 {
+                x;
+            }
+            e0 -> x;
+            edge_0 -> cluster_c1;
+        };
+        let source = graph.to_cytoscape().source;
+        let nodes = [
+            "      {\"data\":{\"id\":\"e0\",\"label\":\"e0\"}},",
+            "      {\"data\":{\"id\":\"node_edge_0\",\"label\":\"edge_0\"}},",
+            "      {\"data\":{\"id\":\"node_cluster_c1\",\"label\":\"cluster_c1\"}},",
+            "      {\"data\":{\"id\":\"cluster_c1\",\"label\":\"c1\"}},",
+            "      {\"data\":{\"id\":\"x\",\"parent\":\"cluster_c1\",\"label\":\"x\"}}",
+            "    ],",
+            "    \"edges\": [",
+            "      {\"data\":{\"id\":\"edge_0\",\"source\":\"e0\",\"target\":\"x\"}},",
+            "      {\"data\":{\"id\":\"edge_1\",\"source\":\"node_edge_0\",\"target\":\"node_cluster_c1\"}}",
+            "    ]"
+        ];
+        assert!(source.contains(&nodes.join("\n")));
+    }
+
+}
@@ -0,0 +1,474 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use std::collections::HashMap;
+
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::{GraphvizEdgeStyleItem, GvArrowHeadFill, GvArrowHeadStyle, GvEdgeLineStyle};
+use crate::export::{cluster_node_id, escaped_node_id, GvExport, GvExportElement, GvExportWarning};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::style::{GraphvizGraphStyleItem, GvGraphRankDir};
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::{GraphvizNodeStyleItem, GvNodeShape, GvNodeStyleKind};
+use crate::traits::DotTranslatable;
+
+
+/// reserved keys of D2, which would be read as attributes of the enclosing container if they were not quoted
+const D2_KEYWORDS : [&str;20] = ["_","label","shape","style","direction","icon","width","height","near","link",
+    "tooltip","class","classes","constraint","top","left","vars","layers","scenarios","steps"];
+
+/// D2 keys that are not plain identifiers or that are reserved must be quoted
+fn d2_key(id : &str) -> String {
+    let plain = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain && !D2_KEYWORDS.contains(&id.to_ascii_lowercase().as_str()) {
+        id.to_string()
+    } else {
+        format!("\"{}\"", id.replace('\\',"\\\\").replace('"',"\\\""))
+    }
+}
+
+/// keys of nodes whose id starts with a prefix of generated keys are escaped with "node_"
+fn d2_node_key(id : &str) -> String {
+    d2_key(&escaped_node_id(id))
+}
+
+fn d2_cluster_key(id : &str) -> String {
+    d2_key(&cluster_node_id(id))
+}
+
+/// the line breaks of Graphviz ("\n", "\l" and "\r") become those of D2, other backslashes being escaped
+fn d2_text(text : &str) -> String {
+    let mut res = String::from("\"");
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if chars.next_if(|next| matches!(next,'n' | 'l' | 'r')).is_some() {
+                    res.push_str("\\n");
+                } else {
+                    res.push_str("\\\\");
+                }
+            },
+            '"' => res.push_str("\\\""),
+            '\n' => res.push_str("\\n"),
+            _ => res.push(c)
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn d2_color(color : &GraphvizColor) -> String {
+    format!("\"{}\"", color.to_hex_string().unwrap_or_else(|| "transparent".to_string()))
+}
+
+/// D2 shape closest to a Graphviz node shape
+fn d2_shape(shape : &GvNodeShape) -> Option<&'static str> {
+    match shape {
+        GvNodeShape::Ellipse => Some("oval"),
+        GvNodeShape::Circle
+        | GvNodeShape::DoubleCircle
+        | GvNodeShape::Point => Some("circle"),
+        GvNodeShape::Diamond => Some("diamond"),
+        GvNodeShape::Hexagon => Some("hexagon"),
+        GvNodeShape::Parallelogram => Some("parallelogram"),
+        GvNodeShape::Rectangle => Some("rectangle"),
+        GvNodeShape::Square => Some("square"),
+        GvNodeShape::PlainText => Some("text"),
+        _ => None
+    }
+}
+
+/// D2 arrowhead shape closest to a Graphviz arrowhead
+fn d2_arrowhead(arrow : &GvArrowHeadStyle) -> Option<(&'static str,bool)> {
+    match arrow {
+        GvArrowHeadStyle::Normal(fill,_) => Some(("triangle",*fill == GvArrowHeadFill::Filled)),
+        GvArrowHeadStyle::Vee(_) => Some(("arrow",true)),
+        GvArrowHeadStyle::Diamond(fill,_) => Some(("diamond",*fill == GvArrowHeadFill::Filled)),
+        GvArrowHeadStyle::Dot(fill) => Some(("circle",*fill == GvArrowHeadFill::Filled)),
+        GvArrowHeadStyle::Box(fill,_) => Some(("box",*fill == GvArrowHeadFill::Filled)),
+        GvArrowHeadStyle::Crow(_) => Some(("cf-many",true)),
+        _ => None
+    }
+}
+
+struct D2Writer {
+    source : String,
+    warnings : Vec<GvExportWarning>,
+    /// D2 path of each node
+    node_paths : HashMap<String,String>,
+    /// D2 path of each cluster
+    cluster_paths : HashMap<String,String>
+}
+
+impl D2Writer {
+
+    fn warn(&mut self, element : GvExportElement, feature : String) {
+        self.warnings.push(GvExportWarning::new(element,feature));
+    }
+
+    fn write_items(&mut self,
+                   items : &mut dyn Iterator<Item=&GraphVizGraphItem>,
+                   parent_path : &str,
+                   depth : usize) {
+        for item in items {
+            match item {
+                GraphVizGraphItem::Node(node) => {
+                    self.write_node(node,parent_path,depth);
+                },
+                GraphVizGraphItem::Cluster(cluster) => {
+                    self.write_cluster(cluster,parent_path,depth);
+                }
+            }
+        }
+    }
+
+    /// translates the style shared by nodes and clusters into D2 attributes
+    fn colors_and_lines(&mut self,
+                        element : &GvExportElement,
+                        item : &GraphvizNodeStyleItem,
+                        attributes : &mut Vec<String>) {
+        match item {
+            GraphvizNodeStyleItem::FillColor(color) => {
+                attributes.push(format!("style.fill: {}",d2_color(color)));
+            },
+            GraphvizNodeStyleItem::Color(color) => {
+                attributes.push(format!("style.stroke: {}",d2_color(color)));
+            },
+            GraphvizNodeStyleItem::FontColor(color) => {
+                attributes.push(format!("style.font-color: {}",d2_color(color)));
+            },
+            GraphvizNodeStyleItem::FontSize(size) => {
+                attributes.push(format!("style.font-size: {}",size));
+            },
+            GraphvizNodeStyleItem::PenWidth(width) => {
+                attributes.push(format!("style.stroke-width: {}",width));
+            },
+            GraphvizNodeStyleItem::Style(kinds) => {
+                for kind in kinds {
+                    match kind {
+                        GvNodeStyleKind::Solid
+                        | GvNodeStyleKind::Filled => {},
+                        GvNodeStyleKind::Dashed => {
+                            attributes.push("style.stroke-dash: 5".to_string());
+                        },
+                        GvNodeStyleKind::Dotted => {
+                            attributes.push("style.stroke-dash: 2".to_string());
+                        },
+                        GvNodeStyleKind::Bold => {
+                            attributes.push("style.stroke-width: 3".to_string());
+                        },
+                        GvNodeStyleKind::Rounded => {
+                            attributes.push("style.border-radius: 8".to_string());
+                        },
+                        GvNodeStyleKind::Invis => {
+                            attributes.push("style.opacity: 0".to_string());
+                        },
+                        _ => {
+                            self.warn(element.clone(),format!("style={}",kind.to_dot_string()));
+                        }
+                    }
+                }
+            },
+            _ => {
+                self.warn(element.clone(),item.to_dot_string());
+            }
+        }
+    }
+
+    fn write_block(&mut self, key : &str, label : &str, attributes : &[String], depth : usize) {
+        let indent = "  ".repeat(depth);
+        if attributes.is_empty() {
+            self.source.push_str(&format!("{}{}: {}\n", indent, key, d2_text(label)));
+        } else {
+            self.source.push_str(&format!("{}{}: {} {{\n", indent, key, d2_text(label)));
+            for attribute in attributes {
+                self.source.push_str(&format!("{}  {}\n", indent, attribute));
+            }
+            self.source.push_str(&format!("{}}}\n", indent));
+        }
+    }
+
+    fn write_node(&mut self, node : &GraphVizNode, parent_path : &str, depth : usize) {
+        let element = GvExportElement::Node(node.id.clone());
+        let mut label = node.id.clone();
+        // nodes are ellipses by default in Graphviz
+        let mut attributes : Vec<String> = vec![];
+        let mut shape = "oval";
+        for item in &node.style {
+            match item {
+                GraphvizNodeStyleItem::Label(lab) => {
+                    label = lab.clone();
+                },
+                GraphvizNodeStyleItem::Shape(node_shape) => {
+                    match d2_shape(node_shape) {
+                        Some(sh) => {
+                            shape = sh;
+                        },
+                        None => {
+                            shape = "rectangle";
+                            self.warn(element.clone(),item.to_dot_string());
+                        }
+                    }
+                    if *node_shape == GvNodeShape::DoubleCircle {
+                        attributes.push("style.double-border: true".to_string());
+                    }
+                },
+                GraphvizNodeStyleItem::Image(path) => {
+                    attributes.push(format!("icon: {}",d2_text(path)));
+                },
+                GraphvizNodeStyleItem::Height(height) => {
+                    // Graphviz sizes are in inches
+                    attributes.push(format!("height: {}",height * 72));
+                },
+                GraphvizNodeStyleItem::Width(width) => {
+                    attributes.push(format!("width: {}",width * 72));
+                },
+                GraphvizNodeStyleItem::Peripheries(2) => {
+                    attributes.push("style.double-border: true".to_string());
+                },
+                _ => {
+                    self.colors_and_lines(&element,item,&mut attributes);
+                }
+            }
+        }
+        attributes.insert(0,format!("shape: {}",shape));
+        let key = d2_node_key(&node.id);
+        self.node_paths.insert(node.id.clone(),format!("{}{}", parent_path, key));
+        self.write_block(&key,&label,&attributes,depth);
+    }
+
+    fn write_cluster(&mut self, cluster : &GraphVizCluster, parent_path : &str, depth : usize) {
+        let element = GvExportElement::Cluster(cluster.id.clone());
+        let mut title = cluster.id.clone();
+        let mut attributes : Vec<String> = vec![];
+        for item in &cluster.style {
+            match item {
+                GraphvizNodeStyleItem::Label(lab) => {
+                    title = lab.clone();
+                },
+                _ => {
+                    self.colors_and_lines(&element,item,&mut attributes);
+                }
+            }
+        }
+        let key = d2_cluster_key(&cluster.id);
+        let path = format!("{}{}", parent_path, key);
+        self.cluster_paths.insert(cluster.id.clone(),path.clone());
+        let indent = "  ".repeat(depth);
+        self.source.push_str(&format!("{}{}: {} {{\n", indent, key, d2_text(&title)));
+        for attribute in &attributes {
+            self.source.push_str(&format!("{}  {}\n", indent, attribute));
+        }
+        self.write_items(&mut cluster.items.iter().map(|item| item.as_ref()),&format!("{}.",path),depth + 1);
+        self.source.push_str(&format!("{}}}\n", indent));
+    }
+
+    fn write_edge(&mut self, edge : &GraphVizEdge) {
+        let element = GvExportElement::Edge(edge.origin_node_id.clone(),edge.target_node_id.clone());
+        let mut label : Option<&String> = None;
+        let mut attributes : Vec<String> = vec![];
+        let mut connection = "->";
+        for item in &edge.style {
+            match item {
+                GraphvizEdgeStyleItem::Label(lab) => {
+                    label = Some(lab);
+                },
+                GraphvizEdgeStyleItem::LineStyle(line) => {
+                    match line {
+                        GvEdgeLineStyle::Solid => {},
+                        GvEdgeLineStyle::Dashed => attributes.push("style.stroke-dash: 5".to_string()),
+                        GvEdgeLineStyle::Dotted => attributes.push("style.stroke-dash: 2".to_string()),
+                        GvEdgeLineStyle::Bold => attributes.push("style.stroke-width: 3".to_string()),
+                        GvEdgeLineStyle::Invis => attributes.push("style.opacity: 0".to_string())
+                    }
+                },
+                GraphvizEdgeStyleItem::Color(color) => {
+                    attributes.push(format!("style.stroke: {}",d2_color(color)));
+                },
+                GraphvizEdgeStyleItem::FontColor(color) => {
+                    attributes.push(format!("style.font-color: {}",d2_color(color)));
+                },
+                GraphvizEdgeStyleItem::FontSize(size) => {
+                    attributes.push(format!("style.font-size: {}",size));
+                },
+                GraphvizEdgeStyleItem::PenWidth(width) => {
+                    attributes.push(format!("style.stroke-width: {}",width));
+                },
+                GraphvizEdgeStyleItem::Head(GvArrowHeadStyle::NoArrow) => {
+                    connection = "--";
+                },
+                GraphvizEdgeStyleItem::Head(arrow) => {
+                    match d2_arrowhead(arrow) {
+                        Some((shape,filled)) => {
+                            attributes.push(format!("target-arrowhead.shape: {}",shape));
+                            if !filled {
+                                attributes.push("target-arrowhead.style.filled: false".to_string());
+                            }
+                        },
+                        None => {
+                            self.warn(element.clone(),item.to_dot_string());
+                        }
+                    }
+                },
+                GraphvizEdgeStyleItem::Tail(_)
+                | GraphvizEdgeStyleItem::ArrowSize(_)
+                | GraphvizEdgeStyleItem::Constraint(_) => {
+                    self.warn(element.clone(),item.to_dot_string());
+                }
+            }
+        }
+        let origin = match &edge.origin_cluster {
+            None => self.node_paths.get(&edge.origin_node_id).cloned()
+                .unwrap_or_else(|| d2_node_key(&edge.origin_node_id)),
+            Some(cluster_id) => self.cluster_paths.get(cluster_id).cloned()
+                .unwrap_or_else(|| d2_cluster_key(cluster_id))
+        };
+        let target = match &edge.target_cluster {
+            None => self.node_paths.get(&edge.target_node_id).cloned()
+                .unwrap_or_else(|| d2_node_key(&edge.target_node_id)),
+            Some(cluster_id) => self.cluster_paths.get(cluster_id).cloned()
+                .unwrap_or_else(|| d2_cluster_key(cluster_id))
+        };
+        let connection = format!("{} {} {}", origin, connection, target);
+        match label {
+            None => {
+                if attributes.is_empty() {
+                    self.source.push_str(&format!("{}\n", connection));
+                } else {
+                    self.source.push_str(&format!("{}: {{\n", connection));
+                    for attribute in &attributes {
+                        self.source.push_str(&format!("  {}\n", attribute));
+                    }
+                    self.source.push_str("}\n");
+                }
+            },
+            Some(lab) => {
+                self.write_block(&connection,lab,&attributes,0);
+            }
+        }
+    }
+
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Translates the graph into D2 source.
+     Clusters become containers and nodes are referred to by their full path in edges.
+     Every style item that D2 cannot represent is listed in the warnings of the result.
+     **/
+    pub fn to_d2(&self) -> GvExport {
+        let mut writer = D2Writer{source:String::new(),warnings:vec![],node_paths:HashMap::new(),cluster_paths:HashMap::new()};
+        for item in &self.style {
+            match item {
+                GraphvizGraphStyleItem::Rankdir(rankdir) => {
+                    let direction = match rankdir {
+                        GvGraphRankDir::TB => "down",
+                        GvGraphRankDir::BT => "up",
+                        GvGraphRankDir::LR => "right",
+                        GvGraphRankDir::RL => "left"
+                    };
+                    writer.source.push_str(&format!("direction: {}\n", direction));
+                },
+                _ => {
+                    writer.warn(GvExportElement::Graph,item.to_dot_string());
+                }
+            }
+        }
+        writer.write_items(&mut self.items.iter(),"",0);
+        for edge in self.all_edges() {
+            writer.write_edge(edge);
+        }
+        GvExport{source:writer.source,warnings:writer.warnings}
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn nodes_and_clusters_do_not_collide() {
+        let graph = crate::digraph!{
+            cluster_x;
+            node_y;
+            subgraph x {
+                z;
+            }
+            cluster_x -> z;
+            node_y -> z [lhead = x];
+        };
+        let expected = [
+            "node_cluster_x: \"cluster_x\" {",
+            "  shape: oval",
+            "}",
+            "node_node_y: \"node_y\" {",
+            "  shape: oval",
+            "}",
+            "cluster_x: \"x\" {",
+            "  z: \"z\" {",
+            "    shape: oval",
+            "  }",
+            "}",
+            "node_cluster_x -> cluster_x.z",
+            "node_node_y -> cluster_x",
+            ""
+        ];
+        assert_eq!(graph.to_d2().source,expected.join("\n"));
+    }
+
+    #[test]
+    fn keywords_and_backslashes_are_quoted() {
+        let graph = crate::digraph!{
+            label [label = "one\\ltwo"];
+            subgraph style {
+                shape;
+                "a\\b \"c\"";
+            }
+            label -> shape;
+            shape -> "a\\b \"c\"";
+        };
+        let expected = [
+            "\"label\": \"one\\ntwo\" {",
+            "  shape: oval",
+            "}",
+            "cluster_style: \"style\" {",
+            "  \"shape\": \"shape\" {",
+            "    shape: oval",
+            "  }",
+            "  \"a\\\\b \\\"c\\\"\": \"a\\\\b \\\"c\\\"\" {",
+            "    shape: oval",
+            "  }",
+            "}",
+            "\"label\" -> cluster_style.\"shape\"",
+            "cluster_style.\"shape\" -> cluster_style.\"a\\\\b \\\"c\\\"\"",
+            ""
+        ];
+        assert_eq!(graph.to_d2().source,expected.join("\n"));
+    }
+
+}
@@ -0,0 +1,484 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::export::{cluster_node_id, escaped_node_id, unescaped_node_id, GvExport, GvExportElement, GvExportWarning};
+use crate::export::xml::{declared_attributes, edge_attributes, edge_from_attributes, node_style_attributes, node_style_from_attributes, parse_xml, place_edges, xml_escape, GvImportError, GvUserData, XmlElement};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::traits::DotTranslatable;
+
+
+const GEXF_NODE_ATTRIBUTES : [(&str,&str);5] = [
+    ("shape","string"),
+    ("color","string"),
+    ("fillcolor","string"),
+    ("fontcolor","string"),
+    ("cluster","boolean")
+];
+
+/// "cluster" is the id of the cluster in which the edge is declared
+const GEXF_EDGE_ATTRIBUTES : [&str;7] = ["linestyle","color","fontcolor","penwidth","ltail","lhead","cluster"];
+
+struct GexfWriter<'a> {
+    user_data : &'a GvUserData,
+    /// GEXF attribute id of each user node attribute
+    node_keys : HashMap<&'a String,String>,
+    /// GEXF attribute id of each user edge attribute
+    edge_keys : HashMap<&'a String,String>,
+    source : String,
+    warnings : Vec<GvExportWarning>
+}
+
+impl GexfWriter<'_> {
+
+    fn write_element(&mut self,
+                     opening : String,
+                     name : &str,
+                     attributes : Vec<(&'static str,String)>,
+                     user_data : Vec<(String,String)>,
+                     depth : usize) {
+        let indent = "  ".repeat(depth);
+        let mut values = vec![];
+        let mut color = None;
+        let mut label = None;
+        for (attr,value) in attributes {
+            match attr {
+                "label" => {
+                    label = Some(value);
+                },
+                _ => {
+                    if attr == "fillcolor" {
+                        color = GraphvizColor::from_str(&value).ok().and_then(|c| c.to_rgb());
+                    }
+                    values.push((attr.to_string(),value));
+                }
+            }
+        }
+        values.extend(user_data);
+        let mut opening = opening;
+        if let Some(label) = label {
+            opening.push_str(&format!(" label=\"{}\"", xml_escape(&label)));
+        }
+        if values.is_empty() && color.is_none() {
+            self.source.push_str(&format!("{}{}/>\n", indent, opening));
+            return;
+        }
+        self.source.push_str(&format!("{}{}>\n", indent, opening));
+        if !values.is_empty() {
+            self.source.push_str(&format!("{}  <attvalues>\n", indent));
+            for (key,value) in values {
+                self.source.push_str(&format!("{}    <attvalue for=\"{}\" value=\"{}\"/>\n",
+                                              indent, key, xml_escape(&value)));
+            }
+            self.source.push_str(&format!("{}  </attvalues>\n", indent));
+        }
+        if let Some((r,g,b)) = color {
+            self.source.push_str(&format!("{}  <viz:color r=\"{}\" g=\"{}\" b=\"{}\"/>\n", indent, r, g, b));
+        }
+        self.source.push_str(&format!("{}</{}>\n", indent, name));
+    }
+
+    fn node_user_data(&self, data : Option<&BTreeMap<String,String>>) -> Vec<(String,String)> {
+        match data {
+            None => vec![],
+            Some(data) => data.iter().map(|(name,value)| (self.node_keys[name].clone(),value.clone())).collect()
+        }
+    }
+
+    fn write_items(&mut self,
+                   items : &mut dyn Iterator<Item=&GraphVizGraphItem>,
+                   parent : Option<&str>) {
+        for item in items {
+            let pid = parent.map(|p| format!(" pid=\"{}\"", xml_escape(&cluster_node_id(p))))
+                .unwrap_or_default();
+            match item {
+                GraphVizGraphItem::Node(node) => {
+                    let attributes = node_style_attributes(&GvExportElement::Node(node.id.clone()),
+                                                           &node.style,
+                                                           &mut self.warnings);
+                    let user_data = self.node_user_data(self.user_data.nodes.get(&node.id));
+                    self.write_element(format!("<node id=\"{}\"{}", xml_escape(&escaped_node_id(&node.id)), pid),
+                                       "node",
+                                       attributes,
+                                       user_data,
+                                       3);
+                },
+                GraphVizGraphItem::Cluster(cluster) => {
+                    let mut attributes = node_style_attributes(&GvExportElement::Cluster(cluster.id.clone()),
+                                                               &cluster.style,
+                                                               &mut self.warnings);
+                    attributes.push(("cluster","true".to_string()));
+                    let user_data = self.node_user_data(self.user_data.clusters.get(&cluster.id));
+                    let id = xml_escape(&cluster_node_id(&cluster.id));
+                    self.write_element(format!("<node id=\"{}\"{}", id, pid),
+                                       "node",
+                                       attributes,
+                                       user_data,
+                                       3);
+                    self.write_items(&mut cluster.items.iter().map(|item| item.as_ref()),Some(&cluster.id));
+                }
+            }
+        }
+    }
+
+    fn write_edge(&mut self, index : usize, cluster_id : Option<&str>, edge : &GraphVizEdge) {
+        let mut attributes = edge_attributes(edge,&mut self.warnings);
+        if let Some(cluster_id) = cluster_id {
+            attributes.push(("cluster",cluster_id.to_string()));
+        }
+        let user_data : Vec<(String,String)> = match self.user_data.edges.get(&index) {
+            None => vec![],
+            Some(data) => data.iter().map(|(name,value)| (self.edge_keys[name].clone(),value.clone())).collect()
+        };
+        self.write_element(format!("<edge id=\"e{}\" source=\"{}\" target=\"{}\"",
+                                   index,
+                                   xml_escape(&escaped_node_id(&edge.origin_node_id)),
+                                   xml_escape(&escaped_node_id(&edge.target_node_id))),
+                           "edge",
+                           attributes,
+                           user_data,
+                           3);
+    }
+
+}
+
+/// edges of the clusters, each with the id of its cluster, in the order of "GraphVizDiGraph::all_edges"
+fn cluster_edges<'a>(items : &mut dyn Iterator<Item=&'a GraphVizGraphItem>,
+                     edges : &mut Vec<(Option<&'a str>,&'a GraphVizEdge)>) {
+    for item in items {
+        if let GraphVizGraphItem::Cluster(cluster) = item {
+            edges.extend(cluster.edges.iter().map(|edge| (Some(cluster.id.as_str()),edge)));
+            cluster_edges(&mut cluster.items.iter().map(|item| item.as_ref()),edges);
+        }
+    }
+}
+
+struct GexfNode {
+    id : String,
+    parent : Option<String>,
+    attributes : Vec<(String,String)>,
+    user_data : BTreeMap<String,String>,
+    is_cluster : bool
+}
+
+/// title of the attributes declared for a class of elements, by attribute id, and whether it is a style attribute among "known"
+fn read_attribute_titles(graph : &XmlElement, class : &str, known : &[&str]) -> HashMap<String,(String,bool)> {
+    let mut declarations = vec![];
+    for attributes in graph.children_named("attributes").filter(|a| a.attribute("class") == Some(class)) {
+        for attribute in attributes.children_named("attribute") {
+            if let Some(id) = attribute.attribute("id") {
+                declarations.push((id,class,attribute.attribute("title").unwrap_or(id)));
+            }
+        }
+    }
+    declared_attributes(declarations.into_iter(),known)
+}
+
+/**
+ Label and values of the style attributes of a node or an edge, by attribute title,
+ the values of the other attributes go into the user data.
+ **/
+fn read_values(element : &XmlElement,
+               titles : &HashMap<String,(String,bool)>,
+               user_data : &mut BTreeMap<String,String>) -> Result<Vec<(String,String)>,GvImportError> {
+    let mut values = vec![];
+    if let Some(label) = element.attribute("label") {
+        values.push(("label".to_string(),label.to_string()));
+    }
+    for attvalues in element.children_named("attvalues") {
+        for attvalue in attvalues.children_named("attvalue") {
+            let key = attvalue.required_attribute("for")?;
+            let value = attvalue.required_attribute("value")?.to_string();
+            match titles.get(key) {
+                Some((title,true)) => values.push((title.clone(),value)),
+                Some((title,false)) => {
+                    user_data.insert(title.clone(),value);
+                },
+                None => {
+                    user_data.insert(key.to_string(),value);
+                }
+            }
+        }
+    }
+    Ok(values)
+}
+
+fn read_nodes(nodes : &XmlElement,
+              parent : Option<&str>,
+              titles : &HashMap<String,(String,bool)>,
+              got : &mut Vec<GexfNode>) -> Result<(),GvImportError> {
+    for node in nodes.children_named("node") {
+        let id = node.required_attribute("id")?.to_string();
+        let parent = node.attribute("pid").or(parent).map(|p| p.to_string());
+        let mut user_data = BTreeMap::new();
+        let mut attributes = read_values(node,titles,&mut user_data)?;
+        let mut is_cluster = false;
+        attributes.retain(|(name,value)| {
+            if name == "cluster" {
+                is_cluster = value == "true";
+                false
+            } else {
+                true
+            }
+        });
+        let nested : Vec<&XmlElement> = node.children_named("nodes").collect();
+        got.push(GexfNode{id:id.clone(),parent,attributes,user_data,is_cluster:is_cluster || !nested.is_empty()});
+        for nested_nodes in nested {
+            read_nodes(nested_nodes,Some(&id),titles,got)?;
+        }
+    }
+    Ok(())
+}
+
+fn build_items(parent : Option<&str>,
+               nodes : &mut Vec<Option<GexfNode>>,
+               children : &HashMap<Option<String>,Vec<usize>>,
+               clusters : &HashSet<String>,
+               user_data : &mut GvUserData) -> Vec<GraphVizGraphItem> {
+    let mut items = vec![];
+    let no_children = vec![];
+    for index in children.get(&parent.map(|p| p.to_string())).unwrap_or(&no_children) {
+        let node = match nodes[*index].take() {
+            None => {continue;},
+            Some(node) => node
+        };
+        let mut data = node.user_data;
+        let style = node_style_from_attributes(node.attributes,&mut data);
+        if clusters.contains(&node.id) {
+            let nested = build_items(Some(&node.id),nodes,children,clusters,user_data)
+                .into_iter().map(Box::new).collect();
+            let cluster_id = node.id.strip_prefix("cluster_").unwrap_or(&node.id).to_string();
+            if !data.is_empty() {
+                user_data.clusters.insert(cluster_id.clone(),data);
+            }
+            items.push(GraphVizGraphItem::Cluster(GraphVizCluster::new(cluster_id,style,nested,vec![])));
+        } else {
+            let id = unescaped_node_id(&node.id);
+            if !data.is_empty() {
+                user_data.nodes.insert(id.clone(),data);
+            }
+            items.push(GraphVizGraphItem::Node(GraphVizNode::new(id,style)));
+        }
+    }
+    items
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Translates the graph into GEXF.
+     Clusters become nodes flagged with the "cluster" attribute and the nodes they contain
+     refer to them with the "pid" hierarchy attribute.
+     Edges declared in a cluster have its id as "cluster" attribute.
+     Node, cluster and edge user data are written as additional GEXF attributes.
+     **/
+    pub fn to_gexf(&self, user_data : &GvUserData) -> GvExport {
+        let mut writer = GexfWriter{user_data,
+            node_keys:HashMap::new(),
+            edge_keys:HashMap::new(),
+            source:String::new(),
+            warnings:vec![]};
+        for item in &self.style {
+            writer.warnings.push(GvExportWarning::new(GvExportElement::Graph,item.to_dot_string()));
+        }
+        writer.source.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        writer.source.push_str("<gexf xmlns=\"http://gexf.net/1.3\" xmlns:viz=\"http://gexf.net/1.3/viz\" version=\"1.3\">\n");
+        writer.source.push_str("  <graph defaultedgetype=\"directed\" mode=\"static\">\n");
+        writer.source.push_str("    <attributes class=\"node\">\n");
+        for (name,kind) in GEXF_NODE_ATTRIBUTES {
+            writer.source.push_str(&format!("      <attribute id=\"{}\" title=\"{}\" type=\"{}\"/>\n", name, name, kind));
+        }
+        let mut node_names = GvUserData::attribute_names(&user_data.nodes);
+        node_names.extend(GvUserData::attribute_names(&user_data.clusters));
+        node_names.sort();
+        node_names.dedup();
+        for (index,name) in node_names.into_iter().enumerate() {
+            let key = format!("user_node_{}", index);
+            writer.source.push_str(&format!("      <attribute id=\"{}\" title=\"{}\" type=\"string\"/>\n",
+                                            key, xml_escape(name)));
+            writer.node_keys.insert(name,key);
+        }
+        writer.source.push_str("    </attributes>\n");
+        writer.source.push_str("    <attributes class=\"edge\">\n");
+        for name in GEXF_EDGE_ATTRIBUTES {
+            writer.source.push_str(&format!("      <attribute id=\"{}\" title=\"{}\" type=\"string\"/>\n", name, name));
+        }
+        for (index,name) in GvUserData::attribute_names(&user_data.edges).into_iter().enumerate() {
+            let key = format!("user_edge_{}", index);
+            writer.source.push_str(&format!("      <attribute id=\"{}\" title=\"{}\" type=\"string\"/>\n",
+                                            key, xml_escape(name)));
+            writer.edge_keys.insert(name,key);
+        }
+        writer.source.push_str("    </attributes>\n");
+        writer.source.push_str("    <nodes>\n");
+        writer.write_items(&mut self.items.iter(),None);
+        writer.source.push_str("    </nodes>\n");
+        writer.source.push_str("    <edges>\n");
+        let mut edges : Vec<(Option<&str>,&GraphVizEdge)> = self.edges.iter().map(|edge| (None,edge)).collect();
+        cluster_edges(&mut self.items.iter(),&mut edges);
+        for (index,(cluster_id,edge)) in edges.into_iter().enumerate() {
+            writer.write_edge(index,cluster_id,edge);
+        }
+        writer.source.push_str("    </edges>\n");
+        writer.source.push_str("  </graph>\n");
+        writer.source.push_str("</gexf>\n");
+        GvExport{source:writer.source,warnings:writer.warnings}
+    }
+
+    /**
+     Reads a graph from GEXF.
+     Nodes flagged with the "cluster" attribute, referred to by a "pid" or containing nested nodes
+     become clusters and edges are added to the cluster given by their "cluster" attribute, if any.
+     Attributes that do not correspond to a style item are returned as user data.
+     Nodes whose chain of "pid" forms a cycle make the graph invalid.
+     **/
+    pub fn from_gexf(source : &str) -> Result<(GraphVizDiGraph,GvUserData),GvImportError> {
+        let root = parse_xml(source)?;
+        if root.local_name() != "gexf" {
+            return Err(GvImportError::Invalid(format!("root element is '{}' instead of 'gexf'", root.name)));
+        }
+        let graph = root.children_named("graph").next()
+            .ok_or_else(|| GvImportError::Invalid("no 'graph' element".to_string()))?;
+        let known : Vec<&str> = GEXF_NODE_ATTRIBUTES.iter().map(|(name,_)| *name).collect();
+        let node_titles = read_attribute_titles(graph,"node",&known);
+        let edge_titles = read_attribute_titles(graph,"edge",&GEXF_EDGE_ATTRIBUTES);
+        // ***
+        let mut nodes = vec![];
+        for nodes_element in graph.children_named("nodes") {
+            read_nodes(nodes_element,None,&node_titles,&mut nodes)?;
+        }
+        let ids : HashSet<String> = nodes.iter().map(|n| n.id.clone()).collect();
+        let mut clusters : HashSet<String> = nodes.iter().filter_map(|n| n.parent.clone()).collect();
+        clusters.extend(nodes.iter().filter(|n| n.is_cluster).map(|n| n.id.clone()));
+        let mut children : HashMap<Option<String>,Vec<usize>> = HashMap::new();
+        for (index,node) in nodes.iter().enumerate() {
+            // nodes whose parent does not exist are put at the top level
+            let parent = node.parent.clone().filter(|p| ids.contains(p));
+            children.entry(parent).or_default().push(index);
+        }
+        let mut user_data = GvUserData::default();
+        let mut nodes : Vec<Option<GexfNode>> = nodes.into_iter().map(Some).collect();
+        let items = build_items(None,&mut nodes,&children,&clusters,&mut user_data);
+        // nodes which are not reachable from the top level have ancestors forming a cycle
+        let unreachable : Vec<&str> = nodes.iter().flatten().map(|node| node.id.as_str()).collect();
+        if !unreachable.is_empty() {
+            return Err(GvImportError::Invalid(format!("cycle in the 'pid' hierarchy of nodes {}", unreachable.join(", "))));
+        }
+        // ***
+        let mut edges = vec![];
+        for edges_element in graph.children_named("edges") {
+            for edge in edges_element.children_named("edge") {
+                let origin = unescaped_node_id(edge.required_attribute("source")?);
+                let target = unescaped_node_id(edge.required_attribute("target")?);
+                let mut data = BTreeMap::new();
+                let mut attributes = read_values(edge,&edge_titles,&mut data)?;
+                let mut cluster_id = None;
+                attributes.retain(|(name,value)| {
+                    if name == "cluster" {
+                        cluster_id = Some(value.clone());
+                        false
+                    } else {
+                        true
+                    }
+                });
+                edges.push((cluster_id,edge_from_attributes(origin,target,attributes,&mut data),data));
+            }
+        }
+        let mut digraph = GraphVizDiGraph::new(vec![]);
+        digraph.items = items;
+        place_edges(&mut digraph,edges,&mut user_data);
+        Ok((digraph,user_data))
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::export::xml::tests::sample;
+
+    #[test]
+    fn round_trip() {
+        let (graph,user_data) = sample();
+        let export = graph.to_gexf(&user_data);
+        assert!(export.warnings.is_empty());
+        let (imported,imported_data) = GraphVizDiGraph::from_gexf(&export.source).unwrap();
+        assert_eq!(imported.to_dot_string(),graph.to_dot_string());
+        assert_eq!(imported_data,user_data);
+    }
+
+    #[test]
+    fn pid_cycles_are_rejected() {
+        let source = "<gexf><graph><nodes>\
+            <node id=\"a\"/>\
+            <node id=\"b\" pid=\"c\"/>\
+            <node id=\"c\" pid=\"b\"/>\
+            <node id=\"d\" pid=\"d\"/>\
+            </nodes></graph></gexf>";
+        match GraphVizDiGraph::from_gexf(source) {
+            Err(GvImportError::Invalid(msg)) => assert_eq!(msg,"cycle in the 'pid' hierarchy of nodes b, c, d"),
+            _ => panic!("the cycle is not reported")
+        }
+    }
+
+    #[test]
+    fn node_and_cluster_ids_do_not_collide() {
+        let graph = crate::digraph!{
+            cluster_c1;
+            subgraph c1 {
+                x;
+            }
+            cluster_c1 -> x;
+        };
+        let source = graph.to_gexf(&GvUserData::default()).source;
+        assert!(source.contains("<node id=\"node_cluster_c1\"/>"));
+        assert!(source.contains("<node id=\"x\" pid=\"cluster_c1\"/>"));
+        let (imported,_) = GraphVizDiGraph::from_gexf(&source).unwrap();
+        assert_eq!(imported.to_dot_string(),graph.to_dot_string());
+    }
+
+    #[test]
+    fn values_are_read_by_attribute_title() {
+        let source = "<gexf><graph>\
+            <attributes class=\"node\">\
+            <attribute id=\"0\" title=\"shape\" type=\"string\"/>\
+            <attribute id=\"shape\" title=\"kind\" type=\"string\"/>\
+            </attributes>\
+            <nodes><node id=\"a\"><attvalues>\
+            <attvalue for=\"0\" value=\"rectangle\"/><attvalue for=\"shape\" value=\"circle\"/>\
+            </attvalues></node></nodes>\
+            </graph></gexf>";
+        let (graph,user_data) = GraphVizDiGraph::from_gexf(source).unwrap();
+        assert_eq!(graph.to_dot_string(),crate::digraph!{ a [shape = Rectangle]; }.to_dot_string());
+        assert_eq!(user_data.nodes["a"],BTreeMap::from([("kind".to_string(),"circle".to_string())]));
+    }
+
+}
\ No newline at end of file
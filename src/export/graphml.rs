@@ -0,0 +1,418 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::export::{cluster_node_id, escaped_node_id, unescaped_node_id, GvExport, GvExportElement, GvExportWarning};
+use crate::export::xml::{declared_attributes, edge_attributes, edge_from_attributes, node_style_attributes, node_style_from_attributes, parse_xml, place_edges, xml_escape, GvImportError, GvUserData, XmlElement};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::style::{GraphvizGraphStyleItem, GvGraphRankDir};
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::traits::DotTranslatable;
+
+
+/// keys declared in every GraphML file, "r", "g" and "b" give the fill color to Gephi
+const GRAPHML_KEYS : [(&str,&str,&str);13] = [
+    ("rankdir","graph","string"),
+    ("label","all","string"),
+    ("shape","node","string"),
+    ("color","all","string"),
+    ("fillcolor","node","string"),
+    ("fontcolor","all","string"),
+    ("r","node","int"),
+    ("g","node","int"),
+    ("b","node","int"),
+    ("linestyle","edge","string"),
+    ("penwidth","edge","int"),
+    ("ltail","edge","string"),
+    ("lhead","edge","string")
+];
+
+struct GraphmlWriter<'a> {
+    user_data : &'a GvUserData,
+    /// GraphML key of each user node attribute
+    node_keys : HashMap<&'a String,String>,
+    /// GraphML key of each user edge attribute
+    edge_keys : HashMap<&'a String,String>,
+    source : String,
+    warnings : Vec<GvExportWarning>,
+    /// index of the next edge declared in a cluster, in the order of "GraphVizDiGraph::all_edges"
+    next_edge : usize
+}
+
+impl GraphmlWriter<'_> {
+
+    fn write_data(&mut self, key : &str, value : &str, depth : usize) {
+        self.source.push_str(&format!("{}<data key=\"{}\">{}</data>\n",
+                                      "  ".repeat(depth), key, xml_escape(value)));
+    }
+
+    fn write_node_data(&mut self,
+                       attributes : &[(&'static str,String)],
+                       user_data : Option<&BTreeMap<String,String>>,
+                       depth : usize) {
+        for (name,value) in attributes {
+            self.write_data(name,value,depth);
+            if *name == "fillcolor" {
+                if let Some((r,g,b)) = GraphvizColor::from_str(value).ok().and_then(|c| c.to_rgb()) {
+                    self.write_data("r",&r.to_string(),depth);
+                    self.write_data("g",&g.to_string(),depth);
+                    self.write_data("b",&b.to_string(),depth);
+                }
+            }
+        }
+        if let Some(data) = user_data {
+            for (name,value) in data {
+                let key = self.node_keys[name].clone();
+                self.write_data(&key,value,depth);
+            }
+        }
+    }
+
+    fn write_items(&mut self, items : &mut dyn Iterator<Item=&GraphVizGraphItem>, depth : usize) {
+        for item in items {
+            match item {
+                GraphVizGraphItem::Node(node) => {
+                    self.write_node(node,depth);
+                },
+                GraphVizGraphItem::Cluster(cluster) => {
+                    self.write_cluster(cluster,depth);
+                }
+            }
+        }
+    }
+
+    fn write_node(&mut self, node : &GraphVizNode, depth : usize) {
+        let attributes = node_style_attributes(&GvExportElement::Node(node.id.clone()),
+                                               &node.style,
+                                               &mut self.warnings);
+        let indent = "  ".repeat(depth);
+        let user_data = self.user_data.nodes.get(&node.id).filter(|data| !data.is_empty());
+        let id = xml_escape(&escaped_node_id(&node.id));
+        if attributes.is_empty() && user_data.is_none() {
+            self.source.push_str(&format!("{}<node id=\"{}\"/>\n", indent, id));
+        } else {
+            self.source.push_str(&format!("{}<node id=\"{}\">\n", indent, id));
+            self.write_node_data(&attributes,user_data,depth + 1);
+            self.source.push_str(&format!("{}</node>\n", indent));
+        }
+    }
+
+    fn write_cluster(&mut self, cluster : &GraphVizCluster, depth : usize) {
+        let attributes = node_style_attributes(&GvExportElement::Cluster(cluster.id.clone()),
+                                               &cluster.style,
+                                               &mut self.warnings);
+        let indent = "  ".repeat(depth);
+        let id = xml_escape(&cluster_node_id(&cluster.id));
+        self.source.push_str(&format!("{}<node id=\"{}\">\n", indent, id));
+        let user_data = self.user_data.clusters.get(&cluster.id);
+        self.write_node_data(&attributes,user_data,depth + 1);
+        self.source.push_str(&format!("{}  <graph id=\"{}:\" edgedefault=\"directed\">\n", indent, id));
+        // the edges of the cluster come before those of its nested clusters
+        let first_edge = self.next_edge;
+        self.next_edge += cluster.edges.len();
+        self.write_items(&mut cluster.items.iter().map(|item| item.as_ref()),depth + 2);
+        for (offset,edge) in cluster.edges.iter().enumerate() {
+            self.write_edge(first_edge + offset,edge,depth + 2);
+        }
+        self.source.push_str(&format!("{}  </graph>\n", indent));
+        self.source.push_str(&format!("{}</node>\n", indent));
+    }
+
+    fn write_edge(&mut self, index : usize, edge : &GraphVizEdge, depth : usize) {
+        let attributes = edge_attributes(edge,&mut self.warnings);
+        let user_data = self.user_data.edges.get(&index).filter(|data| !data.is_empty());
+        let indent = "  ".repeat(depth);
+        let opening = format!("{}<edge id=\"e{}\" source=\"{}\" target=\"{}\"",
+                              indent,
+                              index,
+                              xml_escape(&escaped_node_id(&edge.origin_node_id)),
+                              xml_escape(&escaped_node_id(&edge.target_node_id)));
+        if attributes.is_empty() && user_data.is_none() {
+            self.source.push_str(&format!("{}/>\n", opening));
+            return;
+        }
+        self.source.push_str(&format!("{}>\n", opening));
+        for (name,value) in &attributes {
+            self.write_data(name,value,depth + 1);
+        }
+        if let Some(data) = user_data {
+            for (name,value) in data {
+                let key = self.edge_keys[name].clone();
+                self.write_data(&key,value,depth + 1);
+            }
+        }
+        self.source.push_str(&format!("{}</edge>\n", indent));
+    }
+
+}
+
+/**
+ Text of the "data" children of an element, by the attribute name ("attr.name") of their key,
+ for the style attributes (the other ones go into the user data).
+ **/
+fn read_data(element : &XmlElement,
+             keys : &HashMap<String,(String,bool)>,
+             user_data : &mut BTreeMap<String,String>) -> Result<Vec<(String,String)>,GvImportError> {
+    let mut attributes = vec![];
+    for data in element.children_named("data") {
+        let key = data.required_attribute("key")?;
+        let value = data.text.trim().to_string();
+        match keys.get(key) {
+            Some((name,true)) => attributes.push((name.clone(),value)),
+            Some((name,false)) => {
+                user_data.insert(name.clone(),value);
+            },
+            None => {
+                user_data.insert(key.to_string(),value);
+            }
+        }
+    }
+    if attributes.iter().any(|(name,_)| name == "fillcolor") {
+        attributes.retain(|(name,_)| name != "r" && name != "g" && name != "b");
+    }
+    Ok(attributes)
+}
+
+/// items of a graph element, its edges (and those of its nested graphs) are added to "edges"
+fn read_graph(graph : &XmlElement,
+              cluster_id : Option<&str>,
+              keys : &HashMap<String,(String,bool)>,
+              edges : &mut Vec<(Option<String>,GraphVizEdge,BTreeMap<String,String>)>,
+              user_data : &mut GvUserData) -> Result<Vec<GraphVizGraphItem>,GvImportError> {
+    let mut items = vec![];
+    for child in &graph.children {
+        match child.local_name() {
+            "node" => {
+                let id = child.required_attribute("id")?;
+                let mut data = BTreeMap::new();
+                let attributes = read_data(child,keys,&mut data)?;
+                let style = node_style_from_attributes(attributes,&mut data);
+                match child.children_named("graph").next() {
+                    None => {
+                        let id = unescaped_node_id(id);
+                        if !data.is_empty() {
+                            user_data.nodes.insert(id.clone(),data);
+                        }
+                        items.push(GraphVizGraphItem::Node(GraphVizNode::new(id,style)));
+                    },
+                    Some(nested) => {
+                        let nested_id = id.strip_prefix("cluster_").unwrap_or(id).to_string();
+                        if !data.is_empty() {
+                            user_data.clusters.insert(nested_id.clone(),data);
+                        }
+                        let nested_items = read_graph(nested,Some(&nested_id),keys,edges,user_data)?
+                            .into_iter().map(Box::new).collect();
+                        items.push(GraphVizGraphItem::Cluster(
+                            GraphVizCluster::new(nested_id,style,nested_items,vec![])));
+                    }
+                }
+            },
+            "edge" => {
+                let origin = unescaped_node_id(child.required_attribute("source")?);
+                let target = unescaped_node_id(child.required_attribute("target")?);
+                let mut data = BTreeMap::new();
+                let attributes = read_data(child,keys,&mut data)?;
+                let edge = edge_from_attributes(origin,target,attributes,&mut data);
+                edges.push((cluster_id.map(|id| id.to_string()),edge,data));
+            },
+            _ => {}
+        }
+    }
+    Ok(items)
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Translates the graph into GraphML.
+     Clusters become nodes containing a nested graph, in which the edges of the cluster are declared.
+     Node, cluster and edge user data are written as additional GraphML attributes.
+     **/
+    pub fn to_graphml(&self, user_data : &GvUserData) -> GvExport {
+        let mut writer = GraphmlWriter{user_data,
+            node_keys:HashMap::new(),
+            edge_keys:HashMap::new(),
+            source:String::new(),
+            warnings:vec![],
+            next_edge:self.edges.len()};
+        writer.source.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        writer.source.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        for (name,domain,kind) in GRAPHML_KEYS {
+            writer.source.push_str(&format!("  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+                                            name, domain, name, kind));
+        }
+        let mut node_names = GvUserData::attribute_names(&user_data.nodes);
+        node_names.extend(GvUserData::attribute_names(&user_data.clusters));
+        node_names.sort();
+        node_names.dedup();
+        for (index,name) in node_names.into_iter().enumerate() {
+            let key = format!("user_node_{}", index);
+            writer.source.push_str(&format!("  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"string\"/>\n",
+                                            key, xml_escape(name)));
+            writer.node_keys.insert(name,key);
+        }
+        for (index,name) in GvUserData::attribute_names(&user_data.edges).into_iter().enumerate() {
+            let key = format!("user_edge_{}", index);
+            writer.source.push_str(&format!("  <key id=\"{}\" for=\"edge\" attr.name=\"{}\" attr.type=\"string\"/>\n",
+                                            key, xml_escape(name)));
+            writer.edge_keys.insert(name,key);
+        }
+        writer.source.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+        for item in &self.style {
+            match item {
+                GraphvizGraphStyleItem::Rankdir(rankdir) => {
+                    writer.write_data("rankdir",&rankdir.to_dot_string(),2);
+                },
+                _ => {
+                    writer.warnings.push(GvExportWarning::new(GvExportElement::Graph,item.to_dot_string()));
+                }
+            }
+        }
+        writer.write_items(&mut self.items.iter(),2);
+        for (index,edge) in self.edges.iter().enumerate() {
+            writer.write_edge(index,edge,2);
+        }
+        writer.source.push_str("  </graph>\n");
+        writer.source.push_str("</graphml>\n");
+        GvExport{source:writer.source,warnings:writer.warnings}
+    }
+
+    /**
+     Reads a graph from GraphML.
+     Nodes that contain a nested graph become clusters, with the edges declared in this graph.
+     Attributes that do not correspond to a style item are returned as user data.
+     **/
+    pub fn from_graphml(source : &str) -> Result<(GraphVizDiGraph,GvUserData),GvImportError> {
+        let root = parse_xml(source)?;
+        if root.local_name() != "graphml" {
+            return Err(GvImportError::Invalid(format!("root element is '{}' instead of 'graphml'", root.name)));
+        }
+        let mut declarations = vec![];
+        for key in root.children_named("key") {
+            let id = key.required_attribute("id")?;
+            declarations.push((id,key.attribute("for").unwrap_or("all"),key.attribute("attr.name").unwrap_or(id)));
+        }
+        let known : Vec<&str> = GRAPHML_KEYS.iter().map(|(name,_,_)| *name).collect();
+        let keys = declared_attributes(declarations.into_iter(),&known);
+        let graph = root.children_named("graph").next()
+            .ok_or_else(|| GvImportError::Invalid("no 'graph' element".to_string()))?;
+        let mut style = vec![];
+        for (name,value) in read_data(graph,&keys,&mut BTreeMap::new())? {
+            if name == "rankdir" {
+                if let Ok(rankdir) = GvGraphRankDir::from_str(&value) {
+                    style.push(GraphvizGraphStyleItem::Rankdir(rankdir));
+                }
+            }
+        }
+        let mut user_data = GvUserData::default();
+        let mut edges = vec![];
+        let items = read_graph(graph,None,&keys,&mut edges,&mut user_data)?;
+        let mut digraph = GraphVizDiGraph::new(style);
+        digraph.items = items;
+        place_edges(&mut digraph,edges,&mut user_data);
+        Ok((digraph,user_data))
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::export::xml::tests::sample;
+
+    #[test]
+    fn round_trip() {
+        let (mut graph,user_data) = sample();
+        graph.style.push(GraphvizGraphStyleItem::Rankdir(GvGraphRankDir::LR));
+        let export = graph.to_graphml(&user_data);
+        assert!(export.warnings.is_empty());
+        let (imported,imported_data) = GraphVizDiGraph::from_graphml(&export.source).unwrap();
+        assert_eq!(imported.to_dot_string(),graph.to_dot_string());
+        assert_eq!(imported_data,user_data);
+    }
+
+    #[test]
+    fn edges_of_clusters_are_declared_in_their_graph() {
+        let (graph,user_data) = sample();
+        let source = graph.to_graphml(&user_data).source;
+        let nested = source.find("<graph id=\"cluster_c2:\"").unwrap();
+        let edge = source.find("<edge id=\"e3\" source=\"d\" target=\"d\">").unwrap();
+        let end = source.find("</graph>").unwrap();
+        assert!(nested < edge && edge < end);
+    }
+
+    #[test]
+    fn data_is_read_by_attribute_name() {
+        let source = "<graphml>\
+            <key id=\"d0\" for=\"node\" attr.name=\"label\"/>\
+            <key id=\"d1\" for=\"edge\" attr.name=\"label\"/>\
+            <key id=\"color\" for=\"node\" attr.name=\"weight\"/>\
+            <key id=\"d2\" for=\"node\" attr.name=\"label\"/>\
+            <graph edgedefault=\"directed\">\
+            <node id=\"n0\"><data key=\"d0\">Hello</data><data key=\"color\">red</data><data key=\"d2\">other</data></node>\
+            <node id=\"n1\"/>\
+            <edge source=\"n0\" target=\"n1\"><data key=\"d1\">x</data></edge>\
+            </graph></graphml>";
+        let (graph,user_data) = GraphVizDiGraph::from_graphml(source).unwrap();
+        let expected = crate::digraph!{
+            n0 [label = "Hello"];
+            n1;
+            n0 -> n1 [label = "x"];
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+        let mut expected_data = GvUserData::default();
+        expected_data.nodes.insert("n0".to_string(),BTreeMap::from([
+            ("label".to_string(),"other".to_string()),
+            ("weight".to_string(),"red".to_string())
+        ]));
+        assert_eq!(user_data,expected_data);
+    }
+
+    #[test]
+    fn node_and_cluster_ids_do_not_collide() {
+        let graph = crate::digraph!{
+            cluster_c1;
+            node_x;
+            subgraph c1 {
+                x;
+            }
+            cluster_c1 -> x;
+            node_x -> cluster_c1;
+        };
+        let source = graph.to_graphml(&GvUserData::default()).source;
+        assert!(source.contains("<node id=\"node_cluster_c1\"/>"));
+        assert!(source.contains("<node id=\"node_node_x\"/>"));
+        assert!(source.contains("<node id=\"cluster_c1\">"));
+        assert!(source.contains("<edge id=\"e0\" source=\"node_cluster_c1\" target=\"x\"/>"));
+        let (imported,_) = GraphVizDiGraph::from_graphml(&source).unwrap();
+        assert_eq!(imported.to_dot_string(),graph.to_dot_string());
+    }
+
+}
\ No newline at end of file
@@ -0,0 +1,277 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use crate::edge::style::GraphvizEdgeStyleItem;
+use crate::export::{cluster_node_id, escaped_node_id, json_string};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::style::GraphvizGraphStyleItem;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::style::{GraphvizNodeStyle, GraphvizNodeStyleItem};
+use crate::traits::DotTranslatable;
+
+
+/// sets a metadata entry, an item of a style overriding the previous items setting the same DOT attribute
+fn set_metadata(metadata : &mut Vec<(String,String)>, key : &str, value : String) {
+    match metadata.iter_mut().find(|(k,_)| k == key) {
+        Some(entry) => {
+            entry.1 = value;
+        },
+        None => {
+            metadata.push((key.to_string(),value));
+        }
+    }
+}
+
+/// DOT attributes of a node or cluster style item other than the label, with unquoted values
+fn node_item_metadata(item : &GraphvizNodeStyleItem) -> Vec<(&'static str,String)> {
+    match item {
+        GraphvizNodeStyleItem::Style(kinds) => {
+            let kinds : Vec<String> = kinds.iter().map(|kind| kind.to_dot_string()).collect();
+            vec![("style",kinds.join(","))]
+        },
+        GraphvizNodeStyleItem::Shape(shape) => vec![("shape",shape.to_dot_string())],
+        GraphvizNodeStyleItem::Label(label) => vec![("label",label.clone())],
+        GraphvizNodeStyleItem::Image(path) => vec![("imagescale","true".to_string()),("image",path.clone())],
+        GraphvizNodeStyleItem::Color(color) => vec![("color",color.to_dot_string())],
+        GraphvizNodeStyleItem::FillColor(color) => vec![("style","filled".to_string()),("fillcolor",color.to_dot_string())],
+        GraphvizNodeStyleItem::FontColor(color) => vec![("fontcolor",color.to_dot_string())],
+        GraphvizNodeStyleItem::FontSize(size) => vec![("fontsize",size.to_string())],
+        GraphvizNodeStyleItem::FontName(name) => vec![("fontname",name.clone())],
+        GraphvizNodeStyleItem::Height(height) => vec![("height",height.to_string())],
+        GraphvizNodeStyleItem::Width(width) => vec![("width",width.to_string())],
+        GraphvizNodeStyleItem::Peripheries(peripheries) => vec![("peripheries",peripheries.to_string())],
+        GraphvizNodeStyleItem::PenWidth(width) => vec![("penwidth",width.to_string())]
+    }
+}
+
+/// DOT attribute of an edge style item, with an unquoted value
+fn edge_item_metadata(item : &GraphvizEdgeStyleItem) -> (&'static str,String) {
+    match item {
+        GraphvizEdgeStyleItem::LineStyle(line) => ("style",line.to_dot_string()),
+        GraphvizEdgeStyleItem::Label(label) => ("label",label.clone()),
+        GraphvizEdgeStyleItem::Head(arrow) => ("arrowhead",arrow.to_dot_string()),
+        GraphvizEdgeStyleItem::Tail(arrow) => ("arrowtail",arrow.to_dot_string()),
+        GraphvizEdgeStyleItem::Color(color) => ("color",color.to_dot_string()),
+        GraphvizEdgeStyleItem::FontColor(color) => ("fontcolor",color.to_dot_string()),
+        GraphvizEdgeStyleItem::ArrowSize(size) => ("arrowsize",size.to_string()),
+        GraphvizEdgeStyleItem::FontSize(size) => ("fontsize",size.to_string()),
+        GraphvizEdgeStyleItem::PenWidth(width) => ("penwidth",width.to_string()),
+        GraphvizEdgeStyleItem::Constraint(constraint) => ("constraint",constraint.to_string())
+    }
+}
+
+/// DOT attribute of a graph style item, with an unquoted value
+fn graph_item_metadata(item : &GraphvizGraphStyleItem) -> (&'static str,String) {
+    match item {
+        GraphvizGraphStyleItem::Rankdir(rankdir) => ("rankdir",rankdir.to_dot_string()),
+        GraphvizGraphStyleItem::NodeSep(unit,decimal) => ("nodesep",format!("{}.{}", unit, decimal)),
+        GraphvizGraphStyleItem::Concentrate(concentrate) => ("concentrate",concentrate.to_string()),
+        GraphvizGraphStyleItem::Splines(splines) => {
+            let splines : &'static str = splines.into();
+            ("splines",splines.to_lowercase())
+        }
+    }
+}
+
+fn metadata_json(metadata : &[(String,String)]) -> String {
+    let entries : Vec<String> = metadata.iter()
+        .map(|(key,value)| format!("{}:{}", json_string(key), json_string(value))).collect();
+    format!("{{{}}}", entries.join(","))
+}
+
+fn node_metadata(style : &GraphvizNodeStyle) -> (Option<&String>,Vec<(String,String)>) {
+    let mut label = None;
+    let mut metadata = vec![];
+    for item in style {
+        match item {
+            GraphvizNodeStyleItem::Label(lab) => {
+                label = Some(lab);
+            },
+            _ => {
+                for (key,value) in node_item_metadata(item) {
+                    set_metadata(&mut metadata,key,value);
+                }
+            }
+        }
+    }
+    (label,metadata)
+}
+
+fn write_items(items : &mut dyn Iterator<Item=&GraphVizGraphItem>,
+               parent : Option<&str>,
+               nodes : &mut Vec<String>) {
+    for item in items {
+        let (id,label,mut metadata) = match item {
+            GraphVizGraphItem::Node(node) => {
+                let (label,metadata) = node_metadata(&node.style);
+                (escaped_node_id(&node.id),label,metadata)
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                let (label,metadata) = node_metadata(&cluster.style);
+                (cluster_node_id(&cluster.id),label,metadata)
+            }
+        };
+        if let Some(parent) = parent {
+            metadata.push(("parent".to_string(),parent.to_string()));
+        }
+        let mut fields = vec![];
+        if let Some(label) = label {
+            fields.push(format!("\"label\":{}", json_string(label)));
+        }
+        let is_cluster = matches!(item, GraphVizGraphItem::Cluster(_));
+        if is_cluster || !metadata.is_empty() {
+            let mut json = metadata_json(&metadata);
+            if is_cluster {
+                // the "cluster" flag is the only non-string metadata
+                json.insert_str(1,if metadata.is_empty() {"\"cluster\":true"} else {"\"cluster\":true,"});
+            }
+            fields.push(format!("\"metadata\":{}", json));
+        }
+        nodes.push(format!("{}:{{{}}}", json_string(&id), fields.join(",")));
+        if let GraphVizGraphItem::Cluster(cluster) = item {
+            write_items(&mut cluster.items.iter().map(|item| item.as_ref()),Some(&id),nodes);
+        }
+    }
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Translates the graph into the JSON Graph Format (version 2).
+     Clusters are nodes with a "cluster" metadata and a "cluster_<id>" id (the ids of nodes starting with "cluster_", "node_" or "edge_"
+     being escaped with "node_"), the other nodes refer to their cluster
+     through a "parent" metadata and the style items of every element are kept as DOT attributes (with unquoted values) in its metadata.
+     **/
+    pub fn to_json_graph(&self) -> String {
+        let mut graph_metadata = vec![];
+        for item in &self.style {
+            let (key,value) = graph_item_metadata(item);
+            set_metadata(&mut graph_metadata,key,value);
+        }
+        let mut nodes = vec![];
+        write_items(&mut self.items.iter(),None,&mut nodes);
+        let mut edges = vec![];
+        for edge in self.all_edges() {
+            let mut fields = vec![format!("\"source\":{}", json_string(&escaped_node_id(&edge.origin_node_id))),
+                                  format!("\"target\":{}", json_string(&escaped_node_id(&edge.target_node_id)))];
+            let mut metadata = vec![];
+            for item in &edge.style {
+                match item {
+                    GraphvizEdgeStyleItem::Label(label) => {
+                        fields.push(format!("\"label\":{}", json_string(label)));
+                    },
+                    _ => {
+                        let (key,value) = edge_item_metadata(item);
+                        set_metadata(&mut metadata,key,value);
+                    }
+                }
+            }
+            if let Some(cluster_id) = &edge.origin_cluster {
+                metadata.push(("ltail".to_string(),cluster_node_id(cluster_id)));
+            }
+            if let Some(cluster_id) = &edge.target_cluster {
+                metadata.push(("lhead".to_string(),cluster_node_id(cluster_id)));
+            }
+            if !metadata.is_empty() {
+                fields.push(format!("\"metadata\":{}", metadata_json(&metadata)));
+            }
+            edges.push(format!("{{{}}}", fields.join(",")));
+        }
+        // ***
+        let mut source = String::new();
+        source.push_str("{\n  \"graph\": {\n    \"directed\": true,\n");
+        if !graph_metadata.is_empty() {
+            source.push_str(&format!("    \"metadata\": {},\n", metadata_json(&graph_metadata)));
+        }
+        source.push_str("    \"nodes\": {");
+        source.push_str(&nodes.iter().map(|n| format!("\n      {}", n)).collect::<Vec<String>>().join(","));
+        source.push_str("\n    },\n    \"edges\": [");
+        source.push_str(&edges.iter().map(|e| format!("\n      {}", e)).collect::<Vec<String>>().join(","));
+        source.push_str("\n    ]\n  }\n}\n");
+        source
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn metadata_values_are_not_split() {
+        let graph = crate::digraph!{
+            rankdir = LR;
+            a [label = "a;b=c", fontname = "x;y=z", fillcolor = red];
+            subgraph c1 {
+                b [image = "p;q=r.png"];
+            }
+            a -> b [label = "l;m=n", style = Dashed, color = blue, lhead = c1];
+        };
+        let expected = [
+            "{",
+            "  \"graph\": {",
+            "    \"directed\": true,",
+            "    \"metadata\": {\"rankdir\":\"lr\"},",
+            "    \"nodes\": {",
+            "      \"a\":{\"label\":\"a;b=c\",\"metadata\":{\"fontname\":\"x;y=z\",\"style\":\"filled\",\"fillcolor\":\"red\"}},",
+            "      \"cluster_c1\":{\"metadata\":{\"cluster\":true}},",
+            "      \"b\":{\"metadata\":{\"imagescale\":\"true\",\"image\":\"p;q=r.png\",\"parent\":\"cluster_c1\"}}",
+            "    },",
+            "    \"edges\": [",
+            "      {\"source\":\"a\",\"target\":\"b\",\"label\":\"l;m=n\",\"metadata\":{\"style\":\"dashed\",\"color\":\"blue\",\"lhead\":\"cluster_c1\"}}",
+            "    ]",
+            "  }",
+            "}",
+            ""
+        ];
+        assert_eq!(graph.to_json_graph(),expected.join("\n"));
+    }
+
+    #[test]
+    fn node_and_cluster_ids_are_distinct() {
+        let graph = crate::digraph!{
+            cluster_c1;
+            subgraph c1 {
+                x;
+            }
+            cluster_c1 -> x [ltail = c1];
+        };
+        let expected = [
+            "{",
+            "  \"graph\": {",
+            "    \"directed\": true,",
+            "    \"nodes\": {",
+            "      \"node_cluster_c1\":{},",
+            "      \"cluster_c1\":{\"metadata\":{\"cluster\":true}},",
+            "      \"x\":{\"metadata\":{\"parent\":\"cluster_c1\"}}",
+            "    },",
+            "    \"edges\": [",
+            "      {\"source\":\"node_cluster_c1\",\"target\":\"x\",\"metadata\":{\"ltail\":\"cluster_c1\"}}",
+            "    ]",
+            "  }",
+            "}",
+            ""
+        ];
+        assert_eq!(graph.to_json_graph(),expected.join("\n"));
+    }
+
+}
\ No newline at end of file
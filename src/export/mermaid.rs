@@ -0,0 +1,421 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::{GraphvizEdgeStyleItem, GvArrowHeadFill, GvArrowHeadSide, GvArrowHeadStyle, GvEdgeLineStyle};
+use crate::export::{alphanumeric_id, GvExport, GvExportElement, GvExportWarning};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::style::{GraphvizGraphStyleItem, GvGraphRankDir};
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::{GraphvizNodeStyleItem, GvNodeShape, GvNodeStyleKind};
+use crate::traits::DotTranslatable;
+
+
+/// words which cannot be used as Mermaid identifiers
+const MERMAID_KEYWORDS : [&str;10] = ["end","subgraph","graph","flowchart","direction","style","linkStyle","classDef","class","click"];
+
+/**
+ Mermaid identifiers may only contain letters, digits and underscores.
+ Keywords get a trailing underscore, which no encoded id ends with alone.
+ **/
+fn mermaid_id(id : &str) -> String {
+    let encoded = alphanumeric_id(id);
+    if MERMAID_KEYWORDS.contains(&encoded.as_str()) {
+        format!("{}_", encoded)
+    } else {
+        encoded
+    }
+}
+
+/// no encoded id is an underscore followed by another encoded id, hence clusters cannot collide with nodes
+fn mermaid_cluster_id(id : &str) -> String {
+    format!("cluster_{}", alphanumeric_id(id))
+}
+
+fn mermaid_text(text : &str) -> String {
+    text.replace('"',"#quot;")
+        .replace("\\n","<br/>")
+        .replace("\\l","<br/>")
+        .replace("\\r","<br/>")
+        .replace('\n',"<br/>")
+}
+
+fn css_color(color : &GraphvizColor) -> String {
+    color.to_hex_string().unwrap_or_else(|| "transparent".to_string())
+}
+
+/// opening and closing delimiters of the Mermaid shape closest to a Graphviz node shape
+fn shape_delimiters(shape : &GvNodeShape) -> Option<(&'static str,&'static str)> {
+    match shape {
+        GvNodeShape::Ellipse => Some(("([","])")),
+        GvNodeShape::Circle => Some(("((","))")),
+        GvNodeShape::DoubleCircle => Some(("(((",")))")),
+        GvNodeShape::Diamond => Some(("{","}")),
+        GvNodeShape::Hexagon => Some(("{{","}}")),
+        GvNodeShape::Parallelogram => Some(("[/","/]")),
+        GvNodeShape::Trapezium => Some(("[/","\\]")),
+        GvNodeShape::InvTrapezium => Some(("[\\","/]")),
+        GvNodeShape::Rectangle
+        | GvNodeShape::Square => Some(("[","]")),
+        _ => None
+    }
+}
+
+struct MermaidWriter {
+    source : String,
+    warnings : Vec<GvExportWarning>,
+    /// css of each class definition and the nodes using it
+    classes : Vec<(String,Vec<String>)>,
+    /// style statements of clusters
+    cluster_styles : Vec<String>,
+    link_styles : Vec<String>
+}
+
+impl MermaidWriter {
+
+    fn warn(&mut self, element : GvExportElement, feature : String) {
+        self.warnings.push(GvExportWarning::new(element,feature));
+    }
+
+    fn write_items(&mut self, items : &mut dyn Iterator<Item=&GraphVizGraphItem>, depth : usize) {
+        for item in items {
+            match item {
+                GraphVizGraphItem::Node(node) => {
+                    self.write_node(node,depth);
+                },
+                GraphVizGraphItem::Cluster(cluster) => {
+                    self.write_cluster(cluster,depth);
+                }
+            }
+        }
+    }
+
+    fn write_node(&mut self, node : &GraphVizNode, depth : usize) {
+        let element = GvExportElement::Node(node.id.clone());
+        let mut label = node.id.clone();
+        // nodes are ellipses by default in Graphviz
+        let mut delimiters = ("([","])");
+        let mut css : Vec<String> = vec![];
+        for item in &node.style {
+            match item {
+                GraphvizNodeStyleItem::Label(lab) => {
+                    label = lab.clone();
+                },
+                GraphvizNodeStyleItem::Shape(shape) => {
+                    match shape_delimiters(shape) {
+                        Some(delims) => {
+                            delimiters = delims;
+                        },
+                        None => {
+                            delimiters = ("[","]");
+                            self.warn(element.clone(),item.to_dot_string());
+                        }
+                    }
+                },
+                GraphvizNodeStyleItem::Style(kinds) => {
+                    for kind in kinds {
+                        match kind {
+                            GvNodeStyleKind::Solid
+                            | GvNodeStyleKind::Filled => {},
+                            GvNodeStyleKind::Dashed => {
+                                css.push("stroke-dasharray:5 5".to_string());
+                            },
+                            GvNodeStyleKind::Dotted => {
+                                css.push("stroke-dasharray:2 2".to_string());
+                            },
+                            GvNodeStyleKind::Bold => {
+                                css.push("stroke-width:2px".to_string());
+                            },
+                            _ => {
+                                self.warn(element.clone(),format!("style={}",kind.to_dot_string()));
+                            }
+                        }
+                    }
+                },
+                GraphvizNodeStyleItem::Color(color) => {
+                    css.push(format!("stroke:{}",css_color(color)));
+                },
+                GraphvizNodeStyleItem::FillColor(color) => {
+                    css.push(format!("fill:{}",css_color(color)));
+                },
+                GraphvizNodeStyleItem::FontColor(color) => {
+                    css.push(format!("color:{}",css_color(color)));
+                },
+                GraphvizNodeStyleItem::FontSize(size) => {
+                    css.push(format!("font-size:{}px",size));
+                },
+                GraphvizNodeStyleItem::FontName(name) => {
+                    css.push(format!("font-family:{}",name));
+                },
+                GraphvizNodeStyleItem::PenWidth(width) => {
+                    css.push(format!("stroke-width:{}px",width));
+                },
+                GraphvizNodeStyleItem::Image(_)
+                | GraphvizNodeStyleItem::Height(_)
+                | GraphvizNodeStyleItem::Width(_)
+                | GraphvizNodeStyleItem::Peripheries(_) => {
+                    self.warn(element.clone(),item.to_dot_string());
+                }
+            }
+        }
+        let id = mermaid_id(&node.id);
+        self.source.push_str(&format!("{}{}{}\"{}\"{}\n",
+                                      "    ".repeat(depth),
+                                      id,
+                                      delimiters.0,
+                                      mermaid_text(&label),
+                                      delimiters.1));
+        if !css.is_empty() {
+            let css = css.join(",");
+            match self.classes.iter_mut().find(|(c,_)| *c == css) {
+                Some((_,nodes)) => {
+                    nodes.push(id);
+                },
+                None => {
+                    self.classes.push((css,vec![id]));
+                }
+            }
+        }
+    }
+
+    fn write_cluster(&mut self, cluster : &GraphVizCluster, depth : usize) {
+        let element = GvExportElement::Cluster(cluster.id.clone());
+        let id = mermaid_cluster_id(&cluster.id);
+        let mut title = cluster.id.clone();
+        let mut css : Vec<String> = vec![];
+        for item in &cluster.style {
+            match item {
+                GraphvizNodeStyleItem::Label(lab) => {
+                    title = lab.clone();
+                },
+                GraphvizNodeStyleItem::Color(color) => {
+                    css.push(format!("stroke:{}",css_color(color)));
+                },
+                GraphvizNodeStyleItem::FillColor(color) => {
+                    css.push(format!("fill:{}",css_color(color)));
+                },
+                GraphvizNodeStyleItem::FontColor(color) => {
+                    css.push(format!("color:{}",css_color(color)));
+                },
+                GraphvizNodeStyleItem::PenWidth(width) => {
+                    css.push(format!("stroke-width:{}px",width));
+                },
+                _ => {
+                    self.warn(element.clone(),item.to_dot_string());
+                }
+            }
+        }
+        let indent = "    ".repeat(depth);
+        self.source.push_str(&format!("{}subgraph {} [\"{}\"]\n", indent, id, mermaid_text(&title)));
+        self.write_items(&mut cluster.items.iter().map(|item| item.as_ref()),depth + 1);
+        self.source.push_str(&format!("{}end\n", indent));
+        if !css.is_empty() {
+            self.cluster_styles.push(format!("style {} {}", id, css.join(",")));
+        }
+    }
+
+    fn write_edge(&mut self, edge : &GraphVizEdge) {
+        let element = GvExportElement::Edge(edge.origin_node_id.clone(),edge.target_node_id.clone());
+        let mut line = GvEdgeLineStyle::Solid;
+        let mut head = Some('>');
+        let mut label : Option<&String> = None;
+        let mut css : Vec<String> = vec![];
+        for item in &edge.style {
+            match item {
+                GraphvizEdgeStyleItem::LineStyle(style) => {
+                    if *style == GvEdgeLineStyle::Dotted {
+                        // drawn as dashed
+                        self.warn(element.clone(),item.to_dot_string());
+                    }
+                    line = style.clone();
+                },
+                GraphvizEdgeStyleItem::Label(lab) => {
+                    label = Some(lab);
+                },
+                GraphvizEdgeStyleItem::Head(arrow) => {
+                    head = match arrow {
+                        GvArrowHeadStyle::NoArrow => None,
+                        GvArrowHeadStyle::Normal(GvArrowHeadFill::Filled,GvArrowHeadSide::Both) => Some('>'),
+                        GvArrowHeadStyle::Dot(_) => Some('o'),
+                        _ => {
+                            self.warn(element.clone(),item.to_dot_string());
+                            Some('>')
+                        }
+                    };
+                },
+                GraphvizEdgeStyleItem::Color(color) => {
+                    css.push(format!("stroke:{}",css_color(color)));
+                },
+                GraphvizEdgeStyleItem::FontColor(color) => {
+                    css.push(format!("color:{}",css_color(color)));
+                },
+                GraphvizEdgeStyleItem::PenWidth(width) => {
+                    css.push(format!("stroke-width:{}px",width));
+                },
+                GraphvizEdgeStyleItem::Tail(_)
+                | GraphvizEdgeStyleItem::ArrowSize(_)
+                | GraphvizEdgeStyleItem::FontSize(_)
+                | GraphvizEdgeStyleItem::Constraint(_) => {
+                    self.warn(element.clone(),item.to_dot_string());
+                }
+            }
+        }
+        let link = match (&line,head) {
+            (GvEdgeLineStyle::Invis,_) => "~~~".to_string(),
+            (GvEdgeLineStyle::Dashed,Some(h))
+            | (GvEdgeLineStyle::Dotted,Some(h)) => format!("-.-{}",h),
+            (GvEdgeLineStyle::Dashed,None)
+            | (GvEdgeLineStyle::Dotted,None) => "-.-".to_string(),
+            (GvEdgeLineStyle::Bold,Some(h)) => format!("=={}",h),
+            (GvEdgeLineStyle::Bold,None) => "===".to_string(),
+            (GvEdgeLineStyle::Solid,Some(h)) => format!("--{}",h),
+            (GvEdgeLineStyle::Solid,None) => "---".to_string()
+        };
+        let origin = match &edge.origin_cluster {
+            None => mermaid_id(&edge.origin_node_id),
+            Some(cluster_id) => mermaid_cluster_id(cluster_id)
+        };
+        let target = match &edge.target_cluster {
+            None => mermaid_id(&edge.target_node_id),
+            Some(cluster_id) => mermaid_cluster_id(cluster_id)
+        };
+        match label {
+            Some(lab) if line != GvEdgeLineStyle::Invis => {
+                self.source.push_str(&format!("    {} {}|\"{}\"| {}\n", origin, link, mermaid_text(lab), target));
+            },
+            _ => {
+                if let Some(lab) = label {
+                    self.warn(element,format!("label=\"{}\" on an invisible edge",lab));
+                }
+                self.source.push_str(&format!("    {} {} {}\n", origin, link, target));
+            }
+        }
+        self.link_styles.push(css.join(","));
+    }
+
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Translates the graph into a Mermaid flowchart.
+     Clusters become subgraphs, node shapes are mapped to their closest Mermaid equivalent
+     and node colors are gathered into class definitions.
+     Every style item that Mermaid cannot represent is listed in the warnings of the result.
+     **/
+    pub fn to_mermaid(&self) -> GvExport {
+        let mut writer = MermaidWriter{source:String::new(),
+            warnings:vec![],
+            classes:vec![],
+            cluster_styles:vec![],
+            link_styles:vec![]};
+        let mut direction = "TB";
+        for item in &self.style {
+            match item {
+                GraphvizGraphStyleItem::Rankdir(rankdir) => {
+                    direction = match rankdir {
+                        GvGraphRankDir::TB => "TB",
+                        GvGraphRankDir::BT => "BT",
+                        GvGraphRankDir::LR => "LR",
+                        GvGraphRankDir::RL => "RL"
+                    };
+                },
+                _ => {
+                    writer.warn(GvExportElement::Graph,item.to_dot_string());
+                }
+            }
+        }
+        writer.source.push_str(&format!("flowchart {}\n", direction));
+        writer.write_items(&mut self.items.iter(),1);
+        // edges are written after every node so that they do not move nodes into subgraphs
+        for edge in self.all_edges() {
+            writer.write_edge(edge);
+        }
+        for (index,css) in writer.link_styles.iter().enumerate() {
+            if !css.is_empty() {
+                writer.source.push_str(&format!("    linkStyle {} {}\n", index, css));
+            }
+        }
+        for style in &writer.cluster_styles {
+            writer.source.push_str(&format!("    {}\n", style));
+        }
+        for (index,(css,nodes)) in writer.classes.iter().enumerate() {
+            writer.source.push_str(&format!("    classDef gvclass{} {}\n", index, css));
+            writer.source.push_str(&format!("    class {} gvclass{}\n", nodes.join(","), index));
+        }
+        GvExport{source:writer.source,warnings:writer.warnings}
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn ids_are_distinct() {
+        let ids = ["a-b","a_b","a__b","ab","a b","end","end_","cluster_x","é"];
+        let mut encoded : Vec<String> = ids.iter().map(|id| mermaid_id(id)).collect();
+        encoded.push(mermaid_cluster_id("x"));
+        encoded.push(mermaid_cluster_id("_x"));
+        for (index,id) in encoded.iter().enumerate() {
+            assert!(id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'), "{}", id);
+            assert!(!encoded[index+1..].contains(id), "{}", id);
+        }
+        assert_eq!(mermaid_id("a-b"),"a_2d_b");
+        assert_eq!(mermaid_id("a_b"),"a__b");
+        assert_eq!(mermaid_id("end"),"end_");
+    }
+
+    #[test]
+    fn flowchart() {
+        let graph = crate::digraph!{
+            rankdir = LR;
+            end [label = "End", shape = Diamond];
+            subgraph c1 {
+                label = "C";
+                ("a-b");
+            }
+            end -> ("a-b") [style = Dotted, label = "x"];
+            end -> a_b [style = Dashed, lhead = c1];
+        };
+        let export = graph.to_mermaid();
+        let expected = [
+            "flowchart LR",
+            "    end_{\"End\"}",
+            "    subgraph cluster_c1 [\"C\"]",
+            "        a_2d_b([\"a-b\"])",
+            "    end",
+            "    end_ -.->|\"x\"| a_2d_b",
+            "    end_ -.-> cluster_c1",
+            ""
+        ];
+        assert_eq!(export.source,expected.join("\n"));
+        assert_eq!(export.warnings,vec![GvExportWarning::new(GvExportElement::Edge("end".to_string(),"a-b".to_string()),"style=dotted".to_string())]);
+    }
+
+}
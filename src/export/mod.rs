@@ -0,0 +1,170 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+pub mod terminal;
+pub mod mermaid;
+pub mod plantuml;
+pub mod d2;
+pub mod xml;
+pub mod graphml;
+pub mod gexf;
+pub mod cytoscape;
+pub mod jgf;
+
+
+use std::fmt;
+
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::GraphvizEdgeStyleItem;
+use crate::item::node::style::{GraphvizNodeStyle, GraphvizNodeStyleItem};
+
+
+/**
+ Element of the graph to which an export warning refers.
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub enum GvExportElement {
+    Graph,
+    Node(String),
+    Cluster(String),
+    /// origin and target node ids of the edge
+    Edge(String,String)
+}
+
+impl fmt::Display for GvExportElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GvExportElement::Graph => write!(f, "graph"),
+            GvExportElement::Node(id) => write!(f, "node {}", id),
+            GvExportElement::Cluster(id) => write!(f, "cluster {}", id),
+            GvExportElement::Edge(origin,target) => write!(f, "edge {}->{}", origin, target)
+        }
+    }
+}
+
+/**
+ A feature of the graph that the target format cannot represent and that was not exported.
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub struct GvExportWarning {
+    pub element : GvExportElement,
+    pub feature : String
+}
+
+impl GvExportWarning {
+    pub fn new(element : GvExportElement, feature : String) -> GvExportWarning {
+        GvExportWarning{element,feature}
+    }
+}
+
+impl fmt::Display for GvExportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} : unsupported {}", self.element, self.feature)
+    }
+}
+
+/**
+ Source code of a graph in another format, together with what could not be exported.
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub struct GvExport {
+    pub source : String,
+    pub warnings : Vec<GvExportWarning>
+}
+
+
+
+
+pub(crate) fn node_style_label(style : &GraphvizNodeStyle) -> Option<&String> {
+    style.iter().find_map(|item| {
+        match item {
+            GraphvizNodeStyleItem::Label(label) => Some(label),
+            _ => None
+        }
+    })
+}
+
+pub(crate) fn edge_style_label(edge : &GraphVizEdge) -> Option<&String> {
+    edge.style.iter().find_map(|item| {
+        match item {
+            GraphvizEdgeStyleItem::Label(label) => Some(label),
+            _ => None
+        }
+    })
+}
+
+/**
+ Identifier made only of ASCII letters, digits and underscores, distinct for distinct ids :
+ underscores are doubled and every other character is written as "_<hexadecimal code>_".
+ **/
+pub(crate) fn alphanumeric_id(id : &str) -> String {
+    let mut res = String::with_capacity(id.len());
+    for c in id.chars() {
+        match c {
+            '_' => res.push_str("__"),
+            c if c.is_ascii_alphanumeric() => res.push(c),
+            c => res.push_str(&format!("_{:x}_", c as u32))
+        }
+    }
+    res
+}
+
+/// prefixes of the ids generated for clusters and edges in formats where they share the namespace of nodes
+const GENERATED_ID_PREFIXES : [&str;3] = ["cluster_","node_","edge_"];
+
+/**
+ Id of a node in a format where generated ids ("cluster_<id>" for clusters, "edge_<index>" for edges) share its namespace :
+ the ids of nodes starting with "cluster_", "node_" or "edge_" are escaped with "node_", so that all ids are distinct.
+ **/
+pub(crate) fn escaped_node_id(id : &str) -> String {
+    if GENERATED_ID_PREFIXES.iter().any(|prefix| id.starts_with(prefix)) {
+        format!("node_{}", id)
+    } else {
+        id.to_string()
+    }
+}
+
+pub(crate) fn cluster_node_id(id : &str) -> String {
+    format!("cluster_{}", id)
+}
+
+/// id of a node from the id given by "escaped_node_id", other ids being kept as they are
+pub(crate) fn unescaped_node_id(id : &str) -> String {
+    match id.strip_prefix("node_") {
+        Some(escaped) if GENERATED_ID_PREFIXES.iter().any(|prefix| escaped.starts_with(prefix)) => escaped.to_string(),
+        _ => id.to_string()
+    }
+}
+
+/// quoted and escaped JSON string
+pub(crate) fn json_string(text : &str) -> String {
+    let mut res = String::with_capacity(text.len() + 2);
+    res.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => res.push(c)
+        }
+    }
+    res.push('"');
+    res
+}
@@ -0,0 +1,370 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::{GraphvizEdgeStyleItem, GvArrowHeadFill, GvArrowHeadSide, GvArrowHeadStyle, GvEdgeLineStyle};
+use crate::export::{alphanumeric_id, GvExport, GvExportElement, GvExportWarning};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::style::{GraphvizGraphStyleItem, GvGraphRankDir};
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::{GraphvizNodeStyleItem, GvNodeShape, GvNodeStyleKind};
+use crate::traits::DotTranslatable;
+
+
+/**
+ Kind of PlantUML diagram in which the graph is drawn.
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub enum GvPlantUmlDiagram {
+    /// nodes become components (or the element whose shape is closest to theirs) and clusters become rectangles
+    Component,
+    /// nodes become states and clusters become composite states
+    State
+}
+
+fn plantuml_id(id : &str) -> String {
+    alphanumeric_id(id)
+}
+
+/// no encoded id is an underscore followed by another encoded id, hence clusters cannot collide with nodes
+fn plantuml_cluster_id(id : &str) -> String {
+    format!("cluster_{}", alphanumeric_id(id))
+}
+
+fn plantuml_text(text : &str) -> String {
+    text.replace('"',"'")
+        .replace("\\l","\\n")
+        .replace("\\r","\\n")
+        .replace('\n',"\\n")
+}
+
+fn plantuml_color(color : &GraphvizColor) -> String {
+    color.to_hex_string()
+        .map(|hex| hex.trim_start_matches('#').to_string())
+        .unwrap_or_else(|| "transparent".to_string())
+}
+
+/// inline element style of the form "#back:color;line:color;line.dashed;text:color"
+fn inline_style(parts : &[String]) -> String {
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" #{}", parts.join(";"))
+    }
+}
+
+/// PlantUML element keyword of a component diagram whose shape is closest to a Graphviz node shape
+fn component_keyword(shape : &GvNodeShape) -> Option<&'static str> {
+    match shape {
+        GvNodeShape::Ellipse => Some("usecase"),
+        GvNodeShape::Circle
+        | GvNodeShape::Point => Some("circle"),
+        GvNodeShape::Hexagon => Some("hexagon"),
+        GvNodeShape::Rectangle
+        | GvNodeShape::Square => Some("rectangle"),
+        GvNodeShape::PlainText => Some("label"),
+        _ => None
+    }
+}
+
+/// PlantUML stereotype of a state diagram whose shape is closest to a Graphviz node shape
+fn state_stereotype(shape : &GvNodeShape) -> Option<&'static str> {
+    match shape {
+        GvNodeShape::Diamond => Some(" <<choice>>"),
+        GvNodeShape::Point => Some(" <<start>>"),
+        GvNodeShape::DoubleCircle => Some(" <<end>>"),
+        GvNodeShape::Rectangle
+        | GvNodeShape::Square => Some(""),
+        _ => None
+    }
+}
+
+struct PlantUmlWriter {
+    diagram : GvPlantUmlDiagram,
+    source : String,
+    warnings : Vec<GvExportWarning>
+}
+
+impl PlantUmlWriter {
+
+    fn warn(&mut self, element : GvExportElement, feature : String) {
+        self.warnings.push(GvExportWarning::new(element,feature));
+    }
+
+    fn write_items(&mut self, items : &mut dyn Iterator<Item=&GraphVizGraphItem>, depth : usize) {
+        for item in items {
+            match item {
+                GraphVizGraphItem::Node(node) => {
+                    self.write_node(node,depth);
+                },
+                GraphVizGraphItem::Cluster(cluster) => {
+                    self.write_cluster(cluster,depth);
+                }
+            }
+        }
+    }
+
+    /// translates the style shared by nodes and clusters into inline style parts
+    fn colors_and_lines(&mut self,
+                        element : &GvExportElement,
+                        item : &GraphvizNodeStyleItem,
+                        parts : &mut Vec<String>) {
+        match item {
+            GraphvizNodeStyleItem::FillColor(color) => {
+                parts.insert(0,format!("back:{}",plantuml_color(color)));
+            },
+            GraphvizNodeStyleItem::Color(color) => {
+                parts.push(format!("line:{}",plantuml_color(color)));
+            },
+            GraphvizNodeStyleItem::FontColor(color) => {
+                parts.push(format!("text:{}",plantuml_color(color)));
+            },
+            GraphvizNodeStyleItem::Style(kinds) => {
+                for kind in kinds {
+                    match kind {
+                        GvNodeStyleKind::Solid
+                        | GvNodeStyleKind::Filled => {},
+                        GvNodeStyleKind::Dashed => {
+                            parts.push("line.dashed".to_string());
+                        },
+                        GvNodeStyleKind::Dotted => {
+                            parts.push("line.dotted".to_string());
+                        },
+                        GvNodeStyleKind::Bold => {
+                            parts.push("line.bold".to_string());
+                        },
+                        _ => {
+                            self.warn(element.clone(),format!("style={}",kind.to_dot_string()));
+                        }
+                    }
+                }
+            },
+            _ => {
+                self.warn(element.clone(),item.to_dot_string());
+            }
+        }
+    }
+
+    fn write_node(&mut self, node : &GraphVizNode, depth : usize) {
+        let element = GvExportElement::Node(node.id.clone());
+        let mut label = node.id.clone();
+        let mut keyword = match self.diagram {
+            GvPlantUmlDiagram::Component => "component",
+            GvPlantUmlDiagram::State => "state"
+        };
+        let mut stereotype = "";
+        let mut parts : Vec<String> = vec![];
+        for item in &node.style {
+            match item {
+                GraphvizNodeStyleItem::Label(lab) => {
+                    label = lab.clone();
+                },
+                GraphvizNodeStyleItem::Shape(shape) => {
+                    let mapped = match self.diagram {
+                        GvPlantUmlDiagram::Component => component_keyword(shape).map(|kw| (kw,"")),
+                        GvPlantUmlDiagram::State => state_stereotype(shape).map(|st| ("state",st))
+                    };
+                    match mapped {
+                        Some((kw,st)) => {
+                            keyword = kw;
+                            stereotype = st;
+                        },
+                        None => {
+                            self.warn(element.clone(),item.to_dot_string());
+                        }
+                    }
+                },
+                _ => {
+                    self.colors_and_lines(&element,item,&mut parts);
+                }
+            }
+        }
+        self.source.push_str(&format!("{}{} \"{}\" as {}{}{}\n",
+                                      "  ".repeat(depth),
+                                      keyword,
+                                      plantuml_text(&label),
+                                      plantuml_id(&node.id),
+                                      stereotype,
+                                      inline_style(&parts)));
+    }
+
+    fn write_cluster(&mut self, cluster : &GraphVizCluster, depth : usize) {
+        let element = GvExportElement::Cluster(cluster.id.clone());
+        let mut title = cluster.id.clone();
+        let mut parts : Vec<String> = vec![];
+        for item in &cluster.style {
+            match item {
+                GraphvizNodeStyleItem::Label(lab) => {
+                    title = lab.clone();
+                },
+                _ => {
+                    self.colors_and_lines(&element,item,&mut parts);
+                }
+            }
+        }
+        let keyword = match self.diagram {
+            GvPlantUmlDiagram::Component => "rectangle",
+            GvPlantUmlDiagram::State => "state"
+        };
+        let indent = "  ".repeat(depth);
+        self.source.push_str(&format!("{}{} \"{}\" as {}{} {{\n",
+                                      indent,
+                                      keyword,
+                                      plantuml_text(&title),
+                                      plantuml_cluster_id(&cluster.id),
+                                      inline_style(&parts)));
+        self.write_items(&mut cluster.items.iter().map(|item| item.as_ref()),depth + 1);
+        self.source.push_str(&format!("{}}}\n", indent));
+    }
+
+    fn write_edge(&mut self, edge : &GraphVizEdge) {
+        let element = GvExportElement::Edge(edge.origin_node_id.clone(),edge.target_node_id.clone());
+        let mut label : Option<&String> = None;
+        let mut options : Vec<String> = vec![];
+        let mut head = ">";
+        for item in &edge.style {
+            match item {
+                GraphvizEdgeStyleItem::Label(lab) => {
+                    label = Some(lab);
+                },
+                GraphvizEdgeStyleItem::LineStyle(line) => {
+                    match line {
+                        GvEdgeLineStyle::Solid => {},
+                        GvEdgeLineStyle::Dashed => options.push("dashed".to_string()),
+                        GvEdgeLineStyle::Dotted => options.push("dotted".to_string()),
+                        GvEdgeLineStyle::Bold => options.push("bold".to_string()),
+                        GvEdgeLineStyle::Invis => options.push("hidden".to_string())
+                    }
+                },
+                GraphvizEdgeStyleItem::Color(color) => {
+                    options.insert(0,format!("#{}",plantuml_color(color)));
+                },
+                GraphvizEdgeStyleItem::Head(arrow) => {
+                    head = match arrow {
+                        GvArrowHeadStyle::NoArrow => "",
+                        GvArrowHeadStyle::Normal(GvArrowHeadFill::Filled,GvArrowHeadSide::Both) => ">",
+                        _ => {
+                            self.warn(element.clone(),item.to_dot_string());
+                            ">"
+                        }
+                    };
+                },
+                GraphvizEdgeStyleItem::FontColor(_)
+                | GraphvizEdgeStyleItem::Tail(_)
+                | GraphvizEdgeStyleItem::ArrowSize(_)
+                | GraphvizEdgeStyleItem::FontSize(_)
+                | GraphvizEdgeStyleItem::PenWidth(_)
+                | GraphvizEdgeStyleItem::Constraint(_) => {
+                    self.warn(element.clone(),item.to_dot_string());
+                }
+            }
+        }
+        let arrow = if options.is_empty() {
+            format!("--{}", head)
+        } else {
+            format!("-[{}]-{}", options.join(","), head)
+        };
+        let origin = match &edge.origin_cluster {
+            None => plantuml_id(&edge.origin_node_id),
+            Some(cluster_id) => plantuml_cluster_id(cluster_id)
+        };
+        let target = match &edge.target_cluster {
+            None => plantuml_id(&edge.target_node_id),
+            Some(cluster_id) => plantuml_cluster_id(cluster_id)
+        };
+        match label {
+            None => {
+                self.source.push_str(&format!("{} {} {}\n", origin, arrow, target));
+            },
+            Some(lab) => {
+                self.source.push_str(&format!("{} {} {} : {}\n", origin, arrow, target, plantuml_text(lab)));
+            }
+        }
+    }
+
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Translates the graph into a PlantUML component or state diagram.
+     Clusters become (nested) containers and edges may target them when they use lhead/ltail.
+     Every style item that PlantUML cannot represent is listed in the warnings of the result.
+     **/
+    pub fn to_plantuml(&self, diagram : &GvPlantUmlDiagram) -> GvExport {
+        let mut writer = PlantUmlWriter{diagram:diagram.clone(),source:String::new(),warnings:vec![]};
+        writer.source.push_str("@startuml\n");
+        for item in &self.style {
+            match item {
+                GraphvizGraphStyleItem::Rankdir(GvGraphRankDir::TB) => {
+                    writer.source.push_str("top to bottom direction\n");
+                },
+                GraphvizGraphStyleItem::Rankdir(GvGraphRankDir::LR) => {
+                    writer.source.push_str("left to right direction\n");
+                },
+                _ => {
+                    writer.warn(GvExportElement::Graph,item.to_dot_string());
+                }
+            }
+        }
+        writer.write_items(&mut self.items.iter(),0);
+        for edge in self.all_edges() {
+            writer.write_edge(edge);
+        }
+        writer.source.push_str("@enduml\n");
+        GvExport{source:writer.source,warnings:writer.warnings}
+    }
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn ids_do_not_collide() {
+        let graph = crate::digraph!{
+            ("a-b"); a_b;
+            subgraph x { cluster_x; }
+            ("a-b") -> a_b;
+            a_b -> cluster_x [lhead = x];
+        };
+        let expected = [
+            "@startuml",
+            "component \"a-b\" as a_2d_b",
+            "component \"a_b\" as a__b",
+            "rectangle \"x\" as cluster_x {",
+            "  component \"cluster_x\" as cluster__x",
+            "}",
+            "a_2d_b --> a__b",
+            "a__b --> cluster_x",
+            "@enduml",
+            ""
+        ];
+        assert_eq!(graph.to_plantuml(&GvPlantUmlDiagram::Component).source,expected.join("\n"));
+    }
+
+}
@@ -0,0 +1,772 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use std::collections::HashMap;
+
+use crate::edge::edge::GraphVizEdge;
+use crate::export::{edge_style_label, node_style_label};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::style::GraphvizNodeStyle;
+
+
+/**
+ Characters used to draw boxes and edges in the terminal.
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub enum GvTerminalCharset {
+    /// plain ASCII : '+', '-', '|' and 'v' for arrows
+    Ascii,
+    /// unicode box-drawing characters
+    BoxDrawing
+}
+
+
+const UP : u8 = 1;
+const DOWN : u8 = 2;
+const LEFT : u8 = 4;
+const RIGHT : u8 = 8;
+
+#[derive(Clone,Copy)]
+enum Cell {
+    Empty,
+    Lines(u8),
+    Char(char),
+    Arrow
+}
+
+struct Canvas {
+    width : usize,
+    height : usize,
+    cells : Vec<Vec<Cell>>
+}
+
+impl Canvas {
+
+    fn new(width : usize, height : usize) -> Canvas {
+        Canvas{width,height,cells:vec![vec![Cell::Empty;width];height]}
+    }
+
+    fn ensure_width(&mut self, width : usize) {
+        if width > self.width {
+            for row in self.cells.iter_mut() {
+                row.resize(width,Cell::Empty);
+            }
+            self.width = width;
+        }
+    }
+
+    fn add_bits(&mut self, x : usize, y : usize, bits : u8) {
+        let cell = &mut self.cells[y][x];
+        match cell {
+            Cell::Empty => {
+                *cell = Cell::Lines(bits);
+            },
+            Cell::Lines(b) => {
+                *b |= bits;
+            },
+            Cell::Char(_) | Cell::Arrow => {}
+        }
+    }
+
+    fn put_char(&mut self, x : usize, y : usize, c : char) {
+        self.cells[y][x] = Cell::Char(c);
+    }
+
+    fn put_str(&mut self, x : usize, y : usize, s : &str) {
+        for (i,c) in s.chars().enumerate() {
+            self.put_char(x+i,y,c);
+        }
+    }
+
+    fn hline(&mut self, y : usize, x0 : usize, x1 : usize) {
+        let (from,to) = if x0 <= x1 {(x0,x1)} else {(x1,x0)};
+        if from == to {
+            return;
+        }
+        self.add_bits(from,y,RIGHT);
+        for x in (from+1)..to {
+            self.add_bits(x,y,LEFT|RIGHT);
+        }
+        self.add_bits(to,y,LEFT);
+    }
+
+    fn vline(&mut self, x : usize, y0 : usize, y1 : usize) {
+        let (from,to) = if y0 <= y1 {(y0,y1)} else {(y1,y0)};
+        if from == to {
+            return;
+        }
+        self.add_bits(x,from,DOWN);
+        for y in (from+1)..to {
+            self.add_bits(x,y,UP|DOWN);
+        }
+        self.add_bits(x,to,UP);
+    }
+
+    fn rect(&mut self, x : usize, y : usize, w : usize, h : usize) {
+        self.hline(y,x,x+w-1);
+        self.hline(y+h-1,x,x+w-1);
+        self.vline(x,y,y+h-1);
+        self.vline(x+w-1,y,y+h-1);
+    }
+
+    fn blit(&mut self, other : &Canvas, x : usize, y : usize) {
+        for (dy,row) in other.cells.iter().enumerate() {
+            for (dx,cell) in row.iter().enumerate() {
+                match cell {
+                    Cell::Empty => {},
+                    _ => {
+                        self.cells[y+dy][x+dx] = *cell;
+                    }
+                }
+            }
+        }
+    }
+
+    /// whether a label may be written over this cell of an edge track
+    fn is_free_for_label(&self, x : usize, y : usize) -> bool {
+        if x >= self.width {
+            return true;
+        }
+        match self.cells[y][x] {
+            Cell::Empty => true,
+            Cell::Lines(bits) => bits & (UP|DOWN) == 0,
+            Cell::Char(_) | Cell::Arrow => false
+        }
+    }
+
+    fn render(&self, charset : &GvTerminalCharset) -> Vec<String> {
+        self.cells.iter().map(|row| {
+            let line : String = row.iter().map(|cell| {
+                match cell {
+                    Cell::Empty => ' ',
+                    Cell::Char(c) => *c,
+                    Cell::Arrow => arrow_char(charset),
+                    Cell::Lines(bits) => line_char(*bits,charset)
+                }
+            }).collect();
+            line.trim_end().to_string()
+        }).collect()
+    }
+}
+
+fn line_char(bits : u8, charset : &GvTerminalCharset) -> char {
+    let vertical = bits & (UP|DOWN) != 0;
+    let horizontal = bits & (LEFT|RIGHT) != 0;
+    match charset {
+        GvTerminalCharset::Ascii => {
+            match (vertical,horizontal) {
+                (true,false) => '|',
+                (false,true) => '-',
+                _ => '+'
+            }
+        },
+        GvTerminalCharset::BoxDrawing => {
+            if !horizontal {
+                return '│';
+            }
+            if !vertical {
+                return '─';
+            }
+            match (bits & UP != 0, bits & DOWN != 0, bits & LEFT != 0, bits & RIGHT != 0) {
+                (false,true,false,true) => '┌',
+                (false,true,true,false) => '┐',
+                (true,false,false,true) => '└',
+                (true,false,true,false) => '┘',
+                (true,true,false,true) => '├',
+                (true,true,true,false) => '┤',
+                (false,true,true,true) => '┬',
+                (true,false,true,true) => '┴',
+                _ => '┼'
+            }
+        }
+    }
+}
+
+fn arrow_char(charset : &GvTerminalCharset) -> char {
+    match charset {
+        GvTerminalCharset::Ascii => 'v',
+        GvTerminalCharset::BoxDrawing => '▼'
+    }
+}
+
+
+
+
+/// Graphviz labels may contain escaped line breaks ("\n", "\l" or "\r")
+fn label_lines(label : &str) -> Vec<String> {
+    label.replace("\\n","\n")
+        .replace("\\l","\n")
+        .replace("\\r","\n")
+        .split('\n')
+        .map(|line| line.to_string())
+        .collect()
+}
+
+fn node_label(id : &str, style : &GraphvizNodeStyle) -> String {
+    node_style_label(style).cloned().unwrap_or_else(|| id.to_string())
+}
+
+fn edge_label(edge : &GraphVizEdge) -> Option<String> {
+    edge_style_label(edge).cloned()
+}
+
+fn text_width(text : &str) -> usize {
+    text.chars().count()
+}
+
+
+
+
+enum TermItem {
+    Node{lines : Vec<String>},
+    Cluster{title : String, children : Vec<TermItem>}
+}
+
+struct LevelEdge {
+    origin : usize,
+    target : usize,
+    label : Option<String>
+}
+
+struct TermGraph {
+    /// level path -> edges between items of that level
+    level_edges : HashMap<Vec<usize>,Vec<LevelEdge>>
+}
+
+fn collect_items<'a>(items : &mut dyn Iterator<Item=&'a GraphVizGraphItem>,
+                     path : &mut Vec<usize>,
+                     node_paths : &mut HashMap<&'a str,Vec<usize>>,
+                     edges : &mut Vec<&'a GraphVizEdge>) -> Vec<TermItem> {
+    let mut got = vec![];
+    for (index,item) in items.enumerate() {
+        path.push(index);
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                node_paths.insert(&node.id,path.clone());
+                let lines = label_lines(&node_label(&node.id,&node.style));
+                got.push(TermItem::Node{lines});
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                got.push(collect_cluster(cluster,path,node_paths,edges));
+            }
+        }
+        path.pop();
+    }
+    got
+}
+
+fn collect_cluster<'a>(cluster : &'a GraphVizCluster,
+                       path : &mut Vec<usize>,
+                       node_paths : &mut HashMap<&'a str,Vec<usize>>,
+                       edges : &mut Vec<&'a GraphVizEdge>) -> TermItem {
+    edges.extend(cluster.edges.iter());
+    let children = collect_items(&mut cluster.items.iter().map(|item| item.as_ref()),
+                                 path,
+                                 node_paths,
+                                 edges);
+    TermItem::Cluster{title:node_label(&cluster.id,&cluster.style),children}
+}
+
+impl TermGraph {
+
+    fn new(graph : &GraphVizDiGraph) -> (TermGraph,Vec<TermItem>) {
+        // node id -> path of item indices from the root level
+        let mut node_paths = HashMap::new();
+        let mut edges : Vec<&GraphVizEdge> = graph.edges.iter().collect();
+        let items = collect_items(&mut graph.items.iter(),
+                                  &mut vec![],
+                                  &mut node_paths,
+                                  &mut edges);
+        let mut level_edges : HashMap<Vec<usize>,Vec<LevelEdge>> = HashMap::new();
+        for edge in edges {
+            let (origin_path,target_path) = match (node_paths.get(edge.origin_node_id.as_str()),
+                                                   node_paths.get(edge.target_node_id.as_str())) {
+                (Some(o),Some(t)) => (o,t),
+                _ => {continue;}
+            };
+            let mut common = origin_path.iter().zip(target_path.iter())
+                .take_while(|(o,t)| o == t).count();
+            if common == origin_path.len() {
+                // self loop on a node
+                common -= 1;
+            }
+            let lifted = origin_path.len() > common + 1 || target_path.len() > common + 1;
+            let label = if lifted {
+                match edge_label(edge) {
+                    None => Some(format!("{}->{}", edge.origin_node_id, edge.target_node_id)),
+                    Some(lab) => Some(format!("{}->{} {}", edge.origin_node_id, edge.target_node_id, lab))
+                }
+            } else {
+                edge_label(edge)
+            };
+            level_edges.entry(origin_path[..common].to_vec()).or_default().push(
+                LevelEdge{origin:origin_path[common],target:target_path[common],label}
+            );
+        }
+        (TermGraph{level_edges},items)
+    }
+
+    fn layout_item(&self, item : &TermItem, path : &mut Vec<usize>) -> Canvas {
+        match item {
+            TermItem::Node{lines} => {
+                let inner_width = lines.iter().map(|l| text_width(l)).max().unwrap_or(0);
+                let mut canvas = Canvas::new(inner_width + 4, lines.len() + 2);
+                canvas.rect(0,0,canvas.width,canvas.height);
+                for (i,line) in lines.iter().enumerate() {
+                    canvas.put_str(2,1+i,line);
+                }
+                canvas
+            },
+            TermItem::Cluster{title,children} => {
+                let inner = self.layout_level(children,path);
+                let title = format!(" {} ", title);
+                let width = (inner.width + 4).max(text_width(&title) + 4);
+                let height = inner.height.max(1) + 2;
+                let mut canvas = Canvas::new(width,height);
+                canvas.rect(0,0,width,height);
+                canvas.put_str(2,0,&title);
+                canvas.blit(&inner,2,1);
+                canvas
+            }
+        }
+    }
+
+    fn layout_level(&self, items : &[TermItem], path : &mut Vec<usize>) -> Canvas {
+        let blocks : Vec<Canvas> = items.iter().enumerate().map(|(i,item)| {
+            path.push(i);
+            let block = self.layout_item(item,path);
+            path.pop();
+            block
+        }).collect();
+        let no_edges = vec![];
+        let edges = self.level_edges.get(path.as_slice()).unwrap_or(&no_edges);
+        // ***
+        let ranks = rank_items(items.len(),edges);
+        let num_ranks = ranks.iter().max().map(|r| r + 1).unwrap_or(0);
+        let rows = order_ranks(&ranks,num_ranks,edges);
+        // ***
+        let mut x_of = vec![0;items.len()];
+        let mut row_heights = vec![0;num_ranks];
+        let mut content_width = 0;
+        for (rank,row) in rows.iter().enumerate() {
+            let mut x = 0;
+            for item in row {
+                x_of[*item] = x;
+                x += blocks[*item].width + 3;
+                row_heights[rank] = row_heights[rank].max(blocks[*item].height);
+            }
+            content_width = content_width.max(x.saturating_sub(3));
+        }
+        // ***
+        let is_short : Vec<bool> = edges.iter().map(
+            |e| e.origin != e.target && ranks[e.target] == ranks[e.origin] + 1).collect();
+        let mut tracks = vec![0;num_ranks + 1];
+        let mut out_track = vec![0;edges.len()];
+        let mut in_track = vec![0;edges.len()];
+        for (i,edge) in edges.iter().enumerate() {
+            if is_short[i] {
+                in_track[i] = tracks[ranks[edge.target]];
+                tracks[ranks[edge.target]] += 1;
+            } else {
+                out_track[i] = tracks[ranks[edge.origin] + 1];
+                tracks[ranks[edge.origin] + 1] += 1;
+                in_track[i] = tracks[ranks[edge.target]];
+                tracks[ranks[edge.target]] += 1;
+            }
+        }
+        let mut channel_y = vec![0;num_ranks + 1];
+        let mut row_y = vec![0;num_ranks];
+        let mut y = 0;
+        for channel in 0..=num_ranks {
+            channel_y[channel] = y;
+            y += if tracks[channel] > 0 {
+                tracks[channel] + 1
+            } else if channel == 0 || channel == num_ranks {
+                0
+            } else {
+                1
+            };
+            if channel < num_ranks {
+                row_y[channel] = y;
+                y += row_heights[channel];
+            }
+        }
+        let num_lanes = is_short.iter().filter(|s| !**s).count();
+        let width = if num_lanes > 0 {content_width + 1 + 2 * num_lanes} else {content_width};
+        let mut canvas = Canvas::new(width,y);
+        for (i,block) in blocks.iter().enumerate() {
+            canvas.blit(block,x_of[i],row_y[ranks[i]]);
+        }
+        // ***
+        let out_ports = spread_ports(edges.iter().map(|e| e.origin),&x_of,&blocks);
+        let in_ports = spread_ports(edges.iter().map(|e| e.target),&x_of,&blocks);
+        let mut lane = 0;
+        let mut label_spots = vec![];
+        for (i,edge) in edges.iter().enumerate() {
+            let xs = out_ports[i];
+            let ys = row_y[ranks[edge.origin]] + blocks[edge.origin].height - 1;
+            let xt = in_ports[i];
+            let target_channel = ranks[edge.target];
+            let yi = channel_y[target_channel] + in_track[i];
+            let ya = channel_y[target_channel] + tracks[target_channel];
+            if is_short[i] {
+                canvas.vline(xs,ys,yi);
+                canvas.hline(yi,xs,xt);
+            } else {
+                let yo = channel_y[ranks[edge.origin] + 1] + out_track[i];
+                let xl = content_width + 2 + 2 * lane;
+                lane += 1;
+                canvas.vline(xs,ys,yo);
+                canvas.hline(yo,xs,xl);
+                canvas.vline(xl,yo,yi);
+                canvas.hline(yi,xl,xt);
+            }
+            canvas.vline(xt,yi,ya);
+            canvas.add_bits(xt,ya+1,UP);
+            canvas.cells[ya][xt] = Cell::Arrow;
+            if let Some(label) = &edge.label {
+                label_spots.push((xt,yi,label));
+            }
+        }
+        for (xt,yi,label) in label_spots {
+            place_label(&mut canvas,xt,yi,label);
+        }
+        canvas
+    }
+
+}
+
+/// writes a label on the track row of an edge, starting right after its target column
+fn place_label(canvas : &mut Canvas, xt : usize, y : usize, label : &str) {
+    let span = text_width(label) + 2;
+    let mut start = xt + 1;
+    while !(start..start+span).all(|x| canvas.is_free_for_label(x,y)) {
+        start += 1;
+    }
+    canvas.ensure_width(start + span);
+    canvas.put_str(start + 1,y,label);
+}
+
+/// distributes the edge attachment points of each item along its border
+fn spread_ports(ends : impl Iterator<Item=usize>,
+                x_of : &[usize],
+                blocks : &[Canvas]) -> Vec<usize> {
+    let ends : Vec<usize> = ends.collect();
+    let mut counts = vec![0;blocks.len()];
+    for end in &ends {
+        counts[*end] += 1;
+    }
+    let mut seen = vec![0;blocks.len()];
+    ends.iter().map(|end| {
+        let inner = blocks[*end].width - 2;
+        let offset = ((seen[*end] + 1) * inner) / (counts[*end] + 1);
+        seen[*end] += 1;
+        x_of[*end] + 1 + offset.min(inner - 1)
+    }).collect()
+}
+
+/// longest-path layering, ignoring the edges that close a cycle
+fn rank_items(num_items : usize, edges : &[LevelEdge]) -> Vec<usize> {
+    let mut successors : Vec<Vec<usize>> = vec![vec![];num_items];
+    for edge in edges {
+        if edge.origin != edge.target {
+            successors[edge.origin].push(edge.target);
+        }
+    }
+    // 0 : unvisited, 1 : on stack, 2 : done
+    let mut state = vec![0;num_items];
+    let mut post_order = vec![];
+    let mut forward : Vec<Vec<usize>> = vec![vec![];num_items];
+    for root in 0..num_items {
+        if state[root] != 0 {
+            continue;
+        }
+        let mut stack : Vec<(usize,usize)> = vec![(root,0)];
+        state[root] = 1;
+        while let Some((item,next)) = stack.pop() {
+            if next < successors[item].len() {
+                stack.push((item,next + 1));
+                let succ = successors[item][next];
+                match state[succ] {
+                    0 => {
+                        forward[item].push(succ);
+                        state[succ] = 1;
+                        stack.push((succ,0));
+                    },
+                    2 => {
+                        forward[item].push(succ);
+                    },
+                    _ => {}
+                }
+            } else {
+                state[item] = 2;
+                post_order.push(item);
+            }
+        }
+    }
+    let mut ranks = vec![0;num_items];
+    for item in post_order.iter().rev() {
+        for succ in &forward[*item] {
+            ranks[*succ] = ranks[*succ].max(ranks[*item] + 1);
+        }
+    }
+    ranks
+}
+
+/// orders the items of each rank by the mean position of their predecessors
+fn order_ranks(ranks : &[usize], num_ranks : usize, edges : &[LevelEdge]) -> Vec<Vec<usize>> {
+    let mut rows : Vec<Vec<usize>> = vec![vec![];num_ranks];
+    for (item,rank) in ranks.iter().enumerate() {
+        rows[*rank].push(item);
+    }
+    let mut position = vec![0.0;ranks.len()];
+    for (rank,row) in rows.iter_mut().enumerate() {
+        let keys : Vec<f64> = row.iter().map(|item| {
+            let preds : Vec<f64> = edges.iter()
+                .filter(|e| e.target == *item && ranks[e.origin] < rank)
+                .map(|e| position[e.origin]).collect();
+            if preds.is_empty() {
+                position[*item]
+            } else {
+                preds.iter().sum::<f64>() / preds.len() as f64
+            }
+        }).collect();
+        let mut keyed : Vec<(f64,usize)> = keys.into_iter().zip(row.iter().cloned()).collect();
+        keyed.sort_by(|a,b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        *row = keyed.into_iter().map(|(_,item)| item).collect();
+        for (pos,item) in row.iter().enumerate() {
+            position[*item] = pos as f64;
+        }
+    }
+    rows
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Renders the graph as text for display in a terminal.
+     Nodes are laid out in ranks, clusters are outlined and edges are drawn with arrows and labels.
+     Edges that cross a cluster boundary are attached to the outermost distinct clusters
+     and labelled with their actual origin and target.
+     If the drawing does not fit in "max_width" columns, an adjacency listing is returned instead.
+     **/
+    pub fn to_terminal_string(&self,
+                              charset : &GvTerminalCharset,
+                              max_width : usize) -> String {
+        let (term_graph,items) = TermGraph::new(self);
+        let canvas = term_graph.layout_level(&items,&mut vec![]);
+        if canvas.width > max_width {
+            return self.to_adjacency_string();
+        }
+        canvas.render(charset).join("\n")
+    }
+
+    /**
+     Lists every node (with its label and enclosing clusters) followed by its outgoing edges.
+     **/
+    pub fn to_adjacency_string(&self) -> String {
+        let mut nodes : Vec<(String,String,Vec<String>)> = vec![];
+        let mut edges : Vec<&GraphVizEdge> = self.edges.iter().collect();
+        collect_adjacency(&mut self.items.iter(),&mut vec![],&mut nodes,&mut edges);
+        let mut outgoing : HashMap<&str,Vec<&GraphVizEdge>> = HashMap::new();
+        for edge in edges {
+            outgoing.entry(edge.origin_node_id.as_str()).or_default().push(edge);
+        }
+        let mut res = String::new();
+        for (id,label,clusters) in &nodes {
+            res.push_str(id);
+            if label != id {
+                res.push_str(&format!(" \"{}\"",label));
+            }
+            if !clusters.is_empty() {
+                res.push_str(&format!("  in {}",clusters.join("/")));
+            }
+            res.push('\n');
+            for edge in outgoing.get(id.as_str()).into_iter().flatten() {
+                res.push_str(&format!("    -> {}",edge.target_node_id));
+                if let Some(label) = edge_label(edge) {
+                    res.push_str(&format!(" : {}",label));
+                }
+                res.push('\n');
+            }
+        }
+        res.pop();
+        res
+    }
+
+    /**
+     Prints the graph to the standard output using the width given by the "COLUMNS"
+     environment variable (80 if it is not set).
+     **/
+    pub fn print_to_terminal(&self, charset : &GvTerminalCharset) {
+        let width = std::env::var("COLUMNS").ok()
+            .and_then(|c| c.parse::<usize>().ok())
+            .unwrap_or(80);
+        println!("{}", self.to_terminal_string(charset,width));
+    }
+
+}
+
+fn collect_adjacency<'a>(items : &mut dyn Iterator<Item=&'a GraphVizGraphItem>,
+                         clusters : &mut Vec<String>,
+                         nodes : &mut Vec<(String,String,Vec<String>)>,
+                         edges : &mut Vec<&'a GraphVizEdge>) {
+    for item in items {
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                nodes.push((node.id.clone(),node_label(&node.id,&node.style),clusters.clone()));
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                edges.extend(cluster.edges.iter());
+                clusters.push(cluster.id.clone());
+                collect_adjacency(&mut cluster.items.iter().map(|item| item.as_ref()),clusters,nodes,edges);
+                clusters.pop();
+            }
+        }
+    }
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::edge::style::GraphvizEdgeStyleItem;
+    use crate::item::node::node::GraphVizNode;
+    use crate::item::node::style::GraphvizNodeStyleItem;
+    use crate::traits::DotBuildable;
+
+    fn node(id : &str) -> GraphVizGraphItem {
+        GraphVizGraphItem::Node(GraphVizNode::new(id.to_string(),vec![]))
+    }
+
+    fn edge(origin : &str, target : &str, label : Option<&str>) -> GraphVizEdge {
+        let style = label.map(|label| GraphvizEdgeStyleItem::Label(label.to_string())).into_iter().collect();
+        GraphVizEdge::new(origin.to_string(),None,target.to_string(),None,style)
+    }
+
+    fn clustered() -> GraphVizDiGraph {
+        let mut graph = GraphVizDiGraph::new(vec![]);
+        graph.add_node(GraphVizNode::new("a".to_string(),vec![GraphvizNodeStyleItem::Label("A".to_string())]));
+        graph.add_cluster(GraphVizCluster::new("c1".to_string(),
+                                               vec![GraphvizNodeStyleItem::Label("C".to_string())],
+                                               vec![Box::new(node("b")),Box::new(node("c"))],
+                                               vec![edge("b","c",None)]));
+        graph.add_edge(edge("a","b",Some("x")));
+        graph.add_edge(edge("a","c",None));
+        graph
+    }
+
+    #[test]
+    fn ascii_clusters_and_lifted_edges() {
+        let expected = [
+            "+---+",
+            "| A |",
+            "+-+++",
+            "  ++ a->b x",
+            "   +-+ a->c",
+            "   v v",
+            "+- C +--+",
+            "| +---+ |",
+            "| | b | |",
+            "| +-+-+ |",
+            "|   |   |",
+            "|   v   |",
+            "| +-+-+ |",
+            "| | c | |",
+            "| +---+ |",
+            "+-------+"
+        ];
+        assert_eq!(clustered().to_terminal_string(&GvTerminalCharset::Ascii,80),expected.join("\n"));
+    }
+
+    #[test]
+    fn box_drawing_clusters_and_lifted_edges() {
+        let expected = [
+            "┌───┐",
+            "│ A │",
+            "└─┬┬┘",
+            "  └┤ a->b x",
+            "   ├─┐ a->c",
+            "   ▼ ▼",
+            "┌─ C ┴──┐",
+            "│ ┌───┐ │",
+            "│ │ b │ │",
+            "│ └─┬─┘ │",
+            "│   │   │",
+            "│   ▼   │",
+            "│ ┌─┴─┐ │",
+            "│ │ c │ │",
+            "│ └───┘ │",
+            "└───────┘"
+        ];
+        assert_eq!(clustered().to_terminal_string(&GvTerminalCharset::BoxDrawing,80),expected.join("\n"));
+    }
+
+    #[test]
+    fn ascii_long_edge_lane() {
+        let mut graph = GraphVizDiGraph::new(vec![]);
+        for id in ["a","b","c"] {
+            graph.add_item(node(id));
+        }
+        graph.add_edge(edge("a","b",None));
+        graph.add_edge(edge("b","c",None));
+        graph.add_edge(edge("a","c",Some("skip")));
+        let expected = [
+            "+---+",
+            "| a |",
+            "+-+++",
+            "  ||",
+            "  |+---+",
+            "  v    |",
+            "+-+-+  |",
+            "| b |  |",
+            "+-+-+  |",
+            "  |    |",
+            "  |+---+ skip",
+            "  vv",
+            "+-+++",
+            "| c |",
+            "+---+"
+        ];
+        assert_eq!(graph.to_terminal_string(&GvTerminalCharset::Ascii,80),expected.join("\n"));
+    }
+
+    #[test]
+    fn narrow_width_falls_back_to_adjacency() {
+        let expected = [
+            "a \"A\"",
+            "    -> b : x",
+            "    -> c",
+            "b  in c1",
+            "    -> c",
+            "c  in c1"
+        ];
+        let graph = clustered();
+        assert_eq!(graph.to_terminal_string(&GvTerminalCharset::BoxDrawing,8),expected.join("\n"));
+        assert_eq!(graph.to_adjacency_string(),expected.join("\n"));
+        assert_ne!(graph.to_terminal_string(&GvTerminalCharset::Ascii,20),expected.join("\n"));
+    }
+
+}
@@ -0,0 +1,545 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::{GraphvizEdgeStyleItem, GvEdgeLineStyle};
+use crate::export::{GvExportElement, GvExportWarning};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::node::style::{GraphvizNodeStyle, GraphvizNodeStyleItem, GvNodeShape};
+use crate::traits::DotTranslatable;
+
+
+/**
+ Data attached to the elements of a graph that has no equivalent in its style,
+ as a map from attribute names to values.
+ Edges are numbered in the order of the edges of the graph
+ followed by those of its clusters (in declaration order, recursively).
+ **/
+#[derive(Eq,PartialEq,Clone,Debug,Default)]
+pub struct GvUserData {
+    pub nodes : HashMap<String,BTreeMap<String,String>>,
+    pub clusters : HashMap<String,BTreeMap<String,String>>,
+    pub edges : HashMap<usize,BTreeMap<String,String>>
+}
+
+impl GvUserData {
+
+    /// names of all the attributes used by a kind of element, in alphabetical order
+    pub(crate) fn attribute_names<K>(data : &HashMap<K,BTreeMap<String,String>>) -> Vec<&String> {
+        let mut names : Vec<&String> = data.values().flat_map(|attrs| attrs.keys()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+}
+
+/**
+ Error raised when a graph cannot be read from a file.
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub enum GvImportError {
+    /// the source is not well-formed XML
+    Syntax(String),
+    /// the source is well-formed but does not describe a graph
+    Invalid(String)
+}
+
+impl fmt::Display for GvImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GvImportError::Syntax(msg) => write!(f, "syntax error : {}", msg),
+            GvImportError::Invalid(msg) => write!(f, "invalid graph : {}", msg)
+        }
+    }
+}
+
+impl std::error::Error for GvImportError {}
+
+
+
+
+/**
+ Name of each declared attribute (by id) and whether it is the style attribute among "known" with this name,
+ from the id, domain ("node", "edge", "graph" or "all") and name of the declarations :
+ the first attribute declared for a domain with the name of a style attribute gives it, the following ones being user data.
+ **/
+pub(crate) fn declared_attributes<'a>(declarations : impl Iterator<Item=(&'a str,&'a str,&'a str)>,
+                                      known : &[&str]) -> HashMap<String,(String,bool)> {
+    let mut claimed : Vec<(&str,&str)> = vec![];
+    let mut attributes = HashMap::new();
+    for (id,domain,name) in declarations {
+        let is_style = known.contains(&name) && !claimed.iter().any(|(claimed_domain,claimed_name)| {
+            *claimed_name == name && (*claimed_domain == domain || *claimed_domain == "all" || domain == "all")
+        });
+        if is_style {
+            claimed.push((domain,name));
+        }
+        attributes.insert(id.to_string(),(name.to_string(),is_style));
+    }
+    attributes
+}
+
+pub(crate) fn xml_escape(text : &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&apos;"),
+            _ => res.push(c)
+        }
+    }
+    res
+}
+
+fn xml_unescape(text : &str) -> Result<String,GvImportError> {
+    let mut res = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        res.push_str(&rest[..start]);
+        let end = rest[start..].find(';')
+            .ok_or_else(|| GvImportError::Syntax(format!("unterminated entity in '{}'", text)))?;
+        let entity = &rest[start+1..start+end];
+        match entity {
+            "amp" => res.push('&'),
+            "lt" => res.push('<'),
+            "gt" => res.push('>'),
+            "quot" => res.push('"'),
+            "apos" => res.push('\''),
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex,16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse::<u32>().ok()
+                } else {
+                    None
+                };
+                match code.and_then(char::from_u32) {
+                    Some(c) => res.push(c),
+                    None => {
+                        return Err(GvImportError::Syntax(format!("unknown entity '&{};'", entity)));
+                    }
+                }
+            }
+        }
+        rest = &rest[start+end+1..];
+    }
+    res.push_str(rest);
+    Ok(res)
+}
+
+/**
+ An element of an XML tree, with the concatenation of its text content.
+ **/
+pub(crate) struct XmlElement {
+    pub name : String,
+    pub attributes : Vec<(String,String)>,
+    pub children : Vec<XmlElement>,
+    pub text : String
+}
+
+impl XmlElement {
+
+    /// name of the element without its namespace prefix
+    pub fn local_name(&self) -> &str {
+        match self.name.rfind(':') {
+            None => &self.name,
+            Some(pos) => &self.name[pos+1..]
+        }
+    }
+
+    pub fn attribute(&self, name : &str) -> Option<&str> {
+        self.attributes.iter().find(|(n,_)| n == name).map(|(_,v)| v.as_str())
+    }
+
+    pub fn required_attribute(&self, name : &str) -> Result<&str,GvImportError> {
+        self.attribute(name).ok_or_else(
+            || GvImportError::Invalid(format!("'{}' element without '{}' attribute", self.name, name)))
+    }
+
+    pub fn children_named<'a>(&'a self, local_name : &'a str) -> impl Iterator<Item=&'a XmlElement> {
+        self.children.iter().filter(move |child| child.local_name() == local_name)
+    }
+
+}
+
+struct XmlParser<'a> {
+    source : &'a str,
+    pos : usize
+}
+
+impl<'a> XmlParser<'a> {
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn error(&self, msg : &str) -> GvImportError {
+        GvImportError::Syntax(format!("{} at byte {}", msg, self.pos))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn skip_past(&mut self, end : &str) -> Result<(),GvImportError> {
+        match self.rest().find(end) {
+            None => Err(self.error(&format!("missing '{}'", end))),
+            Some(offset) => {
+                self.pos += offset + end.len();
+                Ok(())
+            }
+        }
+    }
+
+    /// skips comments, processing instructions and declarations, returns whether something was skipped
+    fn skip_misc(&mut self) -> Result<bool,GvImportError> {
+        if self.rest().starts_with("<!--") {
+            self.skip_past("-->")?;
+        } else if self.rest().starts_with("<?") {
+            self.skip_past("?>")?;
+        } else if self.rest().starts_with("<!") && !self.rest().starts_with("<![CDATA[") {
+            self.skip_past(">")?;
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn read_name(&mut self) -> Result<String,GvImportError> {
+        let rest = self.rest();
+        let len = rest.find(|c : char| c.is_whitespace() || c == '/' || c == '>' || c == '=')
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn expect(&mut self, token : &str) -> Result<(),GvImportError> {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", token)))
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<XmlElement,GvImportError> {
+        loop {
+            self.skip_whitespace();
+            if !self.skip_misc()? {
+                break;
+            }
+        }
+        self.parse_element()
+    }
+
+    fn parse_element(&mut self) -> Result<XmlElement,GvImportError> {
+        self.expect("<")?;
+        let name = self.read_name()?;
+        let mut element = XmlElement{name,attributes:vec![],children:vec![],text:String::new()};
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let attr_name = self.read_name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(q) if q == '"' || q == '\'' => q,
+                _ => {
+                    return Err(self.error("expected a quoted attribute value"));
+                }
+            };
+            self.pos += 1;
+            let len = self.rest().find(quote).ok_or_else(|| self.error("unterminated attribute value"))?;
+            let value = xml_unescape(&self.rest()[..len])?;
+            self.pos += len + 1;
+            element.attributes.push((attr_name,value));
+        }
+        loop {
+            if self.rest().is_empty() {
+                return Err(self.error(&format!("unclosed element '{}'", element.name)));
+            }
+            if self.rest().starts_with("</") {
+                self.pos += 2;
+                let closing = self.read_name()?;
+                if closing != element.name {
+                    return Err(self.error(&format!("'{}' closed by '{}'", element.name, closing)));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(element);
+            }
+            if self.rest().starts_with("<![CDATA[") {
+                self.pos += 9;
+                let len = self.rest().find("]]>").ok_or_else(|| self.error("unterminated CDATA section"))?;
+                element.text.push_str(&self.rest()[..len]);
+                self.pos += len + 3;
+                continue;
+            }
+            if self.skip_misc()? {
+                continue;
+            }
+            if self.rest().starts_with('<') {
+                let child = self.parse_element()?;
+                element.children.push(child);
+                continue;
+            }
+            let len = self.rest().find('<').unwrap_or(self.rest().len());
+            let text = xml_unescape(&self.rest()[..len])?;
+            element.text.push_str(&text);
+            self.pos += len;
+        }
+    }
+
+}
+
+pub(crate) fn parse_xml(source : &str) -> Result<XmlElement,GvImportError> {
+    XmlParser{source,pos:0}.parse_document()
+}
+
+
+
+
+/// attributes of a node or cluster style that GraphML and GEXF files preserve
+pub(crate) fn node_style_attributes(element : &GvExportElement,
+                                    style : &GraphvizNodeStyle,
+                                    warnings : &mut Vec<GvExportWarning>) -> Vec<(&'static str,String)> {
+    let mut attributes = vec![];
+    for item in style {
+        match item {
+            GraphvizNodeStyleItem::Label(label) => {
+                attributes.push(("label",label.clone()));
+            },
+            GraphvizNodeStyleItem::Shape(shape) => {
+                attributes.push(("shape",shape.to_dot_string()));
+            },
+            GraphvizNodeStyleItem::Color(color) => {
+                attributes.push(("color",color.to_dot_string()));
+            },
+            GraphvizNodeStyleItem::FillColor(color) => {
+                attributes.push(("fillcolor",color.to_dot_string()));
+            },
+            GraphvizNodeStyleItem::FontColor(color) => {
+                attributes.push(("fontcolor",color.to_dot_string()));
+            },
+            _ => {
+                warnings.push(GvExportWarning::new(element.clone(),item.to_dot_string()));
+            }
+        }
+    }
+    attributes
+}
+
+/// attributes of an edge that GraphML and GEXF files preserve
+pub(crate) fn edge_attributes(edge : &GraphVizEdge,
+                              warnings : &mut Vec<GvExportWarning>) -> Vec<(&'static str,String)> {
+    let mut attributes = vec![];
+    for item in &edge.style {
+        match item {
+            GraphvizEdgeStyleItem::Label(label) => {
+                attributes.push(("label",label.clone()));
+            },
+            GraphvizEdgeStyleItem::LineStyle(line) => {
+                attributes.push(("linestyle",line.to_dot_string()));
+            },
+            GraphvizEdgeStyleItem::Color(color) => {
+                attributes.push(("color",color.to_dot_string()));
+            },
+            GraphvizEdgeStyleItem::FontColor(color) => {
+                attributes.push(("fontcolor",color.to_dot_string()));
+            },
+            GraphvizEdgeStyleItem::PenWidth(width) => {
+                attributes.push(("penwidth",width.to_string()));
+            },
+            _ => {
+                warnings.push(GvExportWarning::new(
+                    GvExportElement::Edge(edge.origin_node_id.clone(),edge.target_node_id.clone()),
+                    item.to_dot_string()));
+            }
+        }
+    }
+    if let Some(cluster_id) = &edge.origin_cluster {
+        attributes.push(("ltail",cluster_id.clone()));
+    }
+    if let Some(cluster_id) = &edge.target_cluster {
+        attributes.push(("lhead",cluster_id.clone()));
+    }
+    attributes
+}
+
+/// rebuilds a node or cluster style, the attributes that are not part of it go into the user data
+pub(crate) fn node_style_from_attributes(attributes : Vec<(String,String)>,
+                                         user_data : &mut BTreeMap<String,String>) -> GraphvizNodeStyle {
+    let mut style = vec![];
+    for (name,value) in attributes {
+        let item = match name.as_str() {
+            "label" => Some(GraphvizNodeStyleItem::Label(value.clone())),
+            "shape" => GvNodeShape::from_str(&value).ok().map(GraphvizNodeStyleItem::Shape),
+            "color" => GraphvizColor::from_str(&value).ok().map(GraphvizNodeStyleItem::Color),
+            "fillcolor" => GraphvizColor::from_str(&value).ok().map(GraphvizNodeStyleItem::FillColor),
+            "fontcolor" => GraphvizColor::from_str(&value).ok().map(GraphvizNodeStyleItem::FontColor),
+            _ => None
+        };
+        match item {
+            Some(item) => style.push(item),
+            None => {
+                user_data.insert(name,value);
+            }
+        }
+    }
+    style
+}
+
+/// rebuilds an edge, the attributes that are not part of its style go into the user data
+pub(crate) fn edge_from_attributes(origin : String,
+                                   target : String,
+                                   attributes : Vec<(String,String)>,
+                                   user_data : &mut BTreeMap<String,String>) -> GraphVizEdge {
+    let mut edge = GraphVizEdge::new(origin,None,target,None,vec![]);
+    for (name,value) in attributes {
+        let item = match name.as_str() {
+            "label" => Some(GraphvizEdgeStyleItem::Label(value.clone())),
+            "linestyle" => GvEdgeLineStyle::from_str(&value).ok().map(GraphvizEdgeStyleItem::LineStyle),
+            "color" => GraphvizColor::from_str(&value).ok().map(GraphvizEdgeStyleItem::Color),
+            "fontcolor" => GraphvizColor::from_str(&value).ok().map(GraphvizEdgeStyleItem::FontColor),
+            "penwidth" => value.parse().ok().map(GraphvizEdgeStyleItem::PenWidth),
+            "ltail" => {
+                edge.origin_cluster = Some(value);
+                continue;
+            },
+            "lhead" => {
+                edge.target_cluster = Some(value);
+                continue;
+            },
+            _ => None
+        };
+        match item {
+            Some(item) => edge.style.push(item),
+            None => {
+                user_data.insert(name,value);
+            }
+        }
+    }
+    edge
+}
+
+
+/**
+ Adds the edges read from a file to the graph, each in the cluster in which it was declared (if it exists),
+ and numbers their user data in the order of "GraphVizDiGraph::all_edges".
+ **/
+pub(crate) fn place_edges(graph : &mut GraphVizDiGraph,
+                          mut edges : Vec<(Option<String>,GraphVizEdge,BTreeMap<String,String>)>,
+                          user_data : &mut GvUserData) {
+    let ranks : HashMap<String,usize> = graph.all_clusters().enumerate()
+        .map(|(index,cluster)| (cluster.id.clone(),index + 1)).collect();
+    // the sort is stable, hence edges keep their order within each cluster
+    edges.sort_by_key(|(cluster,_,_)| cluster.as_ref().and_then(|id| ranks.get(id)).cloned().unwrap_or(0));
+    for (index,(cluster,edge,data)) in edges.into_iter().enumerate() {
+        if !data.is_empty() {
+            user_data.edges.insert(index,data);
+        }
+        match cluster.and_then(|id| graph.find_cluster_mut(&id)) {
+            None => graph.edges.push(edge),
+            Some(cluster) => cluster.edges.push(edge)
+        }
+    }
+}
+
+
+
+
+#[cfg(test)]
+pub(crate) mod tests {
+
+    use super::*;
+
+    /// nested clusters, edges declared in clusters, text to escape and user data named like style attributes
+    pub(crate) fn sample() -> (GraphVizDiGraph,GvUserData) {
+        let graph = crate::digraph!{
+            a [label = "<a> & \"b\" 'c'", shape = Rectangle, color = red];
+            subgraph c1 {
+                label = "C & 1";
+                fillcolor = lightblue;
+                b [fillcolor = green, fontcolor = blue];
+                subgraph c2 {
+                    d;
+                    d -> d [style = Dashed];
+                }
+                b -> d [label = "b->d", color = red];
+            }
+            a -> b [label = "x < y", fontcolor = red, penwidth = 3];
+            a -> d [lhead = c2, ltail = c1];
+        };
+        let mut user_data = GvUserData::default();
+        user_data.nodes.insert("a".to_string(),BTreeMap::from([
+            ("label".to_string(),"user label".to_string()),
+            ("weight".to_string(),"3 < 4 & \"5\"".to_string())
+        ]));
+        user_data.clusters.insert("c2".to_string(),BTreeMap::from([("shape".to_string(),"box".to_string())]));
+        user_data.edges.insert(2,BTreeMap::from([("color".to_string(),"not a style".to_string())]));
+        user_data.edges.insert(3,BTreeMap::from([("weight".to_string(),"2".to_string())]));
+        (graph,user_data)
+    }
+
+    #[test]
+    fn parse_elements() {
+        let source = "<?xml version=\"1.0\"?>\n<!-- comment -->\n<a x='1' y=\"&lt;&#65;&#x42;&gt;\"><b/>t&amp;<![CDATA[<c>]]><!-- c --><ns:d></ns:d></a>";
+        let root = parse_xml(source).unwrap();
+        assert_eq!(root.name,"a");
+        assert_eq!(root.attribute("x"),Some("1"));
+        assert_eq!(root.attribute("y"),Some("<AB>"));
+        assert_eq!(root.text,"t&<c>");
+        let names : Vec<&str> = root.children.iter().map(|child| child.local_name()).collect();
+        assert_eq!(names,vec!["b","d"]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(parse_xml("<a><b></a>"),Err(GvImportError::Syntax(_))));
+        assert!(matches!(parse_xml("<a>"),Err(GvImportError::Syntax(_))));
+        assert!(matches!(parse_xml("<a x='&unknown;'/>"),Err(GvImportError::Syntax(_))));
+    }
+
+    #[test]
+    fn escape_round_trip() {
+        let text = "<a> & \"b\" 'c'";
+        assert_eq!(xml_escape(text),"&lt;a&gt; &amp; &quot;b&quot; &apos;c&apos;");
+        assert_eq!(xml_unescape(&xml_escape(text)).unwrap(),text);
+    }
+
+}
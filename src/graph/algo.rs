@@ -0,0 +1,461 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use crate::builder::node::set_node_style_item;
+use crate::builder::set_style_item;
+use crate::colors::GraphvizColor;
+use crate::edge::style::GraphvizEdgeStyleItem;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::node::style::GraphvizNodeStyleItem;
+
+
+/**
+ Style applied to the nodes and edges highlighted by the "highlight_..." methods of "GraphVizDiGraph".
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvHighlight {
+    pub color : GraphvizColor,
+    pub pen_width : u32
+}
+
+impl GvHighlight {
+    pub fn new(color : GraphvizColor, pen_width : u32) -> GvHighlight {
+        GvHighlight{color,pen_width}
+    }
+}
+
+
+/**
+ The nodes of a graph, whatever the clusters in which they are, as indices :
+ declared nodes first (in depth-first order) and then the nodes which are only referred to by edges.
+ **/
+pub(crate) struct Adjacency<'a> {
+    pub(crate) ids : Vec<&'a str>,
+    index : HashMap<&'a str,usize>,
+    pub(crate) successors : Vec<Vec<usize>>
+}
+
+impl<'a> Adjacency<'a> {
+
+    pub(crate) fn new(graph : &'a GraphVizDiGraph) -> Adjacency<'a> {
+        let mut adjacency = Adjacency{ids:vec![],index:HashMap::new(),successors:vec![]};
+        for node in graph.all_nodes() {
+            adjacency.add(&node.id);
+        }
+        for edge in graph.all_edges() {
+            let origin = adjacency.add(&edge.origin_node_id);
+            let target = adjacency.add(&edge.target_node_id);
+            adjacency.successors[origin].push(target);
+        }
+        adjacency
+    }
+
+    fn add(&mut self, id : &'a str) -> usize {
+        match self.index.get(id) {
+            Some(index) => *index,
+            None => {
+                self.ids.push(id);
+                self.successors.push(vec![]);
+                self.index.insert(id,self.ids.len() - 1);
+                self.ids.len() - 1
+            }
+        }
+    }
+
+    fn to_ids(&self, indices : &[usize]) -> Vec<String> {
+        indices.iter().map(|index| self.ids[*index].to_string()).collect()
+    }
+
+    /// depth-first search, returning the nodes of the first cycle which is found
+    fn find_cycle(&self) -> Option<Vec<usize>> {
+        // 0 : not visited, 1 : on the stack, 2 : done
+        let mut state = vec![0u8;self.ids.len()];
+        for root in 0..self.ids.len() {
+            if state[root] != 0 {
+                continue;
+            }
+            let mut stack : Vec<(usize,usize)> = vec![(root,0)];
+            state[root] = 1;
+            while let Some((node,next)) = stack.last_mut() {
+                let node = *node;
+                match self.successors[node].get(*next) {
+                    None => {
+                        state[node] = 2;
+                        stack.pop();
+                    },
+                    Some(successor) => {
+                        *next += 1;
+                        match state[*successor] {
+                            0 => {
+                                state[*successor] = 1;
+                                stack.push((*successor,0));
+                            },
+                            1 => {
+                                let start = stack.iter().position(|(on_stack,_)| on_stack == successor).unwrap_or(0);
+                                return Some(stack[start..].iter().map(|(on_stack,_)| *on_stack).collect());
+                            },
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Tarjan's algorithm, components being returned in reverse topological order
+    pub(crate) fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let count = self.ids.len();
+        let mut order = vec![usize::MAX;count];
+        let mut low = vec![0;count];
+        let mut on_stack = vec![false;count];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut counter = 0;
+        for root in 0..count {
+            if order[root] != usize::MAX {
+                continue;
+            }
+            let mut calls : Vec<(usize,usize)> = vec![(root,0)];
+            order[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            while let Some((node,next)) = calls.last_mut() {
+                let node = *node;
+                match self.successors[node].get(*next) {
+                    Some(&successor) => {
+                        *next += 1;
+                        if order[successor] == usize::MAX {
+                            order[successor] = counter;
+                            low[successor] = counter;
+                            counter += 1;
+                            stack.push(successor);
+                            on_stack[successor] = true;
+                            calls.push((successor,0));
+                        } else if on_stack[successor] {
+                            low[node] = low[node].min(order[successor]);
+                        }
+                    },
+                    None => {
+                        calls.pop();
+                        if let Some((parent,_)) = calls.last() {
+                            low[*parent] = low[*parent].min(low[node]);
+                        }
+                        if low[node] == order[node] {
+                            let mut component = vec![];
+                            while let Some(member) = stack.pop() {
+                                on_stack[member] = false;
+                                component.push(member);
+                                if member == node {
+                                    break;
+                                }
+                            }
+                            component.sort_unstable();
+                            components.push(component);
+                        }
+                    }
+                }
+            }
+        }
+        components
+    }
+
+}
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     A cycle of the graph, if any, as the ids of its nodes in order
+     (there is an edge from each node to the next one and from the last one to the first one).
+     Nodes are considered whatever the clusters in which they are.
+     **/
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        let adjacency = Adjacency::new(self);
+        adjacency.find_cycle().map(|cycle| adjacency.to_ids(&cycle))
+    }
+
+    /**
+     Ids of all the nodes such that the origin of every edge comes before its target,
+     nodes being otherwise taken in their order of declaration.
+     Fails with a cycle (as given by "find_cycle") if there is none.
+     **/
+    pub fn topological_sort(&self) -> Result<Vec<String>,Vec<String>> {
+        let adjacency = Adjacency::new(self);
+        let mut in_degrees = vec![0;adjacency.ids.len()];
+        for successors in &adjacency.successors {
+            for successor in successors {
+                in_degrees[*successor] += 1;
+            }
+        }
+        let mut ready : BinaryHeap<Reverse<usize>> = (0..adjacency.ids.len())
+            .filter(|node| in_degrees[*node] == 0)
+            .map(Reverse)
+            .collect();
+        let mut sorted = vec![];
+        while let Some(Reverse(node)) = ready.pop() {
+            sorted.push(node);
+            for successor in &adjacency.successors[node] {
+                in_degrees[*successor] -= 1;
+                if in_degrees[*successor] == 0 {
+                    ready.push(Reverse(*successor));
+                }
+            }
+        }
+        if sorted.len() == adjacency.ids.len() {
+            Ok(adjacency.to_ids(&sorted))
+        } else {
+            Err(adjacency.find_cycle().map(|cycle| adjacency.to_ids(&cycle)).unwrap_or_default())
+        }
+    }
+
+    /**
+     Strongly connected components of the graph, in topological order
+     (no edge goes from a component to a previous one),
+     the ids of the nodes of each component being in their order of declaration.
+     **/
+    pub fn strongly_connected_components(&self) -> Vec<Vec<String>> {
+        let adjacency = Adjacency::new(self);
+        let mut components = adjacency.strongly_connected_components();
+        components.reverse();
+        components.iter().map(|component| adjacency.to_ids(component)).collect()
+    }
+
+    /**
+     Ids of the nodes of a path with the fewest edges from a node to another (both included), if any.
+     **/
+    pub fn shortest_path(&self, from : &str, to : &str) -> Option<Vec<String>> {
+        let adjacency = Adjacency::new(self);
+        let start = *adjacency.index.get(from)?;
+        let end = *adjacency.index.get(to)?;
+        let mut previous : Vec<Option<usize>> = vec![None;adjacency.ids.len()];
+        let mut visited = vec![false;adjacency.ids.len()];
+        visited[start] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            if node == end {
+                let mut path = vec![end];
+                while let Some(before) = previous[*path.last().unwrap()] {
+                    path.push(before);
+                }
+                path.reverse();
+                return Some(adjacency.to_ids(&path));
+            }
+            for successor in &adjacency.successors[node] {
+                if !visited[*successor] {
+                    visited[*successor] = true;
+                    previous[*successor] = Some(node);
+                    queue.push_back(*successor);
+                }
+            }
+        }
+        None
+    }
+
+    /// applies the highlight (color and pen width) to the nodes
+    pub fn highlight_nodes<S : AsRef<str>>(&mut self, ids : &[S], highlight : &GvHighlight) {
+        for id in ids {
+            if let Some(node) = self.find_node_mut(id.as_ref()) {
+                set_node_style_item(&mut node.style,GraphvizNodeStyleItem::Color(highlight.color.clone()));
+                set_node_style_item(&mut node.style,GraphvizNodeStyleItem::PenWidth(highlight.pen_width));
+            }
+        }
+    }
+
+    /// applies the highlight (color and pen width) to all the edges from a node to another which are given as pairs of ids
+    pub fn highlight_edges<S : AsRef<str>>(&mut self, pairs : &[(S,S)], highlight : &GvHighlight) {
+        let pairs : HashSet<(&str,&str)> = pairs.iter().map(|(origin,target)| (origin.as_ref(),target.as_ref())).collect();
+        self.for_each_edge_mut(|edge| {
+            if pairs.contains(&(edge.origin_node_id.as_str(),edge.target_node_id.as_str())) {
+                set_style_item(&mut edge.style,GraphvizEdgeStyleItem::Color(highlight.color.clone()));
+                set_style_item(&mut edge.style,GraphvizEdgeStyleItem::PenWidth(highlight.pen_width));
+            }
+        });
+    }
+
+    /// highlights the nodes of the path and the edges from each one to the next one
+    pub fn highlight_path<S : AsRef<str>>(&mut self, path : &[S], highlight : &GvHighlight) {
+        self.highlight_nodes(path,highlight);
+        let pairs : Vec<(&str,&str)> = path.windows(2).map(|pair| (pair[0].as_ref(),pair[1].as_ref())).collect();
+        self.highlight_edges(&pairs,highlight);
+    }
+
+    /// highlights the nodes of the cycle and the edges from each one to the next one (and from the last one to the first one)
+    pub fn highlight_cycle<S : AsRef<str>>(&mut self, cycle : &[S], highlight : &GvHighlight) {
+        self.highlight_path(cycle,highlight);
+        if let (Some(first),Some(last)) = (cycle.first(),cycle.last()) {
+            self.highlight_edges(&[(last.as_ref(),first.as_ref())],highlight);
+        }
+    }
+
+    /**
+     Highlights the nodes of each component and the edges between them, with the colors of the palette in turn.
+     Components made of a single node without a loop are left as they are,
+     so that the strongly connected components of a graph can be given directly.
+     **/
+    pub fn highlight_components(&mut self, components : &[Vec<String>], palette : &[GraphvizColor], pen_width : u32) {
+        if palette.is_empty() {
+            return;
+        }
+        let loops : HashSet<String> = self.all_edges()
+            .filter(|edge| edge.origin_node_id == edge.target_node_id)
+            .map(|edge| edge.origin_node_id.clone())
+            .collect();
+        let non_trivial = components.iter().filter(|component| {
+            component.len() > 1 || component.first().is_some_and(|id| loops.contains(id))
+        });
+        let mut colors : HashMap<&str,(usize,&GraphvizColor)> = HashMap::new();
+        for (index,(component,color)) in non_trivial.zip(palette.iter().cycle()).enumerate() {
+            for id in component {
+                colors.insert(id,(index,color));
+            }
+        }
+        for (id,(_,color)) in &colors {
+            if let Some(node) = self.find_node_mut(id) {
+                set_node_style_item(&mut node.style,GraphvizNodeStyleItem::Color((*color).clone()));
+                set_node_style_item(&mut node.style,GraphvizNodeStyleItem::PenWidth(pen_width));
+            }
+        }
+        self.for_each_edge_mut(|edge| {
+            let origin = colors.get(edge.origin_node_id.as_str());
+            let target = colors.get(edge.target_node_id.as_str());
+            if let (Some((origin_component,color)),Some((target_component,_))) = (origin,target) {
+                if origin_component == target_component {
+                    set_style_item(&mut edge.style,GraphvizEdgeStyleItem::Color((*color).clone()));
+                    set_style_item(&mut edge.style,GraphvizEdgeStyleItem::PenWidth(pen_width));
+                }
+            }
+        });
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::traits::DotTranslatable;
+
+    fn strings(ids : &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn find_cycle() {
+        let acyclic = crate::digraph!{ a; b; c; a -> b -> c; a -> c; };
+        assert_eq!(acyclic.find_cycle(),None);
+        let cyclic = crate::digraph!{
+            a; b; c; d;
+            subgraph c1 { c; }
+            d -> a -> b -> c -> a;
+        };
+        assert_eq!(cyclic.find_cycle(),Some(strings(&["a","b","c"])));
+        let self_loop = crate::digraph!{ a -> b -> b; };
+        assert_eq!(self_loop.find_cycle(),Some(strings(&["b"])));
+    }
+
+    #[test]
+    fn topological_sort() {
+        // the ready node declared first comes first, "f" being only referred to by an edge
+        let diamond = crate::digraph!{
+            d; c; b; a; e;
+            a -> b -> d;
+            a -> c -> d;
+            e -> f;
+        };
+        assert_eq!(diamond.topological_sort(),Ok(strings(&["a","c","b","d","e","f"])));
+        let cyclic = crate::digraph!{ a; b; c; a -> b -> c -> b; };
+        assert_eq!(cyclic.topological_sort(),Err(strings(&["b","c"])));
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let graph = crate::digraph!{
+            a; b; c; d; e;
+            a -> b -> a;
+            b -> c -> d -> c;
+            d -> e;
+            e -> e;
+        };
+        assert_eq!(graph.strongly_connected_components(),
+                   vec![strings(&["a","b"]),strings(&["c","d"]),strings(&["e"])]);
+        let chain = crate::digraph!{ c; b; a; a -> b -> c; };
+        assert_eq!(chain.strongly_connected_components(),
+                   vec![strings(&["a"]),strings(&["b"]),strings(&["c"])]);
+    }
+
+    #[test]
+    fn shortest_path() {
+        let graph = crate::digraph!{
+            a -> b -> c -> d;
+            a -> c;
+            c -> e -> d;
+        };
+        assert_eq!(graph.shortest_path("a","d"),Some(strings(&["a","c","d"])));
+        assert_eq!(graph.shortest_path("b","e"),Some(strings(&["b","c","e"])));
+        assert_eq!(graph.shortest_path("a","a"),Some(strings(&["a"])));
+        assert_eq!(graph.shortest_path("d","a"),None);
+        assert_eq!(graph.shortest_path("a","z"),None);
+    }
+
+    #[test]
+    fn highlights() {
+        let mut graph = crate::digraph!{ a; b; c; a -> b -> c -> a; a -> c; };
+        let cycle = graph.find_cycle().unwrap();
+        graph.highlight_cycle(&cycle,&GvHighlight::new(GraphvizColor::red,3));
+        let expected = crate::digraph!{
+            a [color = red, penwidth = 3];
+            b [color = red, penwidth = 3];
+            c [color = red, penwidth = 3];
+            a -> b [color = red, penwidth = 3];
+            b -> c [color = red, penwidth = 3];
+            c -> a [color = red, penwidth = 3];
+            a -> c;
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+        // ***
+        let mut graph = crate::digraph!{ a; b; c; d; a -> b -> a; b -> c; d -> d; };
+        let components = graph.strongly_connected_components();
+        assert_eq!(components,vec![strings(&["d"]),strings(&["a","b"]),strings(&["c"])]);
+        graph.highlight_components(&components,&[GraphvizColor::red,GraphvizColor::blue],2);
+        let expected = crate::digraph!{
+            a [color = blue, penwidth = 2];
+            b [color = blue, penwidth = 2];
+            c;
+            d [color = red, penwidth = 2];
+            a -> b [color = blue, penwidth = 2];
+            b -> a [color = blue, penwidth = 2];
+            b -> c;
+            d -> d [color = red, penwidth = 2];
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+    }
+
+}
@@ -15,30 +15,31 @@ limitations under the License.
 */
 
 
+use std::fmt;
+
 use crate::edge::edge::GraphVizEdge;
 use crate::graph::graph::GraphVizDiGraph;
 use crate::item::item::GraphVizGraphItem;
 use crate::traits::{DotBuildable, DotTranslatable};
 
 impl DotTranslatable for GraphVizDiGraph {
-    fn to_dot_string(&self) -> String {
-        let mut res = String::new();
-        res.push_str("digraph G {");
-        res.push_str("\ncompound=true;" );
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
+        writer.write_str("digraph G {")?;
+        writer.write_str("\ncompound=true;")?;
         for item in &self.style {
-            res.push_str(&format!("\n{};",item.to_dot_string()) );
+            writer.write_char('\n')?;
+            item.write_dot_fmt(writer)?;
+            writer.write_char(';')?;
         }
         for item in &self.items {
-            res.push_str("\n\t");
-            res.push_str(& item.to_dot_string() );
+            writer.write_str("\n\t")?;
+            item.write_dot_fmt(writer)?;
         }
         for edge in &self.edges {
-            res.push_str("\n\t");
-            res.push_str(& edge.to_dot_string() );
+            writer.write_str("\n\t")?;
+            edge.write_dot_fmt(writer)?;
         }
-        res.push_str("\n}");
-        // ***
-        res
+        writer.write_str("\n}")
     }
 }
 
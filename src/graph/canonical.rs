@@ -0,0 +1,487 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+
+use std::collections::HashMap;
+
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::GraphvizEdgeStyleItem;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::style::GraphvizGraphStyleItem;
+use crate::item::cluster::{cluster_name, GraphVizCluster};
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::GraphvizNodeStyleItem;
+use crate::traits::DotTranslatable;
+
+
+/// keywords of the DOT language, which cannot be used as unquoted ids
+const DOT_KEYWORDS : [&str;6] = ["node","edge","graph","digraph","subgraph","strict"];
+
+/// whether the id can be written without quotes (an alphanumeric identifier or a numeral)
+fn is_plain_id(id : &str) -> bool {
+    if DOT_KEYWORDS.iter().any(|keyword| keyword.eq_ignore_ascii_case(id)) {
+        return false;
+    }
+    let mut chars = id.chars();
+    match chars.next() {
+        None => false,
+        Some(first) if first.is_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_alphanumeric() || c == '_')
+        },
+        Some(_) => {
+            let digits = id.strip_prefix('-').unwrap_or(id);
+            let mut parts = digits.splitn(2,'.');
+            let units = parts.next().unwrap_or("");
+            let decimals = parts.next().unwrap_or("");
+            (!units.is_empty() || !decimals.is_empty())
+                && units.chars().all(|c| c.is_ascii_digit())
+                && decimals.chars().all(|c| c.is_ascii_digit())
+        }
+    }
+}
+
+/// escapes the double quotes of a string value which are not already escaped
+fn escape_quotes(value : &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let mut backslashes = 0;
+    for c in value.chars() {
+        if c == '"' && backslashes % 2 == 0 {
+            escaped.push('\\');
+        }
+        backslashes = if c == '\\' {backslashes + 1} else {0};
+        escaped.push(c);
+    }
+    escaped
+}
+
+/**
+ Node and cluster ids are written unquoted when they are identifiers or numerals and quoted otherwise.
+ Ids containing a ':' are kept as they are given that it introduces a port in edge statements.
+ **/
+fn canonical_id(id : &str) -> String {
+    if id.contains(':') {
+        return id.to_string();
+    }
+    if id.len() >= 2 && id.starts_with('"') && id.ends_with('"') {
+        let inner = &id[1..id.len()-1];
+        if is_plain_id(inner) {
+            return inner.to_string();
+        }
+        return format!("\"{}\"", escape_quotes(inner));
+    }
+    if is_plain_id(id) {
+        id.to_string()
+    } else {
+        format!("\"{}\"", escape_quotes(id))
+    }
+}
+
+/**
+ Cluster ids are written after "cluster_", the whole name being quoted if required :
+ they are kept unquoted, without the enclosing quotes of an already quoted id.
+ **/
+fn canonical_cluster_id(id : &str) -> String {
+    if id.len() >= 2 && id.starts_with('"') && id.ends_with('"') {
+        return id[1..id.len()-1].replace("\\\"","\"");
+    }
+    id.to_string()
+}
+
+/// name of the (first) DOT attribute set by a style item
+fn attribute_name(item : &dyn DotTranslatable) -> String {
+    let dot_string = item.to_dot_string();
+    dot_string.split('=').next().unwrap_or("").to_string()
+}
+
+/**
+ Attributes are sorted by name.
+ The sort is stable so that items setting the same attribute (where the last one wins in Graphviz)
+ keep their relative order, e.g. a "Style" and a "FillColor" (which sets "style=filled").
+ **/
+fn sort_attributes<T : DotTranslatable>(items : &mut [T]) {
+    items.sort_by_cached_key(|item| attribute_name(item));
+}
+
+fn canonical_node_style_item(item : &GraphvizNodeStyleItem) -> GraphvizNodeStyleItem {
+    match item {
+        GraphvizNodeStyleItem::Style(kinds) => {
+            let mut kinds = kinds.clone();
+            kinds.sort_by_cached_key(|kind| kind.to_dot_string());
+            kinds.dedup();
+            GraphvizNodeStyleItem::Style(kinds)
+        },
+        GraphvizNodeStyleItem::Label(label) => {
+            GraphvizNodeStyleItem::Label(escape_quotes(label))
+        },
+        GraphvizNodeStyleItem::Image(path) => {
+            GraphvizNodeStyleItem::Image(escape_quotes(path))
+        },
+        GraphvizNodeStyleItem::FontName(name) => {
+            GraphvizNodeStyleItem::FontName(escape_quotes(name))
+        },
+        _ => item.clone()
+    }
+}
+
+fn canonical_node_style(style : &[GraphvizNodeStyleItem]) -> Vec<GraphvizNodeStyleItem> {
+    let mut style : Vec<GraphvizNodeStyleItem> = style.iter().map(canonical_node_style_item).collect();
+    sort_attributes(&mut style);
+    style
+}
+
+fn canonical_edge(edge : &GraphVizEdge) -> GraphVizEdge {
+    let mut style : Vec<GraphvizEdgeStyleItem> = edge.style.iter().map(|item| {
+        match item {
+            GraphvizEdgeStyleItem::Label(label) => {
+                GraphvizEdgeStyleItem::Label(escape_quotes(label))
+            },
+            _ => item.clone()
+        }
+    }).collect();
+    sort_attributes(&mut style);
+    GraphVizEdge::new(canonical_id(&edge.origin_node_id),
+                      edge.origin_cluster.as_deref().map(canonical_cluster_id),
+                      canonical_id(&edge.target_node_id),
+                      edge.target_cluster.as_deref().map(canonical_cluster_id),
+                      style)
+}
+
+/**
+ The relative order of edges does not matter to Graphviz as long as "ordering" is not set,
+ which this crate never does: they are sorted by origin, target and attributes.
+ **/
+fn canonical_edges(edges : &[GraphVizEdge]) -> Vec<GraphVizEdge> {
+    let mut edges : Vec<GraphVizEdge> = edges.iter().map(canonical_edge).collect();
+    edges.sort_by_cached_key(|edge| {
+        (edge.origin_node_id.clone(),
+         edge.target_node_id.clone(),
+         edge.origin_cluster.clone(),
+         edge.target_cluster.clone(),
+         edge.to_dot_string())
+    });
+    edges
+}
+
+/**
+ Nodes are declared first (sorted by id) and then clusters (sorted by id).
+ Declaring nodes before clusters ensures that an edge inside a cluster
+ never implicitly creates, inside that cluster, a node which is declared later outside of it.
+ **/
+fn canonical_items<'a>(items : impl Iterator<Item=&'a GraphVizGraphItem>) -> Vec<GraphVizGraphItem> {
+    let mut nodes = vec![];
+    let mut clusters = vec![];
+    for item in items {
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                nodes.push(GraphVizNode::new(canonical_id(&node.id),canonical_node_style(&node.style)));
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                clusters.push(canonical_cluster(cluster));
+            }
+        }
+    }
+    nodes.sort_by(|left,right| left.id.cmp(&right.id));
+    clusters.sort_by(|left,right| left.id.cmp(&right.id));
+    let mut items : Vec<GraphVizGraphItem> = nodes.into_iter().map(GraphVizGraphItem::Node).collect();
+    items.extend(clusters.into_iter().map(GraphVizGraphItem::Cluster));
+    items
+}
+
+fn canonical_cluster(cluster : &GraphVizCluster) -> GraphVizCluster {
+    let items = canonical_items(cluster.items.iter().map(|item| item.as_ref()));
+    GraphVizCluster::new(canonical_cluster_id(&cluster.id),
+                         canonical_node_style(&cluster.style),
+                         items.into_iter().map(Box::new).collect(),
+                         canonical_edges(&cluster.edges))
+}
+
+/// "nodesep" is written without trailing zeros
+fn canonical_graph_style_item(item : &GraphvizGraphStyleItem) -> GraphvizGraphStyleItem {
+    match item {
+        GraphvizGraphStyleItem::NodeSep(unit,decimal) => {
+            let mut decimal = *decimal;
+            while decimal > 0 && decimal % 10 == 0 {
+                decimal /= 10;
+            }
+            GraphvizGraphStyleItem::NodeSep(*unit,decimal)
+        },
+        _ => item.clone()
+    }
+}
+
+/// one statement of the canonical DOT source, with the path of the clusters in which it is
+struct CanonicalStatement {
+    path : String,
+    statement : String
+}
+
+fn collect_statements(path : &str,
+                      style : Vec<String>,
+                      items : &mut dyn Iterator<Item=&GraphVizGraphItem>,
+                      edges : &[GraphVizEdge],
+                      statements : &mut Vec<CanonicalStatement>) {
+    for attribute in style {
+        statements.push(CanonicalStatement{path:path.to_string(),statement:format!("{};", attribute)});
+    }
+    for item in items {
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                statements.push(CanonicalStatement{path:path.to_string(),statement:node.to_dot_string()});
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                statements.push(CanonicalStatement{path:path.to_string(),statement:format!("subgraph {}", cluster_name(&cluster.id))});
+                collect_statements(&format!("{} > {}", path, cluster.id),
+                                   cluster.style.iter().map(|item| item.to_dot_string()).collect(),
+                                   &mut cluster.items.iter().map(|item| item.as_ref()),
+                                   &cluster.edges,
+                                   statements);
+            }
+        }
+    }
+    for edge in edges {
+        statements.push(CanonicalStatement{path:path.to_string(),statement:edge.to_dot_string()});
+    }
+}
+
+
+
+impl GraphVizDiGraph {
+
+    /// the graph with its items, edges and attributes sorted and normalized
+    fn canonicalized(&self) -> GraphVizDiGraph {
+        let mut style : Vec<GraphvizGraphStyleItem> = self.style.iter().map(canonical_graph_style_item).collect();
+        sort_attributes(&mut style);
+        GraphVizDiGraph{style,
+            items:canonical_items(self.items.iter()),
+            edges:canonical_edges(&self.edges)}
+    }
+
+    /**
+     Deterministic DOT source of the graph, which is the same for graphs that only differ by
+     the order in which their nodes, clusters, edges and attributes were inserted.
+     Attributes setting the same DOT attribute keep their relative order (the last one prevails),
+     numbers are written without trailing zeros, node ids are quoted only if required
+     and unescaped double quotes are escaped in string values.
+     **/
+    pub fn to_canonical_dot_string(&self) -> String {
+        self.canonicalized().to_dot_string()
+    }
+
+    /**
+     None if both graphs have the same canonical DOT source.
+     Otherwise, a report of the statements of the canonical DOT source only found in either graph,
+     each one prefixed by the path of the clusters in which it is.
+     **/
+    pub fn canonical_dot_mismatch(&self, other : &GraphVizDiGraph) -> Option<String> {
+        let left = self.canonicalized();
+        let right = other.canonicalized();
+        if left.to_dot_string() == right.to_dot_string() {
+            return None;
+        }
+        // ***
+        let mut left_statements = vec![];
+        let mut right_statements = vec![];
+        for (graph,statements) in [(&left,&mut left_statements),(&right,&mut right_statements)] {
+            let style = graph.style.iter().map(|item| item.to_dot_string()).collect();
+            collect_statements("G",style,&mut graph.items.iter(),&graph.edges,statements);
+        }
+        // ***
+        let mut report = String::new();
+        for (title,statements,others) in [("only in left",&left_statements,&right_statements),
+                                          ("only in right",&right_statements,&left_statements)] {
+            let mut remaining : HashMap<(&str,&str),usize> = HashMap::new();
+            for other in others {
+                *remaining.entry((&other.path,&other.statement)).or_insert(0) += 1;
+            }
+            let mut lines = vec![];
+            for statement in statements {
+                match remaining.get_mut(&(statement.path.as_str(),statement.statement.as_str())) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                    },
+                    _ => {
+                        lines.push(format!("    [{}] {}", statement.path, statement.statement));
+                    }
+                }
+            }
+            if !lines.is_empty() {
+                report.push_str(&format!("statements {} ({}):\n{}\n", title, lines.len(), lines.join("\n")));
+            }
+        }
+        Some(report)
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::builder::edge::GvEdgeBuilder;
+    use crate::builder::graph::GvGraphBuilder;
+    use crate::builder::node::GvNodeBuilder;
+    use crate::colors::GraphvizColor;
+    use crate::graph::style::GvGraphRankDir;
+    use crate::item::node::style::GvNodeShape;
+
+    #[test]
+    fn plain_ids() {
+        for id in ["a","_a1","été","42","-3.5",".5","7."] {
+            assert!(is_plain_id(id),"{}",id);
+        }
+        for id in ["","a b","1a","-","a-b","node","Subgraph","\"a\"","1.2.3"] {
+            assert!(!is_plain_id(id),"{}",id);
+        }
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        assert_eq!(escape_quotes("say \"hi\""),"say \\\"hi\\\"");
+        assert_eq!(escape_quotes("say \\\"hi\\\""),"say \\\"hi\\\"");
+        assert_eq!(escape_quotes("a\\\\\"b"),"a\\\\\\\"b");
+        assert_eq!(escape_quotes("plain"),"plain");
+    }
+
+    #[test]
+    fn canonical_ids() {
+        assert_eq!(canonical_id("a"),"a");
+        assert_eq!(canonical_id("\"a\""),"a");
+        assert_eq!(canonical_id("a b"),"\"a b\"");
+        assert_eq!(canonical_id("\"a b\""),"\"a b\"");
+        assert_eq!(canonical_id("edge"),"\"edge\"");
+        assert_eq!(canonical_id("say \"hi\""),"\"say \\\"hi\\\"\"");
+        assert_eq!(canonical_id("a:n"),"a:n");
+        assert_eq!(canonical_id("\"a\":n"),"\"a\":n");
+    }
+
+    fn graph(reversed : bool) -> GraphVizDiGraph {
+        let mut nodes = vec![
+            GvNodeBuilder::new("a").label("A").shape(GvNodeShape::Rectangle).build(),
+            GvNodeBuilder::new("\"b\"").fill(GraphvizColor::red).build()
+        ];
+        let mut edges = vec![
+            GvEdgeBuilder::new("a","b").label("ab").color(GraphvizColor::blue).build(),
+            GvEdgeBuilder::new("b","x").lhead("\"c1\"").build()
+        ];
+        if reversed {
+            nodes.reverse();
+            edges.reverse();
+            for node in nodes.iter_mut() {
+                node.style.reverse();
+            }
+            for edge in edges.iter_mut() {
+                edge.style.reverse();
+            }
+        }
+        let mut builder = GvGraphBuilder::new().rankdir(GvGraphRankDir::LR).node_sep(1,50);
+        if reversed {
+            builder = builder
+                .cluster("c2", |c2| c2.node(GvNodeBuilder::new("y")))
+                .cluster("\"c1\"", |c1| c1.node(GvNodeBuilder::new("x")));
+        }
+        for node in nodes {
+            builder = builder.node(node);
+        }
+        if !reversed {
+            builder = builder
+                .cluster("c1", |c1| c1.node(GvNodeBuilder::new("x")))
+                .cluster("c2", |c2| c2.node(GvNodeBuilder::new("y")));
+        }
+        for edge in edges {
+            builder = builder.edge(edge);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn insertion_order_does_not_matter() {
+        let left = graph(false);
+        let right = graph(true);
+        assert!(left.to_dot_string() != right.to_dot_string());
+        assert_eq!(left.to_canonical_dot_string(),right.to_canonical_dot_string());
+        assert!(left.canonical_dot_mismatch(&right).is_none());
+        crate::assert_dot_eq!(left,right);
+        assert_eq!(left.to_canonical_dot_string(),
+                   "digraph G {\ncompound=true;\nnodesep=1.5;\nrankdir=lr;\n\
+                    \ta [label=\"A\",shape=rectangle];\n\
+                    \tb [style=filled;fillcolor=red];\n\
+                    \tsubgraph cluster_c1 {\n\tx;\n}\n\
+                    \tsubgraph cluster_c2 {\n\ty;\n}\n\
+                    \ta->b [color=blue,label=\"ab\"];\n\
+                    \tb->x [lhead=cluster_c1];\n}");
+    }
+
+    #[test]
+    fn mismatch_report() {
+        let left = crate::digraph!{
+            a; b;
+            subgraph c1 { x; x -> a; }
+            a -> b;
+        };
+        let right = crate::digraph!{
+            b; a [label = "A"];
+            subgraph c1 { x; y; }
+            a -> b;
+            a -> b;
+        };
+        assert_eq!(left.canonical_dot_mismatch(&right).unwrap(),
+                   "statements only in left (2):\n\
+                   \x20   [G] a;\n\
+                   \x20   [G > c1] x->a;\n\
+                   statements only in right (3):\n\
+                   \x20   [G] a [label=\"A\"];\n\
+                   \x20   [G > c1] y;\n\
+                   \x20   [G] a->b;\n");
+    }
+
+    #[test]
+    fn non_plain_cluster_ids_are_quoted_as_a_whole() {
+        let graph = crate::digraph!{
+            subgraph "a b" {
+                x;
+            }
+            y -> x [lhead = "a b"];
+        };
+        assert_eq!(graph.to_canonical_dot_string(),
+                   "digraph G {\ncompound=true;\n\
+                   \tsubgraph \"cluster_a b\" {\n\tx;\n}\n\
+                   \ty->x [lhead=\"cluster_a b\"];\n}");
+        let quoted = GraphVizDiGraph{style:vec![],
+            items:vec![GraphVizGraphItem::Cluster(GraphVizCluster::new("\"a b\"".to_string(),vec![],vec![],vec![]))],
+            edges:vec![GvEdgeBuilder::new("y","y").ltail("\"say \\\"hi\\\"\"").build()]};
+        assert_eq!(quoted.to_canonical_dot_string(),
+                   "digraph G {\ncompound=true;\n\
+                   \tsubgraph \"cluster_a b\" {\n}\n\
+                   \ty->y [ltail=\"cluster_say \\\"hi\\\"\"];\n}");
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `left == right` failed: graph of test\nstatements only in right (1):\n    [G] b;\n")]
+    fn assert_dot_eq_reports_mismatch() {
+        let left = crate::digraph!{ a; };
+        let right = crate::digraph!{ a; b; };
+        crate::assert_dot_eq!(left,right,"graph of {}","test");
+    }
+
+}
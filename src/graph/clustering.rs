@@ -0,0 +1,416 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::edge::edge::GraphVizEdge;
+use crate::graph::algo::Adjacency;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::GraphvizNodeStyle;
+
+
+/// how "GraphVizDiGraph::clustered" groups nodes
+#[derive(Eq,PartialEq,Clone,Copy,Debug)]
+pub enum GvClusteringMethod {
+    /// one cluster per strongly connected component
+    StronglyConnectedComponents,
+    /**
+     Communities found by label propagation on the undirected graph :
+     each node takes in turn (in order of declaration) the label that is the most frequent among its neighbors,
+     ties being broken in favor of its current label and then of the label of the earliest node.
+     As ties are frequent, the result depends on the order of declaration of the nodes.
+     **/
+    LabelPropagation,
+    /**
+     Communities found by the Louvain method (greedy optimization of modularity) on the undirected graph,
+     nodes being considered in order of declaration.
+     Each level of aggregation of the method gives a level of nested clusters.
+     **/
+    Louvain
+}
+
+
+/// above this number of passes without convergence, label propagation and the local moves of Louvain stop
+const MAX_PASSES : usize = 100;
+
+/// undirected weighted graph, with the weights of loops apart
+struct WeightedGraph {
+    neighbors : Vec<BTreeMap<usize,f64>>,
+    loops : Vec<f64>
+}
+
+impl WeightedGraph {
+
+    fn from_adjacency(adjacency : &Adjacency) -> WeightedGraph {
+        let count = adjacency.ids.len();
+        let mut graph = WeightedGraph{neighbors:vec![BTreeMap::new();count],loops:vec![0.0;count]};
+        for (origin,successors) in adjacency.successors.iter().enumerate() {
+            for target in successors {
+                graph.add(origin,*target,1.0);
+            }
+        }
+        graph
+    }
+
+    fn add(&mut self, origin : usize, target : usize, weight : f64) {
+        if origin == target {
+            self.loops[origin] += weight;
+        } else {
+            *self.neighbors[origin].entry(target).or_insert(0.0) += weight;
+            *self.neighbors[target].entry(origin).or_insert(0.0) += weight;
+        }
+    }
+
+    /// weighted degree, a loop counting twice
+    fn degree(&self, node : usize) -> f64 {
+        self.neighbors[node].values().sum::<f64>() + 2.0 * self.loops[node]
+    }
+
+}
+
+/// community of each node, renumbered in order of first appearance
+fn renumber(communities : &[usize]) -> Vec<usize> {
+    let mut numbers : HashMap<usize,usize> = HashMap::new();
+    communities.iter().map(|community| {
+        let next = numbers.len();
+        *numbers.entry(*community).or_insert(next)
+    }).collect()
+}
+
+fn label_propagation(graph : &WeightedGraph) -> Vec<usize> {
+    let mut labels : Vec<usize> = (0..graph.loops.len()).collect();
+    for _ in 0..MAX_PASSES {
+        let mut changed = false;
+        for node in 0..labels.len() {
+            let mut weights : BTreeMap<usize,f64> = BTreeMap::new();
+            for (neighbor,weight) in &graph.neighbors[node] {
+                *weights.entry(labels[*neighbor]).or_insert(0.0) += weight;
+            }
+            let best_weight = weights.values().cloned().fold(0.0,f64::max);
+            if best_weight == 0.0 || weights.get(&labels[node]) == Some(&best_weight) {
+                continue;
+            }
+            // the BTreeMap gives the smallest label among those with the best weight
+            if let Some((label,_)) = weights.iter().find(|(_,weight)| **weight == best_weight) {
+                labels[node] = *label;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    renumber(&labels)
+}
+
+/// local moving phase of Louvain : each node moves to the neighboring community with the best gain of modularity
+fn louvain_moves(graph : &WeightedGraph) -> Vec<usize> {
+    let count = graph.loops.len();
+    let degrees : Vec<f64> = (0..count).map(|node| graph.degree(node)).collect();
+    let total : f64 = degrees.iter().sum();
+    let mut communities : Vec<usize> = (0..count).collect();
+    if total == 0.0 {
+        return communities;
+    }
+    let mut totals = degrees.clone();
+    for _ in 0..MAX_PASSES {
+        let mut moved = false;
+        for node in 0..count {
+            let current = communities[node];
+            totals[current] -= degrees[node];
+            let mut links : BTreeMap<usize,f64> = BTreeMap::new();
+            links.insert(current,0.0);
+            for (neighbor,weight) in &graph.neighbors[node] {
+                *links.entry(communities[*neighbor]).or_insert(0.0) += weight;
+            }
+            let gain = |community : &usize, link : &f64| link - totals[*community] * degrees[node] / total;
+            let mut best = current;
+            let mut best_gain = gain(&current,&links[&current]);
+            for (community,link) in &links {
+                let candidate = gain(community,link);
+                if candidate > best_gain + 1e-12 {
+                    best = *community;
+                    best_gain = candidate;
+                }
+            }
+            totals[best] += degrees[node];
+            if best != current {
+                communities[node] = best;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    renumber(&communities)
+}
+
+/// the graph whose nodes are the communities
+fn aggregate(graph : &WeightedGraph, communities : &[usize]) -> WeightedGraph {
+    let count = communities.iter().max().map_or(0,|max| max + 1);
+    let mut aggregated = WeightedGraph{neighbors:vec![BTreeMap::new();count],loops:vec![0.0;count]};
+    for node in 0..graph.loops.len() {
+        aggregated.loops[communities[node]] += graph.loops[node];
+        for (neighbor,weight) in &graph.neighbors[node] {
+            // each undirected edge is seen from both of its ends
+            if node < *neighbor {
+                aggregated.add(communities[node],communities[*neighbor],*weight);
+            }
+        }
+    }
+    aggregated
+}
+
+/// the community of each node at each level of aggregation, from the finest to the coarsest
+fn louvain(graph : &WeightedGraph) -> Vec<Vec<usize>> {
+    let mut levels : Vec<Vec<usize>> = vec![];
+    let mut current = WeightedGraph{neighbors:graph.neighbors.clone(),loops:graph.loops.clone()};
+    let mut membership : Vec<usize> = (0..graph.loops.len()).collect();
+    loop {
+        let communities = louvain_moves(&current);
+        let count = communities.iter().max().map_or(0,|max| max + 1);
+        if count == current.loops.len() {
+            break;
+        }
+        membership = membership.iter().map(|community| communities[*community]).collect();
+        levels.push(membership.clone());
+        current = aggregate(&current,&communities);
+    }
+    levels
+}
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     New graph in which the nodes are grouped in generated clusters according to the method,
+     communities made of a single node not being put in a cluster.
+     All the nodes (including those only referred to by edges) are declared,
+     the existing clusters being replaced by the generated ones and all the edges being declared at the root of the graph
+     (without their "ltail" and "lhead").
+     Clusters are named "scc_<i>" or "community_<i>" (with "_<j>" suffixes for nested clusters)
+     and their style is given by the "cluster_style" function, from their id and the ids of their nodes.
+     **/
+    pub fn clustered<F>(&self, method : GvClusteringMethod, mut cluster_style : F) -> GraphVizDiGraph
+        where F : FnMut(&str,&[String]) -> GraphvizNodeStyle {
+        let adjacency = Adjacency::new(self);
+        let (levels,prefix) = match method {
+            GvClusteringMethod::StronglyConnectedComponents => {
+                let mut communities = vec![0;adjacency.ids.len()];
+                let mut components = adjacency.strongly_connected_components();
+                components.reverse();
+                for (index,component) in components.iter().enumerate() {
+                    for node in component {
+                        communities[*node] = index;
+                    }
+                }
+                (vec![renumber(&communities)],"scc")
+            },
+            GvClusteringMethod::LabelPropagation => {
+                (vec![label_propagation(&WeightedGraph::from_adjacency(&adjacency))],"community")
+            },
+            GvClusteringMethod::Louvain => {
+                (louvain(&WeightedGraph::from_adjacency(&adjacency)),"community")
+            }
+        };
+        // ***
+        let mut declared : HashMap<&str,&GraphVizNode> = HashMap::new();
+        for node in self.all_nodes() {
+            declared.entry(node.id.as_str()).or_insert(node);
+        }
+        let nodes : Vec<GraphVizNode> = adjacency.ids.iter().map(|id| {
+            match declared.get(id) {
+                Some(node) => (*node).clone(),
+                None => GraphVizNode::new(id.to_string(),vec![])
+            }
+        }).collect();
+        let generator = ClusterGenerator{levels:&levels,nodes:&nodes};
+        let members : Vec<usize> = (0..nodes.len()).collect();
+        let items = generator.items(&members,levels.len(),prefix,&mut cluster_style);
+        let edges = self.all_edges().map(|edge| {
+            GraphVizEdge::new(edge.origin_node_id.clone(),None,edge.target_node_id.clone(),None,edge.style.clone())
+        }).collect();
+        GraphVizDiGraph{style:self.style.clone(),items,edges}
+    }
+
+}
+
+
+struct ClusterGenerator<'a> {
+    levels : &'a [Vec<usize>],
+    nodes : &'a [GraphVizNode]
+}
+
+impl ClusterGenerator<'_> {
+
+    /// the items for the members, grouped according to the levels below "level" (from the coarsest)
+    fn items<F>(&self, members : &[usize], level : usize, id : &str, cluster_style : &mut F) -> Vec<GraphVizGraphItem>
+        where F : FnMut(&str,&[String]) -> GraphvizNodeStyle {
+        if level == 0 {
+            return members.iter().map(|member| GraphVizGraphItem::Node(self.nodes[*member].clone())).collect();
+        }
+        let communities = &self.levels[level - 1];
+        let mut groups : Vec<Vec<usize>> = vec![];
+        let mut group_of : HashMap<usize,usize> = HashMap::new();
+        for member in members {
+            let group = *group_of.entry(communities[*member]).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[group].push(*member);
+        }
+        if groups.len() == 1 {
+            // same members as the enclosing cluster
+            return self.items(members,level - 1,id,cluster_style);
+        }
+        let mut items = vec![];
+        let mut clusters = 0;
+        for group in groups {
+            if group.len() == 1 {
+                items.push(GraphVizGraphItem::Node(self.nodes[group[0]].clone()));
+                continue;
+            }
+            let cluster_id = format!("{}_{}", id, clusters);
+            clusters += 1;
+            let member_ids : Vec<String> = group.iter().map(|member| self.nodes[*member].id.clone()).collect();
+            let style = cluster_style(&cluster_id,&member_ids);
+            let nested = self.items(&group,level - 1,&cluster_id,cluster_style);
+            items.push(GraphVizGraphItem::Cluster(GraphVizCluster::new(cluster_id,style,nested.into_iter().map(Box::new).collect(),vec![])));
+        }
+        items
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::item::node::style::GraphvizNodeStyleItem;
+
+    /// the nesting of clusters and nodes, e.g. "c[a b] d"
+    fn nesting(items : &[GraphVizGraphItem]) -> String {
+        items.iter().map(nesting_item).collect::<Vec<String>>().join(" ")
+    }
+
+    fn nesting_item(item : &GraphVizGraphItem) -> String {
+        match item {
+            GraphVizGraphItem::Node(node) => node.id.clone(),
+            GraphVizGraphItem::Cluster(cluster) => {
+                let nested : Vec<String> = cluster.items.iter().map(|item| nesting_item(item)).collect();
+                format!("{}[{}]", cluster.id, nested.join(" "))
+            }
+        }
+    }
+
+    /// two cliques, each strongly connected, joined by a bridge from "d" to "h"
+    fn two_cliques() -> GraphVizDiGraph {
+        crate::digraph!{
+            a; b; c; d; e; f; g; h;
+            a -> b -> c -> d -> a;
+            a -> c; b -> d;
+            e -> f -> g -> h -> e;
+            e -> g; f -> h;
+            d -> h;
+        }
+    }
+
+    /// a ring of twelve triangles "x1 -> x2 -> x3 -> x1", each linked to the next one by an edge
+    fn ring_of_triangles() -> GraphVizDiGraph {
+        crate::digraph!{
+            a1 -> a2 -> a3 -> a1; a3 -> b1;
+            b1 -> b2 -> b3 -> b1; b3 -> c1;
+            c1 -> c2 -> c3 -> c1; c3 -> d1;
+            d1 -> d2 -> d3 -> d1; d3 -> e1;
+            e1 -> e2 -> e3 -> e1; e3 -> f1;
+            f1 -> f2 -> f3 -> f1; f3 -> g1;
+            g1 -> g2 -> g3 -> g1; g3 -> h1;
+            h1 -> h2 -> h3 -> h1; h3 -> i1;
+            i1 -> i2 -> i3 -> i1; i3 -> j1;
+            j1 -> j2 -> j3 -> j1; j3 -> k1;
+            k1 -> k2 -> k3 -> k1; k3 -> l1;
+            l1 -> l2 -> l3 -> l1; l3 -> a1;
+        }
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let mut graph = two_cliques();
+        graph.edges.push(GraphVizEdge::new("h".to_string(),None,"z".to_string(),None,vec![]));
+        let mut styled = vec![];
+        let clustered = graph.clustered(GvClusteringMethod::StronglyConnectedComponents, |id,members| {
+            styled.push(format!("{} : {}", id, members.join(",")));
+            vec![GraphvizNodeStyleItem::Label(id.to_string())]
+        });
+        assert_eq!(nesting(&clustered.items),"scc_0[a b c d] scc_1[e f g h] z");
+        assert_eq!(styled,vec!["scc_0 : a,b,c,d".to_string(),"scc_1 : e,f,g,h".to_string()]);
+        assert_eq!(clustered.edges.len(),graph.edges.len());
+    }
+
+    #[test]
+    fn label_propagation() {
+        let clustered = two_cliques().clustered(GvClusteringMethod::LabelPropagation,|_,_| vec![]);
+        assert_eq!(nesting(&clustered.items),"community_0[a b c d] community_1[e f g h]");
+        // with the bridge towards "e", "e" takes the label of "d" on a tie and the first clique swallows the second one
+        let flooded = crate::digraph!{
+            a; b; c; d; e; f; g; h;
+            a -> b -> c -> d -> a;
+            a -> c; b -> d;
+            e -> f -> g -> h -> e;
+            e -> g; f -> h;
+            d -> e;
+        };
+        let clustered = flooded.clustered(GvClusteringMethod::LabelPropagation,|_,_| vec![]);
+        assert_eq!(nesting(&clustered.items),"a b c d e f g h");
+    }
+
+    #[test]
+    fn louvain() {
+        let clustered = two_cliques().clustered(GvClusteringMethod::Louvain,|_,_| vec![]);
+        assert_eq!(nesting(&clustered.items),"community_0[a b c d] community_1[e f g h]");
+        // triangles at the first level, pairs of neighboring triangles at the second one
+        let clustered = ring_of_triangles().clustered(GvClusteringMethod::Louvain,|_,_| vec![]);
+        let expected : Vec<String> = ["a","c","e","g","i","k"].iter().enumerate().map(|(index,first)| {
+            let second = ((first.as_bytes()[0] + 1) as char).to_string();
+            format!("community_{0}[community_{0}_0[{1}1 {1}2 {1}3] community_{0}_1[{2}1 {2}2 {2}3]]", index, first, second)
+        }).collect();
+        assert_eq!(nesting(&clustered.items),expected.join(" "));
+    }
+
+    #[test]
+    fn deterministic() {
+        for method in [GvClusteringMethod::StronglyConnectedComponents,GvClusteringMethod::LabelPropagation,GvClusteringMethod::Louvain] {
+            let first = nesting(&ring_of_triangles().clustered(method,|_,_| vec![]).items);
+            for _ in 0..10 {
+                assert_eq!(nesting(&ring_of_triangles().clustered(method,|_,_| vec![]).items),first);
+            }
+        }
+    }
+
+}
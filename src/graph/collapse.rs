@@ -0,0 +1,373 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+
+use std::collections::HashSet;
+
+use crate::builder::node::set_node_style_item;
+use crate::builder::set_style_item;
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::GraphvizEdgeStyleItem;
+use crate::graph::diff::GvLocated;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::{GraphvizNodeStyleItem, GvNodeShape};
+use crate::traits::DotBuildable;
+
+
+/**
+ What "GraphVizDiGraph::collapse_cluster" replaced by a summary node,
+ from which "GraphVizDiGraph::expand_cluster" restores the cluster.
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvCollapsedCluster {
+    /// the cluster, with all its content
+    pub cluster : GraphVizCluster,
+    /// where the cluster was, the summary node taking its place
+    pub location : GvLocated<usize>,
+    /// id of the summary node
+    pub summary_id : String,
+    /// the edges declared outside of the cluster which were from or to its nodes, with their index among the edges of their cluster (or graph)
+    pub removed_edges : Vec<GvLocated<(usize,GraphVizEdge)>>
+}
+
+
+/// ids of the clusters in which the cluster with the given id is nested and its index among the items of the innermost one
+fn cluster_location(items : &mut dyn Iterator<Item=&GraphVizGraphItem>,
+                    id : &str,
+                    path : &mut Vec<String>) -> Option<GvLocated<usize>> {
+    for (index,item) in items.enumerate() {
+        if let GraphVizGraphItem::Cluster(cluster) = item {
+            if cluster.id == id {
+                return Some(GvLocated{cluster_path:path.clone(),element:index});
+            }
+            path.push(cluster.id.clone());
+            let location = cluster_location(&mut cluster.items.iter().map(|item| item.as_ref()),id,path);
+            path.pop();
+            if location.is_some() {
+                return location;
+            }
+        }
+    }
+    None
+}
+
+/// ids of the nodes and clusters nested in the cluster
+fn cluster_content(cluster : &GraphVizCluster, node_ids : &mut HashSet<String>, cluster_ids : &mut HashSet<String>) {
+    cluster_ids.insert(cluster.id.clone());
+    for item in &cluster.items {
+        match item.as_ref() {
+            GraphVizGraphItem::Node(node) => {
+                node_ids.insert(node.id.clone());
+            },
+            GraphVizGraphItem::Cluster(nested) => {
+                cluster_content(nested,node_ids,cluster_ids);
+            }
+        }
+    }
+}
+
+/// all the edges of the cluster and of its nested clusters
+fn cluster_edges(cluster : &GraphVizCluster, edges : &mut Vec<GraphVizEdge>) {
+    edges.extend(cluster.edges.iter().cloned());
+    for item in &cluster.items {
+        if let GraphVizGraphItem::Cluster(nested) = item.as_ref() {
+            cluster_edges(nested,edges);
+        }
+    }
+}
+
+/// removes the edges satisfying the predicate from the cluster and its nested clusters
+fn take_cluster_edges(cluster : &mut GraphVizCluster,
+                      path : &mut Vec<String>,
+                      take : &dyn Fn(&GraphVizEdge) -> bool,
+                      taken : &mut Vec<GvLocated<(usize,GraphVizEdge)>>) {
+    path.push(cluster.id.clone());
+    take_edges(&mut cluster.edges,path,take,taken);
+    for item in cluster.items.iter_mut() {
+        if let GraphVizGraphItem::Cluster(nested) = item.as_mut() {
+            take_cluster_edges(nested,path,take,taken);
+        }
+    }
+    path.pop();
+}
+
+/// removes the edges satisfying the predicate, keeping their index
+fn take_edges(edges : &mut Vec<GraphVizEdge>,
+              path : &[String],
+              take : &dyn Fn(&GraphVizEdge) -> bool,
+              taken : &mut Vec<GvLocated<(usize,GraphVizEdge)>>) {
+    let mut kept = vec![];
+    for (index,edge) in std::mem::take(edges).into_iter().enumerate() {
+        if take(&edge) {
+            taken.push(GvLocated{cluster_path:path.to_vec(),element:(index,edge)});
+        } else {
+            kept.push(edge);
+        }
+    }
+    *edges = kept;
+}
+
+/// depth (1 for the clusters at the root of the graph) and id of all the clusters, in depth-first order
+fn cluster_depths(items : &mut dyn Iterator<Item=&GraphVizGraphItem>, depth : usize, depths : &mut Vec<(usize,String)>) {
+    for item in items {
+        if let GraphVizGraphItem::Cluster(cluster) = item {
+            depths.push((depth,cluster.id.clone()));
+            cluster_depths(&mut cluster.items.iter().map(|item| item.as_ref()),depth + 1,depths);
+        }
+    }
+}
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Replaces the cluster by a summary node with the same style,
+     labelled with the id of the cluster and the number of nodes it contains.
+     The id of the summary node is that of the cluster if no node of the graph has it,
+     and otherwise that of the cluster prefixed by "cluster_" as many times as needed for it to be free.
+     The edges between a node of the cluster and a node outside of it are redirected to the summary node,
+     parallel edges being merged into one (with the style of the first one),
+     which is labelled with the number of edges it stands for if "label_counts" is set.
+     The edges between nodes of the cluster are hidden.
+     Returns None if there is no such cluster.
+     **/
+    pub fn collapse_cluster(&mut self, id : &str, label_counts : bool) -> Option<GvCollapsedCluster> {
+        let location = cluster_location(&mut self.items.iter(),id,&mut vec![])?;
+        let summary_id = {
+            let graph_node_ids : HashSet<&str> = self.all_nodes().map(|node| node.id.as_str()).collect();
+            let mut summary_id = id.to_string();
+            while graph_node_ids.contains(summary_id.as_str()) {
+                summary_id = format!("cluster_{}", summary_id);
+            }
+            summary_id
+        };
+        let mut node_ids = HashSet::new();
+        let mut cluster_ids = HashSet::new();
+        // *** replaces the cluster by the summary node
+        let mut summary_style = {
+            let cluster = self.find_cluster(id)?;
+            cluster_content(cluster,&mut node_ids,&mut cluster_ids);
+            cluster.style.clone()
+        };
+        set_node_style_item(&mut summary_style,GraphvizNodeStyleItem::Shape(GvNodeShape::Rectangle));
+        let plural = if node_ids.len() == 1 {""} else {"s"};
+        set_node_style_item(&mut summary_style,GraphvizNodeStyleItem::Label(format!("{}\\n({} node{})", id, node_ids.len(), plural)));
+        let summary = GraphVizGraphItem::Node(GraphVizNode::new(summary_id.clone(),summary_style));
+        let replaced = match location.cluster_path.last() {
+            None => std::mem::replace(&mut self.items[location.element],summary),
+            Some(parent_id) => {
+                let parent = self.find_cluster_mut(parent_id)?;
+                *std::mem::replace(&mut parent.items[location.element],Box::new(summary))
+            }
+        };
+        let cluster = match replaced {
+            GraphVizGraphItem::Cluster(cluster) => cluster,
+            GraphVizGraphItem::Node(_) => return None
+        };
+        // *** removes the edges declared outside of the cluster which involve its nodes
+        let involves = |edge : &GraphVizEdge| node_ids.contains(&edge.origin_node_id) || node_ids.contains(&edge.target_node_id);
+        let mut removed_edges = vec![];
+        take_edges(&mut self.edges,&[],&involves,&mut removed_edges);
+        for item in self.items.iter_mut() {
+            if let GraphVizGraphItem::Cluster(other) = item {
+                take_cluster_edges(other,&mut vec![],&involves,&mut removed_edges);
+            }
+        }
+        // *** redirects the edges crossing the boundary of the cluster, wherever they were declared
+        let mut crossing : Vec<GraphVizEdge> = removed_edges.iter().map(|located| located.element.1.clone()).collect();
+        cluster_edges(&cluster,&mut crossing);
+        let mut redirected : Vec<(GraphVizEdge,usize)> = vec![];
+        for edge in crossing {
+            let origin_inside = node_ids.contains(&edge.origin_node_id);
+            let target_inside = node_ids.contains(&edge.target_node_id);
+            if origin_inside == target_inside {
+                continue;
+            }
+            let mut edge = edge;
+            if origin_inside {
+                edge.origin_node_id = summary_id.clone();
+            } else {
+                edge.target_node_id = summary_id.clone();
+            }
+            for cluster_id in [&mut edge.origin_cluster,&mut edge.target_cluster] {
+                if cluster_id.as_ref().is_some_and(|cluster_id| cluster_ids.contains(cluster_id)) {
+                    *cluster_id = None;
+                }
+            }
+            match redirected.iter_mut().find(|(existing,_)| {
+                existing.origin_node_id == edge.origin_node_id && existing.target_node_id == edge.target_node_id
+            }) {
+                Some((_,count)) => *count += 1,
+                None => redirected.push((edge,1))
+            }
+        }
+        for (mut edge,count) in redirected {
+            if label_counts {
+                set_style_item(&mut edge.style,GraphvizEdgeStyleItem::Label(count.to_string()));
+            }
+            match location.cluster_path.last().and_then(|parent_id| self.find_cluster_mut(parent_id)) {
+                Some(parent) => parent.add_edge(edge),
+                None => self.add_edge(edge)
+            }
+        }
+        Some(GvCollapsedCluster{cluster,location,summary_id,removed_edges})
+    }
+
+    /**
+     Inverse of "GraphVizDiGraph::collapse_cluster" :
+     the summary node and all the edges from or to it are replaced by the cluster and the edges which were removed,
+     which are put back at their former index.
+     Collapsed clusters must be expanded in the reverse order of their collapsing.
+     **/
+    pub fn expand_cluster(&mut self, collapsed : GvCollapsedCluster) {
+        let GvCollapsedCluster{cluster,location,summary_id,removed_edges} = collapsed;
+        self.remove_node(&summary_id);
+        match location.cluster_path.last().and_then(|parent_id| self.find_cluster_mut(parent_id)) {
+            Some(parent) => {
+                let index = location.element.min(parent.items.len());
+                parent.items.insert(index,Box::new(GraphVizGraphItem::Cluster(cluster)));
+            },
+            None => {
+                let index = location.element.min(self.items.len());
+                self.items.insert(index,GraphVizGraphItem::Cluster(cluster));
+            }
+        }
+        // the edges of each cluster (or of the graph) were taken in increasing order of their index
+        for located in removed_edges {
+            let (index,edge) = located.element;
+            let edges = match located.cluster_path.last().and_then(|parent_id| self.find_cluster_mut(parent_id)) {
+                Some(parent) => &mut parent.edges,
+                None => &mut self.edges
+            };
+            edges.insert(index.min(edges.len()),edge);
+        }
+    }
+
+    /**
+     Collapses every cluster nested in more than "depth" clusters
+     (all the clusters for 0, the clusters nested in those at the root of the graph for 1, etc.).
+     Returns what was collapsed, in the order in which it must be expanded back.
+     **/
+    pub fn collapse_deeper_than(&mut self, depth : usize, label_counts : bool) -> Vec<GvCollapsedCluster> {
+        let mut depths = vec![];
+        cluster_depths(&mut self.items.iter(),1,&mut depths);
+        let mut collapsed = vec![];
+        for (cluster_depth,id) in depths {
+            if cluster_depth == depth + 1 {
+                if let Some(record) = self.collapse_cluster(&id,label_counts) {
+                    collapsed.push(record);
+                }
+            }
+        }
+        collapsed.reverse();
+        collapsed
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::traits::DotTranslatable;
+
+    #[test]
+    fn summary_node_does_not_clash() {
+        let original = crate::digraph!{
+            a;
+            subgraph a { x; }
+            a -> y;
+            x -> y;
+        };
+        let mut graph = GraphVizDiGraph{style:original.style.clone(),items:original.items.clone(),edges:original.edges.clone()};
+        let collapsed = graph.collapse_cluster("a",true).unwrap();
+        assert_eq!(collapsed.summary_id,"cluster_a");
+        let expected = crate::digraph!{
+            a;
+            cluster_a [shape = Rectangle, label = "a\\n(1 node)"];
+            a -> y;
+            cluster_a -> y [label = "1"];
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+        graph.expand_cluster(collapsed);
+        assert_eq!(graph.to_dot_string(),original.to_dot_string());
+    }
+
+    #[test]
+    fn collapse_and_expand_round_trip() {
+        let original = crate::digraph!{
+            a; cluster_c2;
+            subgraph c1 {
+                label = "C1";
+                b;
+                subgraph c2 { c; d; c -> d; d -> e; }
+                b -> c [lhead = c2];
+                e -> b;
+            }
+            subgraph c3 { e; f; c -> f; }
+            a -> b [lhead = c1];
+            a -> c;
+            b -> f;
+            d -> a;
+        };
+        let mut graph = GraphVizDiGraph{style:original.style.clone(),items:original.items.clone(),edges:original.edges.clone()};
+        let collapsed = graph.collapse_deeper_than(1,false);
+        assert_eq!(collapsed.len(),1);
+        assert_eq!(collapsed[0].summary_id,"c2");
+        // the edges crossing the boundary of c2 are redirected to the summary node in c1, without their "lhead"
+        let expected = crate::digraph!{
+            a; cluster_c2;
+            subgraph c1 {
+                label = "C1";
+                b;
+                c2 [shape = Rectangle, label = "c2\\n(2 nodes)"];
+                e -> b;
+                a -> c2;
+                c2 -> a;
+                b -> c2;
+                c2 -> f;
+                c2 -> e;
+            }
+            subgraph c3 { e; f; }
+            a -> b [lhead = c1];
+            b -> f;
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+        for record in collapsed {
+            graph.expand_cluster(record);
+        }
+        assert_eq!(graph.to_dot_string(),original.to_dot_string());
+        // ***
+        let collapsed = graph.collapse_deeper_than(0,true);
+        assert_eq!(collapsed.len(),2);
+        for record in collapsed {
+            graph.expand_cluster(record);
+        }
+        assert_eq!(graph.to_dot_string(),original.to_dot_string());
+    }
+
+}
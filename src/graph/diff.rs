@@ -0,0 +1,579 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::builder::node::set_node_style_item;
+use crate::builder::set_style_item;
+use crate::colors::GraphvizColor;
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::{GraphvizEdgeStyleItem, GvEdgeLineStyle};
+use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::style::GraphvizGraphStyleItem;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::{GraphvizNodeStyle, GraphvizNodeStyleItem, GvNodeStyleKind};
+use crate::traits::{DotBuildable, DotTranslatable};
+
+
+/**
+ An element of a graph with the ids of the nested clusters in which it is declared
+ (outermost first, empty for the root of the graph).
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvLocated<T> {
+    pub cluster_path : Vec<String>,
+    pub element : T
+}
+
+/**
+ Style items only found in the old (removed) or in the new (added) version of an element,
+ regardless of their order.
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvStyleDiff<T> {
+    pub removed : Vec<T>,
+    pub added : Vec<T>
+}
+
+impl<T : PartialEq + Clone> GvStyleDiff<T> {
+
+    pub fn new(old : &[T], new : &[T]) -> GvStyleDiff<T> {
+        let mut added : Vec<T> = new.to_vec();
+        let mut removed = vec![];
+        for item in old {
+            match added.iter().position(|candidate| candidate == item) {
+                Some(position) => {
+                    added.remove(position);
+                },
+                None => {
+                    removed.push(item.clone());
+                }
+            }
+        }
+        GvStyleDiff{removed,added}
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty()
+    }
+
+}
+
+/**
+ A node or cluster found (by id) in both versions of the graph,
+ which either moved to other clusters or whose style changed.
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvElementChange<T> {
+    pub id : String,
+    pub old_cluster_path : Vec<String>,
+    pub new_cluster_path : Vec<String>,
+    pub style : GvStyleDiff<T>
+}
+
+/**
+ An edge found in both versions of the graph, edges being matched by origin and target
+ (the n-th edge from a node to another in the old version being matched with the n-th one in the new version).
+ Either it is declared in other clusters, its "ltail" or "lhead" changed, or its style changed.
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvEdgeChange {
+    pub old : GvLocated<GraphVizEdge>,
+    pub new : GvLocated<GraphVizEdge>,
+    pub style : GvStyleDiff<GraphvizEdgeStyleItem>
+}
+
+/**
+ Differences between two versions of a graph, as computed by "GraphVizDiGraph::diff".
+ Nodes and clusters are identified by their ids.
+ Added and removed clusters are given without their items and edges, which are reported separately.
+ **/
+#[derive(Eq,PartialEq,Clone)]
+pub struct GvGraphDiff {
+    pub graph_style : GvStyleDiff<GraphvizGraphStyleItem>,
+    pub added_nodes : Vec<GvLocated<GraphVizNode>>,
+    pub removed_nodes : Vec<GvLocated<GraphVizNode>>,
+    pub changed_nodes : Vec<GvElementChange<GraphvizNodeStyleItem>>,
+    pub added_clusters : Vec<GvLocated<GraphVizCluster>>,
+    pub removed_clusters : Vec<GvLocated<GraphVizCluster>>,
+    pub changed_clusters : Vec<GvElementChange<GraphvizNodeStyleItem>>,
+    pub added_edges : Vec<GvLocated<GraphVizEdge>>,
+    pub removed_edges : Vec<GvLocated<GraphVizEdge>>,
+    pub changed_edges : Vec<GvEdgeChange>
+}
+
+
+
+fn fmt_path(path : &[String]) -> String {
+    if path.is_empty() {
+        "graph".to_string()
+    } else {
+        format!("graph > {}", path.join(" > "))
+    }
+}
+
+fn fmt_items<T : DotTranslatable>(items : &[T]) -> String {
+    let items : Vec<String> = items.iter().map(|item| item.to_dot_string()).collect();
+    items.join(",")
+}
+
+fn fmt_style<T : DotTranslatable>(items : &[T]) -> String {
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", fmt_items(items))
+    }
+}
+
+fn fmt_style_diff<T : DotTranslatable>(f : &mut fmt::Formatter<'_>, style : &GvStyleDiff<T>) -> fmt::Result {
+    if !style.removed.is_empty() {
+        write!(f, " -[{}]", fmt_items(&style.removed))?;
+    }
+    if !style.added.is_empty() {
+        write!(f, " +[{}]", fmt_items(&style.added))?;
+    }
+    Ok(())
+}
+
+fn fmt_element_change<T : DotTranslatable>(f : &mut fmt::Formatter<'_>, kind : &str, change : &GvElementChange<T>) -> fmt::Result {
+    write!(f, "~ {} {} in {}", kind, change.id, fmt_path(&change.new_cluster_path))?;
+    if change.old_cluster_path != change.new_cluster_path {
+        write!(f, " (moved from {})", fmt_path(&change.old_cluster_path))?;
+    }
+    fmt_style_diff(f,&change.style)?;
+    writeln!(f)
+}
+
+fn fmt_edge(edge : &GraphVizEdge) -> String {
+    let attributes = edge.dot_attributes();
+    if attributes.is_empty() {
+        format!("{}->{}", edge.origin_node_id, edge.target_node_id)
+    } else {
+        format!("{}->{} [{}]", edge.origin_node_id, edge.target_node_id, attributes.join(","))
+    }
+}
+
+/// one line per difference, prefixed by "+" for additions, "-" for removals and "~" for changes
+impl fmt::Display for GvGraphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.graph_style.is_empty() {
+            write!(f, "~ graph style")?;
+            fmt_style_diff(f,&self.graph_style)?;
+            writeln!(f)?;
+        }
+        for located in &self.removed_clusters {
+            writeln!(f, "- cluster {} in {}", located.element.id, fmt_path(&located.cluster_path))?;
+        }
+        for located in &self.added_clusters {
+            writeln!(f, "+ cluster {} in {}{}", located.element.id, fmt_path(&located.cluster_path), fmt_style(&located.element.style))?;
+        }
+        for change in &self.changed_clusters {
+            fmt_element_change(f,"cluster",change)?;
+        }
+        for located in &self.removed_nodes {
+            writeln!(f, "- node {} in {}", located.element.id, fmt_path(&located.cluster_path))?;
+        }
+        for located in &self.added_nodes {
+            writeln!(f, "+ node {} in {}{}", located.element.id, fmt_path(&located.cluster_path), fmt_style(&located.element.style))?;
+        }
+        for change in &self.changed_nodes {
+            fmt_element_change(f,"node",change)?;
+        }
+        for located in &self.removed_edges {
+            writeln!(f, "- edge {} in {}", fmt_edge(&located.element), fmt_path(&located.cluster_path))?;
+        }
+        for located in &self.added_edges {
+            writeln!(f, "+ edge {} in {}", fmt_edge(&located.element), fmt_path(&located.cluster_path))?;
+        }
+        for change in &self.changed_edges {
+            write!(f, "~ edge {}->{} in {}", change.new.element.origin_node_id, change.new.element.target_node_id, fmt_path(&change.new.cluster_path))?;
+            if change.old.cluster_path != change.new.cluster_path {
+                write!(f, " (moved from {})", fmt_path(&change.old.cluster_path))?;
+            }
+            for (attribute,old,new) in [("ltail",&change.old.element.origin_cluster,&change.new.element.origin_cluster),
+                                        ("lhead",&change.old.element.target_cluster,&change.new.element.target_cluster)] {
+                if old != new {
+                    write!(f, " {}: {} -> {}", attribute, old.as_deref().unwrap_or("none"), new.as_deref().unwrap_or("none"))?;
+                }
+            }
+            fmt_style_diff(f,&change.style)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// all the nodes and clusters (without their content) in depth-first order, and all the edges, with their cluster paths
+#[derive(Default)]
+struct Elements {
+    nodes : Vec<GvLocated<GraphVizNode>>,
+    clusters : Vec<GvLocated<GraphVizCluster>>,
+    edges : Vec<GvLocated<GraphVizEdge>>
+}
+
+/// edges are collected in the same order as in "GraphVizDiGraph::for_each_edge_mut"
+fn collect_elements(items : &mut dyn Iterator<Item=&GraphVizGraphItem>,
+                    edges : &[GraphVizEdge],
+                    path : &mut Vec<String>,
+                    elements : &mut Elements) {
+    for edge in edges {
+        elements.edges.push(GvLocated{cluster_path:path.clone(),element:edge.clone()});
+    }
+    for item in items {
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                elements.nodes.push(GvLocated{cluster_path:path.clone(),element:node.clone()});
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                let header = GraphVizCluster::new(cluster.id.clone(),cluster.style.clone(),vec![],vec![]);
+                elements.clusters.push(GvLocated{cluster_path:path.clone(),element:header});
+                path.push(cluster.id.clone());
+                collect_elements(&mut cluster.items.iter().map(|item| item.as_ref()),&cluster.edges,path,elements);
+                path.pop();
+            }
+        }
+    }
+}
+
+fn elements_of(graph : &GraphVizDiGraph) -> Elements {
+    let mut elements = Elements::default();
+    collect_elements(&mut graph.items.iter(),&graph.edges,&mut vec![],&mut elements);
+    elements
+}
+
+/// origin, target and rank among the edges with the same origin and target
+type EdgeKey = (String,String,usize);
+
+fn edge_keys<'a>(edges : impl Iterator<Item=&'a GraphVizEdge>) -> Vec<EdgeKey> {
+    let mut ranks : HashMap<(&str,&str),usize> = HashMap::new();
+    edges.map(|edge| {
+        let rank = ranks.entry((&edge.origin_node_id,&edge.target_node_id)).or_insert(0);
+        *rank += 1;
+        (edge.origin_node_id.clone(),edge.target_node_id.clone(),*rank - 1)
+    }).collect()
+}
+
+/// added, removed and changed elements identified by id
+#[allow(clippy::type_complexity)]
+fn diff_by_id<T : Clone>(old : &[GvLocated<T>],
+                         new : &[GvLocated<T>],
+                         id : fn(&T) -> &str,
+                         style : fn(&T) -> &GraphvizNodeStyle)
+        -> (Vec<GvLocated<T>>,Vec<GvLocated<T>>,Vec<GvElementChange<GraphvizNodeStyleItem>>) {
+    let old_by_id : HashMap<&str,&GvLocated<T>> = old.iter().map(|located| (id(&located.element),located)).collect();
+    let new_by_id : HashMap<&str,&GvLocated<T>> = new.iter().map(|located| (id(&located.element),located)).collect();
+    let mut added = vec![];
+    let mut changed = vec![];
+    for located in new {
+        match old_by_id.get(id(&located.element)) {
+            None => {
+                added.push(located.clone());
+            },
+            Some(previous) => {
+                let style_diff = GvStyleDiff::new(style(&previous.element),style(&located.element));
+                if !style_diff.is_empty() || previous.cluster_path != located.cluster_path {
+                    changed.push(GvElementChange{id:id(&located.element).to_string(),
+                        old_cluster_path:previous.cluster_path.clone(),
+                        new_cluster_path:located.cluster_path.clone(),
+                        style:style_diff});
+                }
+            }
+        }
+    }
+    let removed = old.iter().filter(|located| !new_by_id.contains_key(id(&located.element))).cloned().collect();
+    (added,removed,changed)
+}
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Differences from this graph (the old version) to the other one (the new version).
+     Nodes and clusters are matched by id and edges by origin and target.
+     **/
+    pub fn diff(&self, other : &GraphVizDiGraph) -> GvGraphDiff {
+        let old = elements_of(self);
+        let new = elements_of(other);
+        let (added_nodes,removed_nodes,changed_nodes) = diff_by_id(
+            &old.nodes,&new.nodes,|node| &node.id,|node| &node.style);
+        let (added_clusters,removed_clusters,changed_clusters) = diff_by_id(
+            &old.clusters,&new.clusters,|cluster| &cluster.id,|cluster| &cluster.style);
+        // ***
+        let old_keys = edge_keys(old.edges.iter().map(|located| &located.element));
+        let new_keys = edge_keys(new.edges.iter().map(|located| &located.element));
+        let old_by_key : HashMap<&EdgeKey,&GvLocated<GraphVizEdge>> = old_keys.iter().zip(old.edges.iter()).collect();
+        let new_by_key : HashMap<&EdgeKey,&GvLocated<GraphVizEdge>> = new_keys.iter().zip(new.edges.iter()).collect();
+        let mut added_edges = vec![];
+        let mut changed_edges = vec![];
+        for (key,located) in new_keys.iter().zip(new.edges.iter()) {
+            match old_by_key.get(key) {
+                None => {
+                    added_edges.push(located.clone());
+                },
+                Some(previous) => {
+                    if *previous != located {
+                        changed_edges.push(GvEdgeChange{old:(*previous).clone(),
+                            new:located.clone(),
+                            style:GvStyleDiff::new(&previous.element.style,&located.element.style)});
+                    }
+                }
+            }
+        }
+        let removed_edges = old_keys.iter().zip(old.edges.iter())
+            .filter(|(key,_)| !new_by_key.contains_key(key))
+            .map(|(_,located)| located.clone())
+            .collect();
+        // ***
+        GvGraphDiff{graph_style:GvStyleDiff::new(&self.style,&other.style),
+            added_nodes,removed_nodes,changed_nodes,
+            added_clusters,removed_clusters,changed_clusters,
+            added_edges,removed_edges,changed_edges}
+    }
+
+}
+
+
+
+impl GvGraphDiff {
+
+    pub fn is_empty(&self) -> bool {
+        self.graph_style.is_empty()
+            && self.added_nodes.is_empty() && self.removed_nodes.is_empty() && self.changed_nodes.is_empty()
+            && self.added_clusters.is_empty() && self.removed_clusters.is_empty() && self.changed_clusters.is_empty()
+            && self.added_edges.is_empty() && self.removed_edges.is_empty() && self.changed_edges.is_empty()
+    }
+
+    /**
+     Both versions of the graph drawn as one, starting from the new version given as argument
+     (which must be the one from which the diff was computed) :
+     additions are green, removals (which are put back at their former location) red and dashed,
+     and changed (or moved) nodes, clusters and edges orange.
+     **/
+    pub fn to_merged_graph(&self, new : &GraphVizDiGraph) -> GraphVizDiGraph {
+        let mut merged = GraphVizDiGraph{style:new.style.clone(),items:new.items.clone(),edges:new.edges.clone()};
+        // *** highlights the additions and changes
+        for located in &self.added_nodes {
+            if let Some(node) = merged.find_node_mut(&located.element.id) {
+                highlight_node_style(&mut node.style,GraphvizColor::green3,None);
+            }
+        }
+        for change in &self.changed_nodes {
+            if let Some(node) = merged.find_node_mut(&change.id) {
+                highlight_node_style(&mut node.style,GraphvizColor::orange,None);
+            }
+        }
+        for located in &self.added_clusters {
+            if let Some(cluster) = merged.find_cluster_mut(&located.element.id) {
+                highlight_node_style(&mut cluster.style,GraphvizColor::green3,None);
+            }
+        }
+        for change in &self.changed_clusters {
+            if let Some(cluster) = merged.find_cluster_mut(&change.id) {
+                highlight_node_style(&mut cluster.style,GraphvizColor::orange,None);
+            }
+        }
+        let highlights = self.edge_highlights(new);
+        let mut index = 0;
+        merged.for_each_edge_mut(|edge| {
+            if let Some(color) = &highlights[index] {
+                highlight_edge_style(&mut edge.style,color.clone(),None);
+            }
+            index += 1;
+        });
+        // *** puts back the removals, clusters first so that they may contain removed nodes and edges
+        for located in &self.removed_clusters {
+            let mut cluster = located.element.clone();
+            highlight_node_style(&mut cluster.style,GraphvizColor::red,Some(GvNodeStyleKind::Dashed));
+            match located.cluster_path.last().and_then(|parent_id| merged.find_cluster_mut(parent_id)) {
+                Some(parent) => parent.add_cluster(cluster),
+                None => merged.add_cluster(cluster)
+            }
+        }
+        for located in &self.removed_nodes {
+            let mut node = located.element.clone();
+            highlight_node_style(&mut node.style,GraphvizColor::red,Some(GvNodeStyleKind::Dashed));
+            match located.cluster_path.last().and_then(|parent_id| merged.find_cluster_mut(parent_id)) {
+                Some(parent) => parent.add_node(node),
+                None => merged.add_node(node)
+            }
+        }
+        for located in &self.removed_edges {
+            let mut edge = located.element.clone();
+            highlight_edge_style(&mut edge.style,GraphvizColor::red,Some(GvEdgeLineStyle::Dashed));
+            match located.cluster_path.last().and_then(|parent_id| merged.find_cluster_mut(parent_id)) {
+                Some(parent) => parent.add_edge(edge),
+                None => merged.add_edge(edge)
+            }
+        }
+        merged
+    }
+
+    /**
+     Color of each edge of the new version (in the order of "GraphVizDiGraph::for_each_edge_mut") in the merged graph.
+     Added and changed edges are matched in order with the edges of the new version declared at the same location
+     (identical edges being indistinguishable in the drawing anyway).
+     **/
+    fn edge_highlights(&self, new : &GraphVizDiGraph) -> Vec<Option<GraphvizColor>> {
+        let mut pending : Vec<(&GvLocated<GraphVizEdge>,GraphvizColor)> = vec![];
+        pending.extend(self.added_edges.iter().map(|located| (located,GraphvizColor::green3)));
+        pending.extend(self.changed_edges.iter().map(|change| (&change.new,GraphvizColor::orange)));
+        elements_of(new).edges.iter().map(|located| {
+            let position = pending.iter().position(|(candidate,_)| *candidate == located)?;
+            Some(pending.remove(position).1)
+        }).collect()
+    }
+
+}
+
+fn highlight_node_style(style : &mut GraphvizNodeStyle, color : GraphvizColor, kind : Option<GvNodeStyleKind>) {
+    set_node_style_item(style,GraphvizNodeStyleItem::Color(color.clone()));
+    set_node_style_item(style,GraphvizNodeStyleItem::FontColor(color));
+    set_node_style_item(style,GraphvizNodeStyleItem::PenWidth(2));
+    if let Some(kind) = kind {
+        set_node_style_item(style,GraphvizNodeStyleItem::Style(vec![kind]));
+    }
+}
+
+fn highlight_edge_style(style : &mut Vec<GraphvizEdgeStyleItem>, color : GraphvizColor, line_style : Option<GvEdgeLineStyle>) {
+    set_style_item(style,GraphvizEdgeStyleItem::Color(color.clone()));
+    set_style_item(style,GraphvizEdgeStyleItem::FontColor(color));
+    if let Some(line_style) = line_style {
+        set_style_item(style,GraphvizEdgeStyleItem::LineStyle(line_style));
+    }
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn old() -> GraphVizDiGraph {
+        crate::digraph!{
+            a; m; s [label = "S"]; gone;
+            subgraph c1 { label = "C1"; x; }
+            subgraph c2 { y; }
+            subgraph dropped { r; r -> a; }
+            a -> x;
+            a -> gone;
+            x -> y [color = red];
+            m -> y;
+            a -> y [lhead = c2];
+        }
+    }
+
+    fn new() -> GraphVizDiGraph {
+        crate::digraph!{
+            a; s [label = "T"]; fresh;
+            subgraph c1 {
+                label = "C"; x; m;
+                subgraph c2 { y; }
+                m -> y;
+            }
+            subgraph added { z; }
+            a -> x;
+            x -> y [color = blue];
+            a -> y;
+            a -> fresh;
+            fresh -> z;
+        }
+    }
+
+    #[test]
+    fn display() {
+        let diff = old().diff(&new());
+        assert!(!diff.is_empty());
+        assert!(old().diff(&old()).is_empty());
+        assert_eq!(diff.to_string(),
+                   "- cluster dropped in graph\n\
+                    + cluster added in graph\n\
+                    ~ cluster c1 in graph -[label=\"C1\"] +[label=\"C\"]\n\
+                    ~ cluster c2 in graph > c1 (moved from graph)\n\
+                    - node gone in graph\n\
+                    - node r in graph > dropped\n\
+                    + node fresh in graph\n\
+                    + node z in graph > added\n\
+                    ~ node s in graph -[label=\"S\"] +[label=\"T\"]\n\
+                    ~ node m in graph > c1 (moved from graph)\n\
+                    ~ node y in graph > c1 > c2 (moved from graph > c2)\n\
+                    - edge a->gone in graph\n\
+                    - edge r->a in graph > dropped\n\
+                    + edge a->fresh in graph\n\
+                    + edge fresh->z in graph\n\
+                    ~ edge x->y in graph -[color=red] +[color=blue]\n\
+                    ~ edge a->y in graph lhead: c2 -> none\n\
+                    ~ edge m->y in graph > c1 (moved from graph)\n");
+    }
+
+    #[test]
+    fn edge_highlights() {
+        let new = new();
+        let highlights = old().diff(&new).edge_highlights(&new);
+        // a->x, x->y, a->y, a->fresh and fresh->z at the root and then m->y in c1
+        assert!(highlights == vec![None,
+                                   Some(GraphvizColor::orange),
+                                   Some(GraphvizColor::orange),
+                                   Some(GraphvizColor::green3),
+                                   Some(GraphvizColor::green3),
+                                   Some(GraphvizColor::orange)]);
+        // identical edges being indistinguishable, the first one is highlighted
+        let old = crate::digraph!{ a -> b; };
+        let new = crate::digraph!{ a -> b; a -> b; a -> b [color = red]; };
+        assert!(old.diff(&new).edge_highlights(&new) == vec![Some(GraphvizColor::green3),None,Some(GraphvizColor::green3)]);
+    }
+
+    #[test]
+    fn merged_graph() {
+        let new = new();
+        let merged = old().diff(&new).to_merged_graph(&new);
+        assert_eq!(merged.to_dot_string(),
+                   "digraph G {\ncompound=true;\n\
+                    \ta;\n\
+                    \ts [label=\"T\",color=orange,fontcolor=orange,penwidth=2];\n\
+                    \tfresh [color=green3,fontcolor=green3,penwidth=2];\n\
+                    \tsubgraph cluster_c1 {\nlabel=\"C\";\ncolor=orange;\nfontcolor=orange;\npenwidth=2;\n\
+                    \tx;\n\
+                    \tm [color=orange,fontcolor=orange,penwidth=2];\n\
+                    \tsubgraph cluster_c2 {\ncolor=orange;\nfontcolor=orange;\npenwidth=2;\n\
+                    \ty [color=orange,fontcolor=orange,penwidth=2];\n}\n\
+                    \tm->y [color=orange,fontcolor=orange];\n}\n\
+                    \tsubgraph cluster_added {\ncolor=green3;\nfontcolor=green3;\npenwidth=2;\n\
+                    \tz [color=green3,fontcolor=green3,penwidth=2];\n}\n\
+                    \tsubgraph cluster_dropped {\ncolor=red;\nfontcolor=red;\npenwidth=2;\nstyle=\"dashed\";\n\
+                    \tr [color=red,fontcolor=red,penwidth=2,style=\"dashed\"];\n\
+                    \tr->a [color=red,fontcolor=red,style=dashed];\n}\n\
+                    \tgone [color=red,fontcolor=red,penwidth=2,style=\"dashed\"];\n\
+                    \ta->x;\n\
+                    \tx->y [color=orange,fontcolor=orange];\n\
+                    \ta->y [color=orange,fontcolor=orange];\n\
+                    \ta->fresh [color=green3,fontcolor=green3];\n\
+                    \tfresh->z [color=green3,fontcolor=green3];\n\
+                    \ta->gone [color=red,fontcolor=red,style=dashed];\n}");
+    }
+
+}
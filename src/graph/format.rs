@@ -0,0 +1,319 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use crate::edge::edge::GraphVizEdge;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::cluster::{cluster_name, GraphVizCluster};
+use crate::item::item::GraphVizGraphItem;
+use crate::traits::DotTranslatable;
+
+
+/**
+ Layout of the DOT source produced by "GraphVizDiGraph::to_formatted_dot_string".
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub struct GvDotFormat {
+    /// indentation of each level of nesting
+    pub indent : String,
+    /// attribute lists longer than this are written with one attribute per line
+    pub max_attributes_width : usize,
+    /// blank lines between the style, the items and the edges of the graph and of each cluster
+    pub blank_lines : bool,
+    /// everything on a single line, without any optional space
+    pub compact : bool
+}
+
+impl Default for GvDotFormat {
+    fn default() -> Self {
+        GvDotFormat{indent:"    ".to_string(),max_attributes_width:80,blank_lines:true,compact:false}
+    }
+}
+
+impl GvDotFormat {
+
+    /// minified output
+    pub fn compact() -> GvDotFormat {
+        GvDotFormat{indent:String::new(),max_attributes_width:usize::MAX,blank_lines:false,compact:true}
+    }
+
+}
+
+
+/// style items such as "style=filled;fillcolor=red" are made of several attributes
+fn split_attributes(attributes : &[String]) -> Vec<String> {
+    let mut split = vec![];
+    for attribute in attributes {
+        let mut current = String::new();
+        let mut quoted = false;
+        let mut escaped = false;
+        for c in attribute.chars() {
+            match c {
+                ';' if !quoted => {
+                    split.push(std::mem::take(&mut current));
+                    continue;
+                },
+                '"' if !escaped => quoted = !quoted,
+                _ => {}
+            }
+            escaped = c == '\\' && !escaped;
+            current.push(c);
+        }
+        split.push(current);
+    }
+    split
+}
+
+
+struct DotFormatter<'a> {
+    format : &'a GvDotFormat,
+    res : String
+}
+
+impl DotFormatter<'_> {
+
+    fn line(&mut self, depth : usize, content : &str) {
+        if self.format.compact {
+            self.res.push_str(content);
+        } else {
+            for _ in 0..depth {
+                self.res.push_str(&self.format.indent);
+            }
+            self.res.push_str(content);
+            self.res.push('\n');
+        }
+    }
+
+    fn section_break(&mut self) {
+        if self.format.blank_lines && !self.format.compact {
+            self.res.push('\n');
+        }
+    }
+
+    /// a node or edge statement with its attribute list
+    fn statement(&mut self, depth : usize, head : &str, attributes : &[String]) {
+        let attributes = split_attributes(attributes);
+        if attributes.is_empty() {
+            self.line(depth,&format!("{};", head));
+            return;
+        }
+        let (open,separator) = if self.format.compact {("[",",")} else {(" [",", ")};
+        let inline = attributes.join(separator);
+        if inline.len() <= self.format.max_attributes_width {
+            self.line(depth,&format!("{}{}{}];", head, open, inline));
+        } else {
+            self.line(depth,&format!("{}{}", head, open));
+            for (index,attribute) in attributes.iter().enumerate() {
+                let comma = if index + 1 < attributes.len() {","} else {""};
+                self.line(depth + 1,&format!("{}{}", attribute, comma));
+            }
+            self.line(depth,"];");
+        }
+    }
+
+    /// the style, items and edges of the graph or of a cluster, separated by blank lines
+    fn block(&mut self,
+             depth : usize,
+             style : Vec<String>,
+             items : &mut dyn Iterator<Item=&GraphVizGraphItem>,
+             edges : &[GraphVizEdge]) {
+        let items : Vec<&GraphVizGraphItem> = items.collect();
+        let mut sections = 0;
+        if !style.is_empty() {
+            for item in &style {
+                self.line(depth,&format!("{};", item));
+            }
+            sections += 1;
+        }
+        if !items.is_empty() {
+            if sections > 0 {
+                self.section_break();
+            }
+            for item in items {
+                match item {
+                    GraphVizGraphItem::Node(node) => {
+                        self.statement(depth,&node.id,&node.dot_attributes());
+                    },
+                    GraphVizGraphItem::Cluster(cluster) => {
+                        self.cluster(depth,cluster);
+                    }
+                }
+            }
+            sections += 1;
+        }
+        if !edges.is_empty() {
+            if sections > 0 {
+                self.section_break();
+            }
+            let arrow = if self.format.compact {"->"} else {" -> "};
+            for edge in edges {
+                self.statement(depth,&format!("{}{}{}", edge.origin_node_id, arrow, edge.target_node_id),&edge.dot_attributes());
+            }
+        }
+    }
+
+    fn cluster(&mut self, depth : usize, cluster : &GraphVizCluster) {
+        let open = if self.format.compact {"{"} else {" {"};
+        self.line(depth,&format!("subgraph {}{}", cluster_name(&cluster.id), open));
+        let style = cluster.style.iter().map(|item| item.to_dot_string()).collect();
+        self.block(depth + 1,style,&mut cluster.items.iter().map(|item| item.as_ref()),&cluster.edges);
+        self.line(depth,"}");
+    }
+
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     DOT source of the graph, laid out according to the given format
+     (which does not change the drawing, contrary to the style of the graph).
+     Contrary to "to_dot_string", whose layout is kept raw, the content of clusters is indented according to its depth.
+     **/
+    pub fn to_formatted_dot_string(&self, format : &GvDotFormat) -> String {
+        let mut formatter = DotFormatter{format,res:String::new()};
+        let open = if format.compact {"{"} else {" {"};
+        formatter.line(0,&format!("digraph G{}", open));
+        let mut style = vec!["compound=true".to_string()];
+        style.extend(self.style.iter().map(|item| item.to_dot_string()));
+        formatter.block(1,style,&mut self.items.iter(),&self.edges);
+        formatter.line(0,"}");
+        formatter.res
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn nested() -> GraphVizDiGraph {
+        crate::digraph!{
+            rankdir = LR;
+            a [label = "A"];
+            subgraph c1 {
+                label = "C1";
+                b;
+                subgraph c2 {
+                    c;
+                    c -> b;
+                }
+            }
+            a -> b [lhead = c1];
+        }
+    }
+
+    #[test]
+    fn clusters_are_indented_by_depth() {
+        let expected = [
+            "digraph G {",
+            "    compound=true;",
+            "    rankdir=lr;",
+            "",
+            "    a [label=\"A\"];",
+            "    subgraph cluster_c1 {",
+            "        label=\"C1\";",
+            "",
+            "        b;",
+            "        subgraph cluster_c2 {",
+            "            c;",
+            "",
+            "            c -> b;",
+            "        }",
+            "    }",
+            "",
+            "    a -> b [lhead=cluster_c1];",
+            "}",
+            ""
+        ];
+        assert_eq!(nested().to_formatted_dot_string(&GvDotFormat::default()),expected.join("\n"));
+    }
+
+    #[test]
+    fn custom_indent_without_blank_lines() {
+        let format = GvDotFormat{indent:"\t".to_string(),blank_lines:false,..GvDotFormat::default()};
+        let expected = [
+            "digraph G {",
+            "\tcompound=true;",
+            "\trankdir=lr;",
+            "\ta [label=\"A\"];",
+            "\tsubgraph cluster_c1 {",
+            "\t\tlabel=\"C1\";",
+            "\t\tb;",
+            "\t\tsubgraph cluster_c2 {",
+            "\t\t\tc;",
+            "\t\t\tc -> b;",
+            "\t\t}",
+            "\t}",
+            "\ta -> b [lhead=cluster_c1];",
+            "}",
+            ""
+        ];
+        assert_eq!(nested().to_formatted_dot_string(&format),expected.join("\n"));
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(nested().to_formatted_dot_string(&GvDotFormat::compact()),
+                   "digraph G{compound=true;rankdir=lr;a[label=\"A\"];subgraph cluster_c1{label=\"C1\";b;subgraph cluster_c2{c;c->b;}}a->b[lhead=cluster_c1];}");
+    }
+
+    #[test]
+    fn long_attribute_lists_are_wrapped() {
+        let graph = crate::digraph!{
+            a [label = "x;y", fillcolor = red, fontname = "Courier"];
+            b [color = red];
+            a -> b [label = "l", color = blue];
+        };
+        let format = GvDotFormat{max_attributes_width:20,..GvDotFormat::default()};
+        let expected = [
+            "digraph G {",
+            "    compound=true;",
+            "",
+            "    a [",
+            "        label=\"x;y\",",
+            "        style=filled,",
+            "        fillcolor=red,",
+            "        fontname=\"Courier\"",
+            "    ];",
+            "    b [color=red];",
+            "",
+            "    a -> b [",
+            "        label=\"l\",",
+            "        color=blue",
+            "    ];",
+            "}",
+            ""
+        ];
+        assert_eq!(graph.to_formatted_dot_string(&format),expected.join("\n"));
+    }
+
+    #[test]
+    fn quoted_semicolons_are_not_split() {
+        let attributes = ["label=\"x;y\"".to_string(),"style=filled;fillcolor=red".to_string(),"label=\"a\\\";b\"".to_string()];
+        assert_eq!(split_attributes(&attributes),vec!["label=\"x;y\"","style=filled","fillcolor=red","label=\"a\\\";b\""]);
+    }
+
+}
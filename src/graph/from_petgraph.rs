@@ -0,0 +1,194 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use petgraph::visit::{EdgeRef, IntoEdgeReferences, IntoNodeReferences, NodeIndexable, NodeRef};
+
+use crate::edge::edge::GraphVizEdge;
+use crate::edge::style::GraphvizEdgeStyle;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::{GraphvizNodeStyle, GraphvizNodeStyleItem};
+
+
+/// id of the node of the given index in the GraphViz graph
+fn petgraph_node_id(index : usize) -> String {
+    format!("n{}", index)
+}
+
+impl GraphVizDiGraph {
+
+    /**
+     Translates a petgraph graph ("Graph", "StableGraph", "GraphMap", "DiGraphMap", ...) into a GraphViz graph.
+     The node of index i is given the id "n{i}"
+     and its style is given by the "node_style" function, which takes the petgraph id and weight of the node.
+     The style of each edge is given by the "edge_style" function, which takes the petgraph ids of its source and target and its weight.
+     **/
+    pub fn from_petgraph<G, NF, EF>(graph : G,
+                                    node_style : NF,
+                                    edge_style : EF) -> GraphVizDiGraph
+        where G : IntoNodeReferences + IntoEdgeReferences + NodeIndexable,
+              NF : FnMut(G::NodeId, &G::NodeWeight) -> GraphvizNodeStyle,
+              EF : FnMut(G::NodeId, G::NodeId, &G::EdgeWeight) -> GraphvizEdgeStyle {
+        GraphVizDiGraph::from_petgraph_with_clusters(graph,node_style,edge_style,|_,_| vec![])
+    }
+
+    /**
+     Same as "from_petgraph", with the nodes being grouped into clusters.
+     The "cluster" function gives, for each node, the path of the ids of the nested clusters in which it is drawn
+     (outermost first, an empty path meaning that the node is not in any cluster).
+     Clusters are created as they are first encountered, labelled with their id.
+     **/
+    pub fn from_petgraph_with_clusters<G, NF, EF, CF>(graph : G,
+                                                      mut node_style : NF,
+                                                      mut edge_style : EF,
+                                                      mut cluster : CF) -> GraphVizDiGraph
+        where G : IntoNodeReferences + IntoEdgeReferences + NodeIndexable,
+              NF : FnMut(G::NodeId, &G::NodeWeight) -> GraphvizNodeStyle,
+              EF : FnMut(G::NodeId, G::NodeId, &G::EdgeWeight) -> GraphvizEdgeStyle,
+              CF : FnMut(G::NodeId, &G::NodeWeight) -> Vec<String> {
+        let mut gv_graph = GraphVizDiGraph::new(vec![]);
+        for node_ref in graph.node_references() {
+            let id = node_ref.id();
+            let node = GraphVizNode::new(petgraph_node_id(graph.to_index(id)),
+                                         node_style(id,node_ref.weight()));
+            let path = cluster(id,node_ref.weight());
+            gv_graph.add_node_in_clusters(&path,node,&mut |cluster_id| {
+                vec![GraphvizNodeStyleItem::Label(cluster_id.to_string())]
+            });
+        }
+        // ***
+        for edge_ref in graph.edge_references() {
+            let style = edge_style(edge_ref.source(),edge_ref.target(),edge_ref.weight());
+            gv_graph.edges.push(GraphVizEdge::new(petgraph_node_id(graph.to_index(edge_ref.source())),
+                                                  None,
+                                                  petgraph_node_id(graph.to_index(edge_ref.target())),
+                                                  None,
+                                                  style));
+        }
+        gv_graph
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::hash_map::RandomState;
+
+    use petgraph::graph::Graph;
+    use petgraph::graphmap::{DiGraphMap, UnGraphMap};
+    use petgraph::stable_graph::StableGraph;
+
+    use crate::edge::style::GraphvizEdgeStyleItem;
+    use crate::graph::graph::GraphVizDiGraph;
+    use crate::item::node::style::GraphvizNodeStyleItem;
+    use crate::traits::DotTranslatable;
+
+    fn label(text : &str) -> Vec<GraphvizNodeStyleItem> {
+        vec![GraphvizNodeStyleItem::Label(text.to_string())]
+    }
+
+    #[test]
+    fn graph() {
+        let mut graph = Graph::<&str,u32>::new();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        graph.add_edge(a,b,1);
+        graph.add_edge(b,b,2);
+        let gv_graph = GraphVizDiGraph::from_petgraph(&graph,
+                                                     |_,weight| label(weight),
+                                                     |_,_,weight| vec![GraphvizEdgeStyleItem::Label(weight.to_string())]);
+        assert_eq!(gv_graph.to_dot_string(),"digraph G {\ncompound=true;\n\tn0 [label=\"a\"];\n\tn1 [label=\"b\"];\n\tn0->n1 [label=\"1\"];\n\tn1->n1 [label=\"2\"];\n}");
+    }
+
+    #[test]
+    fn stable_graph_after_a_removal() {
+        let mut graph = StableGraph::<&str,()>::new();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        let c = graph.add_node("c");
+        graph.add_edge(a,b,());
+        graph.add_edge(a,c,());
+        graph.remove_node(b);
+        let gv_graph = GraphVizDiGraph::from_petgraph(&graph,|_,weight| label(weight),|_,_,_| vec![]);
+        assert_eq!(gv_graph.to_dot_string(),"digraph G {\ncompound=true;\n\tn0 [label=\"a\"];\n\tn2 [label=\"c\"];\n\tn0->n2;\n}");
+    }
+
+    #[test]
+    fn graph_maps() {
+        let mut directed = DiGraphMap::<u32,(),RandomState>::default();
+        directed.add_edge(3,1,());
+        directed.add_edge(1,2,());
+        let gv_graph = GraphVizDiGraph::from_petgraph(&directed,
+                                                     |id,_| label(&id.to_string()),
+                                                     |_,_,_| vec![]);
+        assert_eq!(gv_graph.to_dot_string(),"digraph G {\ncompound=true;\n\tn0 [label=\"3\"];\n\tn1 [label=\"1\"];\n\tn2 [label=\"2\"];\n\tn0->n1;\n\tn1->n2;\n}");
+        // an undirected graph map stores each edge from its smaller endpoint
+        let mut undirected = UnGraphMap::<u32,(),RandomState>::default();
+        undirected.add_edge(3,1,());
+        undirected.add_edge(2,1,());
+        let gv_graph = GraphVizDiGraph::from_petgraph(&undirected,
+                                                     |id,_| label(&id.to_string()),
+                                                     |_,_,_| vec![]);
+        assert_eq!(gv_graph.to_dot_string(),"digraph G {\ncompound=true;\n\tn0 [label=\"3\"];\n\tn1 [label=\"1\"];\n\tn2 [label=\"2\"];\n\tn1->n0;\n\tn1->n2;\n}");
+    }
+
+    #[test]
+    fn nested_clusters() {
+        let mut graph = Graph::<&str,()>::new();
+        let a = graph.add_node("x/y/a");
+        let b = graph.add_node("b");
+        let c = graph.add_node("x/c");
+        let d = graph.add_node("x/y/d");
+        graph.add_edge(a,b,());
+        graph.add_edge(c,d,());
+        let gv_graph = GraphVizDiGraph::from_petgraph_with_clusters(
+            &graph,
+            |_,_| vec![],
+            |_,_,_| vec![],
+            |_,weight| {
+                let mut path : Vec<String> = weight.split('/').map(|s| s.to_string()).collect();
+                path.pop();
+                path
+            });
+        let expected = [
+            "digraph G {",
+            "compound=true;",
+            "\tsubgraph cluster_x {",
+            "label=\"x\";",
+            "\tsubgraph cluster_y {",
+            "label=\"y\";",
+            "\tn0;",
+            "\tn3;",
+            "}",
+            "\tn2;",
+            "}",
+            "\tn1;",
+            "\tn0->n1;",
+            "\tn2->n3;",
+            "}"
+        ];
+        assert_eq!(gv_graph.to_dot_string(),expected.join("\n"));
+    }
+
+}
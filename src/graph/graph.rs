@@ -18,10 +18,12 @@ use crate::edge::edge::GraphVizEdge;
 use crate::graph::style::GraphvizGraphStyle;
 use crate::item::cluster::GraphVizCluster;
 use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
 use crate::item::node::style::GraphvizNodeStyle;
-use crate::traits::RenameableWithPrefix;
+use crate::traits::{RenameableWith, RenameableWithPrefix};
 
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GraphVizDiGraph {
     pub style : GraphvizGraphStyle,
     pub items : Vec<GraphVizGraphItem>,
@@ -54,6 +56,137 @@ impl GraphVizDiGraph {
             }
         }
     }
+
+    /**
+     Adds a node inside the nested clusters whose ids are given by the path (outermost first),
+     the missing clusters being created with the style given by the "cluster_style" function.
+     **/
+    pub(crate) fn add_node_in_clusters<F>(&mut self,
+                                          path : &[String],
+                                          node : GraphVizNode,
+                                          cluster_style : &mut F)
+        where F : FnMut(&str) -> GraphvizNodeStyle {
+        match path.split_first() {
+            None => {
+                self.items.push(GraphVizGraphItem::Node(node));
+            },
+            Some((first,rest)) => {
+                let position = self.items.iter().position(|item| {
+                    matches!(item, GraphVizGraphItem::Cluster(cluster) if cluster.id == *first)
+                });
+                let position = match position {
+                    Some(position) => position,
+                    None => {
+                        let cluster = GraphVizCluster::new(first.clone(),cluster_style(first),vec![],vec![]);
+                        self.items.push(GraphVizGraphItem::Cluster(cluster));
+                        self.items.len() - 1
+                    }
+                };
+                if let GraphVizGraphItem::Cluster(cluster) = &mut self.items[position] {
+                    add_node_in_nested_clusters(cluster,rest,node,cluster_style);
+                }
+            }
+        }
+    }
+}
+
+impl RenameableWith for GraphVizDiGraph {
+    fn rename_with_maps(&self,
+                        node_map : &dyn Fn(&str) -> String,
+                        cluster_map : &dyn Fn(&str) -> String) -> Self {
+        let new_items : Vec<GraphVizGraphItem> = self.items.iter().map(
+            |item| item.rename_with_maps(node_map,cluster_map)).collect();
+        let new_edges : Vec<GraphVizEdge> = self.edges.iter().map(
+            |edge| edge.rename_with_maps(node_map,cluster_map)).collect();
+        // ***
+        GraphVizDiGraph{style:self.style.clone(),items:new_items,edges:new_edges}
+    }
+}
+
+fn add_node_in_nested_clusters<F>(cluster : &mut GraphVizCluster,
+                                  path : &[String],
+                                  node : GraphVizNode,
+                                  cluster_style : &mut F)
+    where F : FnMut(&str) -> GraphvizNodeStyle {
+    match path.split_first() {
+        None => {
+            cluster.items.push(Box::new(GraphVizGraphItem::Node(node)));
+        },
+        Some((first,rest)) => {
+            let position = cluster.items.iter().position(|item| {
+                matches!(item.as_ref(), GraphVizGraphItem::Cluster(nested) if nested.id == *first)
+            });
+            let position = match position {
+                Some(position) => position,
+                None => {
+                    let nested = GraphVizCluster::new(first.clone(),cluster_style(first),vec![],vec![]);
+                    cluster.items.push(Box::new(GraphVizGraphItem::Cluster(nested)));
+                    cluster.items.len() - 1
+                }
+            };
+            if let GraphVizGraphItem::Cluster(nested) = cluster.items[position].as_mut() {
+                add_node_in_nested_clusters(nested,rest,node,cluster_style);
+            }
+        }
+    }
 }
 
 
+
+
+
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+
+    use super::*;
+    use crate::traits::DotTranslatable;
+
+    #[test]
+    fn json_round_trip() {
+        let graph = crate::digraph!{
+            rankdir = LR;
+            a [label = "A", shape = Rectangle, fillcolor = lightblue];
+            subgraph c1 {
+                label = "C1";
+                style = Rounded;
+                b;
+                subgraph c2 {
+                    c [fontsize = 12];
+                    c -> c;
+                }
+                b -> c [lhead = c2, style = Dashed];
+            }
+            a -> b [ltail = c1, label = "x", penwidth = 2];
+        };
+        let expected = serde_json::json!({
+            "style": [{"rankdir": "lr"}],
+            "items": [
+                {"node": {"id": "a", "style": [{"label": "A"}, {"shape": "rectangle"}, {"fillcolor": "lightblue"}]}},
+                {"cluster": {
+                    "id": "c1",
+                    "style": [{"label": "C1"}, {"style": ["rounded"]}],
+                    "items": [
+                        {"node": {"id": "b", "style": []}},
+                        {"cluster": {
+                            "id": "c2",
+                            "style": [],
+                            "items": [{"node": {"id": "c", "style": [{"fontsize": 12}]}}],
+                            "edges": [{"origin_node_id": "c", "origin_cluster": null,
+                                       "target_node_id": "c", "target_cluster": null, "style": []}]
+                        }}
+                    ],
+                    "edges": [{"origin_node_id": "b", "origin_cluster": null,
+                               "target_node_id": "c", "target_cluster": "c2", "style": [{"style": "dashed"}]}]
+                }}
+            ],
+            "edges": [{"origin_node_id": "a", "origin_cluster": "c1",
+                       "target_node_id": "b", "target_cluster": null, "style": [{"label": "x"}, {"penwidth": 2}]}]
+        });
+        let json = serde_json::to_value(&graph).unwrap();
+        assert_eq!(json,expected);
+        let read : GraphVizDiGraph = serde_json::from_value(json).unwrap();
+        assert_eq!(read.to_dot_string(),graph.to_dot_string());
+    }
+
+}
@@ -0,0 +1,659 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::edge::edge::GraphVizEdge;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::style::GraphvizGraphStyle;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::item::node::style::GraphvizNodeStyle;
+use crate::traits::DotTranslatable;
+
+
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub enum GvIndexError {
+    /// a node (resp. a cluster) with this id already exists, a node and a cluster being allowed to share an id
+    DuplicateId(String),
+    UnknownNode(String),
+    UnknownCluster(String),
+    /// a cluster cannot be moved inside itself or one of its nested clusters
+    CyclicCluster(String)
+}
+
+impl fmt::Display for GvIndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GvIndexError::DuplicateId(id) => write!(f, "duplicate id {}", id),
+            GvIndexError::UnknownNode(id) => write!(f, "unknown node {}", id),
+            GvIndexError::UnknownCluster(id) => write!(f, "unknown cluster {}", id),
+            GvIndexError::CyclicCluster(id) => write!(f, "cluster {} cannot be nested in itself", id)
+        }
+    }
+}
+
+impl std::error::Error for GvIndexError {}
+
+
+#[derive(Clone,Copy,Eq,PartialEq)]
+enum ItemKey {
+    Node(usize),
+    Cluster(usize)
+}
+
+/// position of an item in the (doubly linked) list of the items of its cluster
+#[derive(Clone)]
+struct ItemLinks {
+    /// None for items at the root of the graph
+    parent : Option<usize>,
+    previous : Option<ItemKey>,
+    next : Option<ItemKey>
+}
+
+#[derive(Clone)]
+struct IndexedNode {
+    node : GraphVizNode,
+    links : ItemLinks
+}
+
+#[derive(Clone)]
+struct IndexedCluster {
+    id : String,
+    style : GraphvizNodeStyle,
+    edges : Vec<GraphVizEdge>,
+    links : ItemLinks,
+    first : Option<ItemKey>,
+    last : Option<ItemKey>
+}
+
+
+/**
+ A directed graph with nested clusters, which produces the same DOT output as a "GraphVizDiGraph"
+ but stores nodes and clusters in arenas indexed by their ids.
+ Looking up, adding, restyling and moving nodes and clusters are done in constant time
+ (except for the check that a cluster is not moved inside itself, which is proportional to the nesting depth).
+ **/
+#[derive(Clone)]
+pub struct GraphVizIndexedDiGraph {
+    pub style : GraphvizGraphStyle,
+    nodes : Vec<Option<IndexedNode>>,
+    clusters : Vec<Option<IndexedCluster>>,
+    free_nodes : Vec<usize>,
+    free_clusters : Vec<usize>,
+    node_index : HashMap<String,usize>,
+    cluster_index : HashMap<String,usize>,
+    first : Option<ItemKey>,
+    last : Option<ItemKey>,
+    edges : Vec<GraphVizEdge>
+}
+
+impl GraphVizIndexedDiGraph {
+
+    pub fn new(style : GraphvizGraphStyle) -> GraphVizIndexedDiGraph {
+        GraphVizIndexedDiGraph{style,
+            nodes:vec![],
+            clusters:vec![],
+            free_nodes:vec![],
+            free_clusters:vec![],
+            node_index:HashMap::new(),
+            cluster_index:HashMap::new(),
+            first:None,
+            last:None,
+            edges:vec![]}
+    }
+
+    // *** arenas
+
+    fn node_entry(&self, index : usize) -> &IndexedNode {
+        self.nodes[index].as_ref().unwrap()
+    }
+
+    fn node_entry_mut(&mut self, index : usize) -> &mut IndexedNode {
+        self.nodes[index].as_mut().unwrap()
+    }
+
+    fn cluster_entry(&self, index : usize) -> &IndexedCluster {
+        self.clusters[index].as_ref().unwrap()
+    }
+
+    fn cluster_entry_mut(&mut self, index : usize) -> &mut IndexedCluster {
+        self.clusters[index].as_mut().unwrap()
+    }
+
+    fn links_mut(&mut self, key : ItemKey) -> &mut ItemLinks {
+        match key {
+            ItemKey::Node(index) => &mut self.node_entry_mut(index).links,
+            ItemKey::Cluster(index) => &mut self.cluster_entry_mut(index).links
+        }
+    }
+
+    fn links(&self, key : ItemKey) -> &ItemLinks {
+        match key {
+            ItemKey::Node(index) => &self.node_entry(index).links,
+            ItemKey::Cluster(index) => &self.cluster_entry(index).links
+        }
+    }
+
+    /// first and last items of a cluster (or of the root of the graph)
+    fn bounds_mut(&mut self, parent : Option<usize>) -> (&mut Option<ItemKey>,&mut Option<ItemKey>) {
+        match parent {
+            None => (&mut self.first,&mut self.last),
+            Some(index) => {
+                let cluster = self.cluster_entry_mut(index);
+                (&mut cluster.first,&mut cluster.last)
+            }
+        }
+    }
+
+    fn first_item(&self, parent : Option<usize>) -> Option<ItemKey> {
+        match parent {
+            None => self.first,
+            Some(index) => self.cluster_entry(index).first
+        }
+    }
+
+    fn unlink(&mut self, key : ItemKey) {
+        let ItemLinks{parent,previous,next} = self.links(key).clone();
+        match previous {
+            None => *self.bounds_mut(parent).0 = next,
+            Some(previous) => self.links_mut(previous).next = next
+        }
+        match next {
+            None => *self.bounds_mut(parent).1 = previous,
+            Some(next) => self.links_mut(next).previous = previous
+        }
+    }
+
+    /// puts the item at the end of the items of the cluster (or of the root of the graph)
+    fn link_last(&mut self, key : ItemKey, parent : Option<usize>) {
+        let (_,last) = self.bounds_mut(parent);
+        let previous = last.replace(key);
+        match previous {
+            None => *self.bounds_mut(parent).0 = Some(key),
+            Some(previous) => self.links_mut(previous).next = Some(key)
+        }
+        *self.links_mut(key) = ItemLinks{parent,previous,next:None};
+    }
+
+    fn cluster_position(&self, cluster_id : Option<&str>) -> Result<Option<usize>,GvIndexError> {
+        match cluster_id {
+            None => Ok(None),
+            Some(cluster_id) => {
+                match self.cluster_index.get(cluster_id) {
+                    None => Err(GvIndexError::UnknownCluster(cluster_id.to_string())),
+                    Some(index) => Ok(Some(*index))
+                }
+            }
+        }
+    }
+
+    /// checks that the id is not yet in the index of the nodes or in that of the clusters
+    fn check_new_id(index : &HashMap<String,usize>, id : &str) -> Result<(),GvIndexError> {
+        if index.contains_key(id) {
+            Err(GvIndexError::DuplicateId(id.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    // *** building
+
+    /// adds a node at the end of the given cluster (or of the root of the graph)
+    pub fn add_node(&mut self, node : GraphVizNode, cluster_id : Option<&str>) -> Result<(),GvIndexError> {
+        Self::check_new_id(&self.node_index,&node.id)?;
+        let parent = self.cluster_position(cluster_id)?;
+        let id = node.id.clone();
+        let entry = IndexedNode{node,links:ItemLinks{parent,previous:None,next:None}};
+        let index = match self.free_nodes.pop() {
+            Some(index) => {
+                self.nodes[index] = Some(entry);
+                index
+            },
+            None => {
+                self.nodes.push(Some(entry));
+                self.nodes.len() - 1
+            }
+        };
+        self.node_index.insert(id,index);
+        self.link_last(ItemKey::Node(index),parent);
+        Ok(())
+    }
+
+    /// adds an empty cluster at the end of the given cluster (or of the root of the graph)
+    pub fn add_cluster(&mut self,
+                       id : String,
+                       style : GraphvizNodeStyle,
+                       parent_id : Option<&str>) -> Result<(),GvIndexError> {
+        Self::check_new_id(&self.cluster_index,&id)?;
+        let parent = self.cluster_position(parent_id)?;
+        let entry = IndexedCluster{id:id.clone(),
+            style,
+            edges:vec![],
+            links:ItemLinks{parent,previous:None,next:None},
+            first:None,
+            last:None};
+        let index = match self.free_clusters.pop() {
+            Some(index) => {
+                self.clusters[index] = Some(entry);
+                index
+            },
+            None => {
+                self.clusters.push(Some(entry));
+                self.clusters.len() - 1
+            }
+        };
+        self.cluster_index.insert(id,index);
+        self.link_last(ItemKey::Cluster(index),parent);
+        Ok(())
+    }
+
+    /// adds an edge, declared in the given cluster (or at the root of the graph)
+    pub fn add_edge(&mut self, edge : GraphVizEdge, cluster_id : Option<&str>) -> Result<(),GvIndexError> {
+        match self.cluster_position(cluster_id)? {
+            None => self.edges.push(edge),
+            Some(index) => self.cluster_entry_mut(index).edges.push(edge)
+        }
+        Ok(())
+    }
+
+    // *** lookup
+
+    pub fn node_count(&self) -> usize {
+        self.node_index.len()
+    }
+
+    pub fn cluster_count(&self) -> usize {
+        self.cluster_index.len()
+    }
+
+    pub fn contains_node(&self, id : &str) -> bool {
+        self.node_index.contains_key(id)
+    }
+
+    pub fn node(&self, id : &str) -> Option<&GraphVizNode> {
+        self.node_index.get(id).map(|index| &self.node_entry(*index).node)
+    }
+
+    /// the style of the node, which can be modified (contrary to its id which is indexed)
+    pub fn node_style_mut(&mut self, id : &str) -> Option<&mut GraphvizNodeStyle> {
+        let index = *self.node_index.get(id)?;
+        Some(&mut self.node_entry_mut(index).node.style)
+    }
+
+    pub fn cluster_style(&self, id : &str) -> Option<&GraphvizNodeStyle> {
+        self.cluster_index.get(id).map(|index| &self.cluster_entry(*index).style)
+    }
+
+    pub fn cluster_style_mut(&mut self, id : &str) -> Option<&mut GraphvizNodeStyle> {
+        let index = *self.cluster_index.get(id)?;
+        Some(&mut self.cluster_entry_mut(index).style)
+    }
+
+    /// id of the cluster directly containing the node (None if the node is at the root of the graph or does not exist)
+    pub fn node_cluster(&self, id : &str) -> Option<&str> {
+        let index = self.node_index.get(id)?;
+        self.node_entry(*index).links.parent.map(|parent| self.cluster_entry(parent).id.as_str())
+    }
+
+    /// id of the cluster directly containing the cluster (None if the cluster is at the root of the graph or does not exist)
+    pub fn cluster_parent(&self, id : &str) -> Option<&str> {
+        let index = self.cluster_index.get(id)?;
+        self.cluster_entry(*index).links.parent.map(|parent| self.cluster_entry(parent).id.as_str())
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item=&GraphVizNode> {
+        self.nodes.iter().flatten().map(|entry| &entry.node)
+    }
+
+    /// edges declared at the root of the graph and in its clusters
+    pub fn edges(&self) -> impl Iterator<Item=&GraphVizEdge> {
+        self.edges.iter().chain(self.clusters.iter().flatten().flat_map(|cluster| cluster.edges.iter()))
+    }
+
+    // *** modification
+
+    /// moves the node at the end of the given cluster (or of the root of the graph)
+    pub fn move_node(&mut self, id : &str, cluster_id : Option<&str>) -> Result<(),GvIndexError> {
+        let index = *self.node_index.get(id).ok_or_else(|| GvIndexError::UnknownNode(id.to_string()))?;
+        let parent = self.cluster_position(cluster_id)?;
+        self.unlink(ItemKey::Node(index));
+        self.link_last(ItemKey::Node(index),parent);
+        Ok(())
+    }
+
+    /// moves the cluster, with its content, at the end of the given cluster (or of the root of the graph)
+    pub fn move_cluster(&mut self, id : &str, parent_id : Option<&str>) -> Result<(),GvIndexError> {
+        let index = *self.cluster_index.get(id).ok_or_else(|| GvIndexError::UnknownCluster(id.to_string()))?;
+        let parent = self.cluster_position(parent_id)?;
+        let mut ancestor = parent;
+        while let Some(ancestor_index) = ancestor {
+            if ancestor_index == index {
+                return Err(GvIndexError::CyclicCluster(id.to_string()));
+            }
+            ancestor = self.cluster_entry(ancestor_index).links.parent;
+        }
+        self.unlink(ItemKey::Cluster(index));
+        self.link_last(ItemKey::Cluster(index),parent);
+        Ok(())
+    }
+
+    /**
+     Removes the node together with its incoming and outgoing edges
+     (which requires going through all the edges).
+     **/
+    pub fn remove_node(&mut self, id : &str) -> Option<GraphVizNode> {
+        let index = self.node_index.remove(id)?;
+        self.unlink(ItemKey::Node(index));
+        let entry = self.nodes[index].take().unwrap();
+        self.free_nodes.push(index);
+        let incident = |edge : &GraphVizEdge| edge.origin_node_id == id || edge.target_node_id == id;
+        self.edges.retain(|edge| !incident(edge));
+        for cluster in self.clusters.iter_mut().flatten() {
+            cluster.edges.retain(|edge| !incident(edge));
+        }
+        Some(entry.node)
+    }
+
+    /**
+     Removes the cluster with everything it contains,
+     together with the edges from or to the nodes it contains.
+     The remaining edges which referred to it (or its nested clusters) via "ltail" or "lhead" no longer do.
+     **/
+    pub fn remove_cluster(&mut self, id : &str) -> Option<GraphVizCluster> {
+        let index = *self.cluster_index.get(id)?;
+        let cluster = self.cluster_of(index);
+        self.unlink(ItemKey::Cluster(index));
+        let mut removed_nodes = HashSet::new();
+        let mut removed_clusters = HashSet::new();
+        self.free_cluster_content(index,&mut removed_nodes,&mut removed_clusters);
+        let update = |edges : &mut Vec<GraphVizEdge>| {
+            edges.retain(|edge| !removed_nodes.contains(&edge.origin_node_id) && !removed_nodes.contains(&edge.target_node_id));
+            for edge in edges.iter_mut() {
+                if edge.origin_cluster.as_ref().is_some_and(|cluster_id| removed_clusters.contains(cluster_id)) {
+                    edge.origin_cluster = None;
+                }
+                if edge.target_cluster.as_ref().is_some_and(|cluster_id| removed_clusters.contains(cluster_id)) {
+                    edge.target_cluster = None;
+                }
+            }
+        };
+        update(&mut self.edges);
+        for other in self.clusters.iter_mut().flatten() {
+            update(&mut other.edges);
+        }
+        Some(cluster)
+    }
+
+    fn free_cluster_content(&mut self,
+                            index : usize,
+                            removed_nodes : &mut HashSet<String>,
+                            removed_clusters : &mut HashSet<String>) {
+        let mut item = self.cluster_entry(index).first;
+        while let Some(key) = item {
+            item = self.links(key).next;
+            match key {
+                ItemKey::Node(node_index) => {
+                    let entry = self.nodes[node_index].take().unwrap();
+                    self.node_index.remove(&entry.node.id);
+                    self.free_nodes.push(node_index);
+                    removed_nodes.insert(entry.node.id);
+                },
+                ItemKey::Cluster(nested) => {
+                    self.free_cluster_content(nested,removed_nodes,removed_clusters);
+                }
+            }
+        }
+        let entry = self.clusters[index].take().unwrap();
+        self.cluster_index.remove(&entry.id);
+        self.free_clusters.push(index);
+        removed_clusters.insert(entry.id);
+    }
+
+    // *** conversions
+
+    fn items_of(&self, parent : Option<usize>) -> Vec<GraphVizGraphItem> {
+        let mut items = vec![];
+        let mut item = self.first_item(parent);
+        while let Some(key) = item {
+            match key {
+                ItemKey::Node(index) => items.push(GraphVizGraphItem::Node(self.node_entry(index).node.clone())),
+                ItemKey::Cluster(index) => items.push(GraphVizGraphItem::Cluster(self.cluster_of(index)))
+            }
+            item = self.links(key).next;
+        }
+        items
+    }
+
+    fn cluster_of(&self, index : usize) -> GraphVizCluster {
+        let entry = self.cluster_entry(index);
+        GraphVizCluster::new(entry.id.clone(),
+                             entry.style.clone(),
+                             self.items_of(Some(index)).into_iter().map(Box::new).collect(),
+                             entry.edges.clone())
+    }
+
+    pub fn to_digraph(&self) -> GraphVizDiGraph {
+        let mut graph = GraphVizDiGraph::new(self.style.clone());
+        graph.items = self.items_of(None);
+        graph.edges = self.edges.clone();
+        graph
+    }
+
+    /// indexes a graph, which fails if several of its nodes or clusters have the same id
+    pub fn from_digraph(graph : &GraphVizDiGraph) -> Result<GraphVizIndexedDiGraph,GvIndexError> {
+        let mut indexed = GraphVizIndexedDiGraph::new(graph.style.clone());
+        for item in &graph.items {
+            indexed.add_item(item,None)?;
+        }
+        indexed.edges = graph.edges.clone();
+        Ok(indexed)
+    }
+
+    fn add_item(&mut self, item : &GraphVizGraphItem, parent_id : Option<&str>) -> Result<(),GvIndexError> {
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                self.add_node(node.clone(),parent_id)
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                self.add_cluster(cluster.id.clone(),cluster.style.clone(),parent_id)?;
+                for nested in &cluster.items {
+                    self.add_item(nested,Some(&cluster.id))?;
+                }
+                for edge in &cluster.edges {
+                    self.add_edge(edge.clone(),Some(&cluster.id))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+}
+
+impl DotTranslatable for GraphVizIndexedDiGraph {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
+        writer.write_str("digraph G {")?;
+        writer.write_str("\ncompound=true;")?;
+        for item in &self.style {
+            writer.write_char('\n')?;
+            item.write_dot_fmt(writer)?;
+            writer.write_char(';')?;
+        }
+        let mut item = self.first;
+        while let Some(key) = item {
+            writer.write_str("\n\t")?;
+            match key {
+                ItemKey::Node(index) => self.node_entry(index).node.write_dot_fmt(writer)?,
+                ItemKey::Cluster(index) => self.cluster_of(index).write_dot_fmt(writer)?
+            }
+            item = self.links(key).next;
+        }
+        for edge in &self.edges {
+            writer.write_str("\n\t")?;
+            edge.write_dot_fmt(writer)?;
+        }
+        writer.write_str("\n}")
+    }
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn nodes_and_clusters_have_separate_ids() {
+        let mut indexed = GraphVizIndexedDiGraph::new(vec![]);
+        indexed.add_node(GraphVizNode::new("a".to_string(),vec![]),None).unwrap();
+        indexed.add_cluster("a".to_string(),vec![],None).unwrap();
+        indexed.add_node(GraphVizNode::new("b".to_string(),vec![]),Some("a")).unwrap();
+        assert_eq!(indexed.add_node(GraphVizNode::new("a".to_string(),vec![]),Some("a")),
+                   Err(GvIndexError::DuplicateId("a".to_string())));
+        assert_eq!(indexed.add_cluster("a".to_string(),vec![],None),
+                   Err(GvIndexError::DuplicateId("a".to_string())));
+        assert_eq!(indexed.node_cluster("b"),Some("a"));
+        assert_eq!(indexed.node_cluster("a"),None);
+    }
+
+    #[test]
+    fn remove_cluster_as_in_digraph() {
+        let graph = crate::digraph!{
+            a; b;
+            subgraph c1 {
+                x;
+                subgraph c2 { y; }
+                x -> y;
+            }
+            a -> b [lhead = c2];
+            b -> a [ltail = c1, lhead = c3];
+            subgraph c3 { z; }
+            y -> a;
+            z -> b;
+        };
+        let mut indexed = GraphVizIndexedDiGraph::from_digraph(&graph).unwrap();
+        let removed = indexed.remove_cluster("c1").unwrap();
+        let mut expected = GraphVizDiGraph{style:graph.style.clone(),items:graph.items.clone(),edges:graph.edges.clone()};
+        assert!(expected.remove_cluster("c1").unwrap() == removed);
+        assert_eq!(indexed.to_dot_string(),expected.to_dot_string());
+        assert_eq!(indexed.to_dot_string(),indexed.to_digraph().to_dot_string());
+        assert!(!indexed.contains_node("x") && !indexed.contains_node("y"));
+        assert_eq!(indexed.cluster_count(),1);
+        assert!(indexed.edges().all(|edge| edge.origin_cluster.is_none() && edge.target_cluster.as_deref() != Some("c2")));
+    }
+
+    fn nested() -> GraphVizDiGraph {
+        crate::digraph!{
+            a;
+            subgraph c1 {
+                label = "C1";
+                b;
+                subgraph c2 {
+                    c;
+                    c -> b;
+                }
+            }
+            subgraph c3 {
+                d;
+            }
+            a -> c [lhead = c2];
+            d -> b [ltail = c3];
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let graph = nested();
+        let indexed = GraphVizIndexedDiGraph::from_digraph(&graph).unwrap();
+        assert_eq!(indexed.to_dot_string(),graph.to_dot_string());
+        assert_eq!(indexed.to_digraph().to_dot_string(),graph.to_dot_string());
+    }
+
+    #[test]
+    fn move_nodes() {
+        let mut indexed = GraphVizIndexedDiGraph::from_digraph(&nested()).unwrap();
+        indexed.move_node("a",Some("c2")).unwrap();
+        indexed.move_node("c",None).unwrap();
+        indexed.move_node("d",Some("c3")).unwrap();
+        assert_eq!(indexed.node_cluster("a"),Some("c2"));
+        assert_eq!(indexed.node_cluster("c"),None);
+        assert_eq!(indexed.move_node("e",None),Err(GvIndexError::UnknownNode("e".to_string())));
+        assert_eq!(indexed.move_node("a",Some("c4")),Err(GvIndexError::UnknownCluster("c4".to_string())));
+        let expected = crate::digraph!{
+            subgraph c1 {
+                label = "C1";
+                b;
+                subgraph c2 {
+                    a;
+                    c -> b;
+                }
+            }
+            subgraph c3 {
+                d;
+            }
+            c;
+            a -> c [lhead = c2];
+            d -> b [ltail = c3];
+        };
+        assert_eq!(indexed.to_dot_string(),expected.to_dot_string());
+    }
+
+    #[test]
+    fn move_clusters() {
+        let mut indexed = GraphVizIndexedDiGraph::from_digraph(&nested()).unwrap();
+        indexed.move_cluster("c2",None).unwrap();
+        indexed.move_cluster("c1",Some("c3")).unwrap();
+        assert_eq!(indexed.cluster_parent("c2"),None);
+        assert_eq!(indexed.cluster_parent("c1"),Some("c3"));
+        assert_eq!(indexed.move_cluster("c4",None),Err(GvIndexError::UnknownCluster("c4".to_string())));
+        let expected = crate::digraph!{
+            a;
+            subgraph c3 {
+                d;
+                subgraph c1 {
+                    label = "C1";
+                    b;
+                }
+            }
+            subgraph c2 {
+                c;
+                c -> b;
+            }
+            a -> c [lhead = c2];
+            d -> b [ltail = c3];
+        };
+        assert_eq!(indexed.to_dot_string(),expected.to_dot_string());
+    }
+
+    #[test]
+    fn clusters_cannot_be_nested_in_themselves() {
+        let mut indexed = GraphVizIndexedDiGraph::from_digraph(&nested()).unwrap();
+        assert_eq!(indexed.move_cluster("c1",Some("c1")),Err(GvIndexError::CyclicCluster("c1".to_string())));
+        assert_eq!(indexed.move_cluster("c1",Some("c2")),Err(GvIndexError::CyclicCluster("c1".to_string())));
+        assert_eq!(GvIndexError::CyclicCluster("c1".to_string()).to_string(),"cluster c1 cannot be nested in itself");
+        // the graph is left unchanged
+        assert_eq!(indexed.to_dot_string(),nested().to_dot_string());
+    }
+
+}
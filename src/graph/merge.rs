@@ -0,0 +1,416 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::builder::node::set_node_style_item;
+use crate::builder::set_style_item;
+use crate::edge::edge::GraphVizEdge;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+use crate::traits::{DotBuildable, RenameableWith};
+
+
+/**
+ What "GraphVizDiGraph::merge" does when a node (resp. cluster) of the merged graph
+ has the same id as a node (resp. cluster) of the graph it is merged into.
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub enum GvMergeStrategy {
+    /**
+     Nodes and clusters with the same id are the same :
+     their styles are merged (the items of the merged graph replacing those of the same kind)
+     and they stay where they already are, the content of merged clusters being added to the existing ones.
+     Edges which already exist at the same location are not duplicated.
+     **/
+    Unify,
+    /// conflicting nodes and clusters of the merged graph (and the edges referring to them) are renamed with the prefix
+    PrefixOnConflict(String),
+    /// conflicting nodes or clusters are an error, in which case the graph is left unchanged
+    ErrorOnConflict
+}
+
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub enum GvMergeError {
+    NodeConflict(String),
+    ClusterConflict(String)
+}
+
+impl fmt::Display for GvMergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GvMergeError::NodeConflict(id) => write!(f, "both graphs have a node with id {}", id),
+            GvMergeError::ClusterConflict(id) => write!(f, "both graphs have a cluster with id {}", id)
+        }
+    }
+}
+
+impl std::error::Error for GvMergeError {}
+
+
+/// the new ids of the conflicting nodes and clusters of the merged graph
+#[derive(Default)]
+struct Renaming {
+    nodes : HashMap<String,String>,
+    clusters : HashMap<String,String>
+}
+
+impl Renaming {
+
+    /// the id is prefixed as many times as needed for it to be free
+    fn rename(renamed : &mut HashMap<String,String>, id : &str, prefix : &str, taken : &mut HashSet<String>) {
+        let mut new_id = format!("{}{}", prefix, id);
+        while taken.contains(&new_id) {
+            new_id = format!("{}{}", prefix, new_id);
+        }
+        taken.insert(new_id.clone());
+        renamed.insert(id.to_string(),new_id);
+    }
+
+    fn node<'a>(&'a self, id : &'a str) -> &'a str {
+        self.nodes.get(id).map(|new_id| new_id.as_str()).unwrap_or(id)
+    }
+
+    fn cluster<'a>(&'a self, id : &'a str) -> &'a str {
+        self.clusters.get(id).map(|new_id| new_id.as_str()).unwrap_or(id)
+    }
+
+    fn edge(&self, edge : &GraphVizEdge) -> GraphVizEdge {
+        edge.rename_with_maps(&|id| self.node(id).to_string(),&|id| self.cluster(id).to_string())
+    }
+
+}
+
+/// positions of an item in the items of the graph and then in those of the nested clusters (empty for the graph itself)
+type ItemPath = Vec<usize>;
+
+/**
+ Paths of the nodes and clusters of the graph into which another one is merged, by id
+ (the first one in depth-first order if several have the same id).
+ It is built once and kept up to date as items are only ever appended to the graph during the merge.
+ **/
+#[derive(Default)]
+struct MergeIndex {
+    nodes : HashMap<String,ItemPath>,
+    clusters : HashMap<String,ItemPath>
+}
+
+impl MergeIndex {
+
+    fn new(graph : &GraphVizDiGraph) -> MergeIndex {
+        let mut index = MergeIndex::default();
+        index.add_items(&mut graph.items.iter(),&mut vec![]);
+        index
+    }
+
+    fn add_items(&mut self, items : &mut dyn Iterator<Item=&GraphVizGraphItem>, path : &mut ItemPath) {
+        for (position,item) in items.enumerate() {
+            path.push(position);
+            match item {
+                GraphVizGraphItem::Node(node) => {
+                    self.nodes.entry(node.id.clone()).or_insert_with(|| path.clone());
+                },
+                GraphVizGraphItem::Cluster(cluster) => {
+                    self.clusters.entry(cluster.id.clone()).or_insert_with(|| path.clone());
+                    self.add_items(&mut cluster.items.iter().map(|item| item.as_ref()),path);
+                }
+            }
+            path.pop();
+        }
+    }
+
+}
+
+fn item_at_mut<'a>(graph : &'a mut GraphVizDiGraph, path : &[usize]) -> &'a mut GraphVizGraphItem {
+    let mut item = &mut graph.items[path[0]];
+    for position in &path[1..] {
+        item = match item {
+            GraphVizGraphItem::Cluster(cluster) => cluster.items[*position].as_mut(),
+            GraphVizGraphItem::Node(_) => unreachable!("only clusters contain items")
+        };
+    }
+    item
+}
+
+/// the cluster at the path, None for the graph itself
+fn cluster_at_mut<'a>(graph : &'a mut GraphVizDiGraph, path : &[usize]) -> Option<&'a mut GraphVizCluster> {
+    if path.is_empty() {
+        return None;
+    }
+    match item_at_mut(graph,path) {
+        GraphVizGraphItem::Cluster(cluster) => Some(cluster),
+        GraphVizGraphItem::Node(_) => unreachable!("the path of a container is that of a cluster")
+    }
+}
+
+/// adds the item at the end of the cluster at the given path (or of the graph) and returns its path
+fn push_item(graph : &mut GraphVizDiGraph, parent : &[usize], item : GraphVizGraphItem) -> ItemPath {
+    let mut path = parent.to_vec();
+    match cluster_at_mut(graph,parent) {
+        Some(cluster) => {
+            path.push(cluster.items.len());
+            cluster.add_item(item);
+        },
+        None => {
+            path.push(graph.items.len());
+            graph.add_item(item);
+        }
+    }
+    path
+}
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Adds all the nodes, clusters (with their nesting) and edges of the other graph to this one,
+     the ids which are used in both graphs being handled according to the strategy.
+     The graph style items of the other graph replace those of the same kind.
+     **/
+    pub fn merge(&mut self, other : &GraphVizDiGraph, strategy : &GvMergeStrategy) -> Result<(),GvMergeError> {
+        let mut index = MergeIndex::new(self);
+        let mut renaming = Renaming::default();
+        match strategy {
+            GvMergeStrategy::Unify => {},
+            GvMergeStrategy::ErrorOnConflict => {
+                if let Some(node) = other.all_nodes().find(|node| index.nodes.contains_key(&node.id)) {
+                    return Err(GvMergeError::NodeConflict(node.id.clone()));
+                }
+                if let Some(cluster) = other.all_clusters().find(|cluster| index.clusters.contains_key(&cluster.id)) {
+                    return Err(GvMergeError::ClusterConflict(cluster.id.clone()));
+                }
+            },
+            GvMergeStrategy::PrefixOnConflict(prefix) => {
+                let mut taken_nodes : HashSet<String> = other.all_nodes().map(|node| node.id.clone()).collect();
+                taken_nodes.extend(index.nodes.keys().cloned());
+                for node in other.all_nodes() {
+                    if index.nodes.contains_key(&node.id) && !renaming.nodes.contains_key(&node.id) {
+                        Renaming::rename(&mut renaming.nodes,&node.id,prefix,&mut taken_nodes);
+                    }
+                }
+                let mut taken_clusters : HashSet<String> = other.all_clusters().map(|cluster| cluster.id.clone()).collect();
+                taken_clusters.extend(index.clusters.keys().cloned());
+                for cluster in other.all_clusters() {
+                    if index.clusters.contains_key(&cluster.id) && !renaming.clusters.contains_key(&cluster.id) {
+                        Renaming::rename(&mut renaming.clusters,&cluster.id,prefix,&mut taken_clusters);
+                    }
+                }
+            }
+        }
+        // ***
+        for item in &other.style {
+            set_style_item(&mut self.style,item.clone());
+        }
+        let unify = *strategy == GvMergeStrategy::Unify;
+        self.merge_items(&mut other.items.iter(),&other.edges,&[],&mut index,&renaming,unify);
+        Ok(())
+    }
+
+    /// merges several graphs, in order, into a new one
+    pub fn merge_all<'a>(graphs : impl IntoIterator<Item=&'a GraphVizDiGraph>,
+                         strategy : &GvMergeStrategy) -> Result<GraphVizDiGraph,GvMergeError> {
+        let mut merged = GraphVizDiGraph::new(vec![]);
+        for graph in graphs {
+            merged.merge(graph,strategy)?;
+        }
+        Ok(merged)
+    }
+
+    /// adds the items and edges of a cluster of the other graph (or of its root) in the cluster at the given path (or the root)
+    fn merge_items(&mut self,
+                   items : &mut dyn Iterator<Item=&GraphVizGraphItem>,
+                   edges : &[GraphVizEdge],
+                   parent : &[usize],
+                   index : &mut MergeIndex,
+                   renaming : &Renaming,
+                   unify : bool) {
+        for item in items {
+            match item {
+                GraphVizGraphItem::Node(node) => {
+                    let id = renaming.node(&node.id);
+                    match index.nodes.get(id).filter(|_| unify) {
+                        Some(path) => {
+                            if let GraphVizGraphItem::Node(existing) = item_at_mut(self,path) {
+                                for style_item in &node.style {
+                                    set_node_style_item(&mut existing.style,style_item.clone());
+                                }
+                            }
+                        },
+                        None => {
+                            let node = GraphVizNode::new(id.to_string(),node.style.clone());
+                            let path = push_item(self,parent,GraphVizGraphItem::Node(node));
+                            index.nodes.entry(id.to_string()).or_insert(path);
+                        }
+                    }
+                },
+                GraphVizGraphItem::Cluster(cluster) => {
+                    let id = renaming.cluster(&cluster.id);
+                    let path = match index.clusters.get(id).filter(|_| unify) {
+                        Some(path) => {
+                            let path = path.clone();
+                            if let Some(existing) = cluster_at_mut(self,&path) {
+                                for style_item in &cluster.style {
+                                    set_node_style_item(&mut existing.style,style_item.clone());
+                                }
+                            }
+                            path
+                        },
+                        None => {
+                            let new_cluster = GraphVizCluster::new(id.to_string(),cluster.style.clone(),vec![],vec![]);
+                            let path = push_item(self,parent,GraphVizGraphItem::Cluster(new_cluster));
+                            index.clusters.entry(id.to_string()).or_insert_with(|| path.clone());
+                            path
+                        }
+                    };
+                    self.merge_items(&mut cluster.items.iter().map(|item| item.as_ref()),&cluster.edges,&path,index,renaming,unify);
+                }
+            }
+        }
+        let existing_edges = match cluster_at_mut(self,parent) {
+            Some(cluster) => &mut cluster.edges,
+            None => &mut self.edges
+        };
+        for edge in edges {
+            let edge = renaming.edge(edge);
+            if !(unify && existing_edges.contains(&edge)) {
+                existing_edges.push(edge);
+            }
+        }
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::traits::DotTranslatable;
+
+    fn base() -> GraphVizDiGraph {
+        crate::digraph!{
+            a; m_a;
+            subgraph c1 { b; subgraph c2 { d; } }
+            subgraph m_c1 { e; }
+            a -> b;
+        }
+    }
+
+    fn other() -> GraphVizDiGraph {
+        crate::digraph!{
+            rankdir = LR;
+            a [label = "A"]; f;
+            subgraph c1 {
+                label = "C1"; c;
+                subgraph c2 { g; }
+                c -> g [lhead = c2];
+            }
+            a -> b;
+            a -> c;
+        }
+    }
+
+    #[test]
+    fn unify() {
+        let mut graph = base();
+        graph.merge(&other(),&GvMergeStrategy::Unify).unwrap();
+        let expected = crate::digraph!{
+            rankdir = LR;
+            a [label = "A"]; m_a;
+            subgraph c1 {
+                b;
+                subgraph c2 { d; g; }
+                label = "C1";
+                c;
+                c -> g [lhead = c2];
+            }
+            subgraph m_c1 { e; }
+            f;
+            a -> b;
+            a -> c;
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+        // merging again changes nothing
+        let merged = graph.to_dot_string();
+        graph.merge(&other(),&GvMergeStrategy::Unify).unwrap();
+        assert_eq!(graph.to_dot_string(),merged);
+    }
+
+    #[test]
+    fn prefix_on_conflict() {
+        let mut graph = base();
+        graph.merge(&other(),&GvMergeStrategy::PrefixOnConflict("m_".to_string())).unwrap();
+        // "m_a" and "m_c1" being already taken, "a" and "c1" of the other graph are prefixed twice
+        let expected = crate::digraph!{
+            rankdir = LR;
+            a; m_a;
+            subgraph c1 { b; subgraph c2 { d; } }
+            subgraph m_c1 { e; }
+            m_m_a [label = "A"]; f;
+            subgraph m_m_c1 {
+                label = "C1"; c;
+                subgraph m_c2 { g; }
+                c -> g [lhead = m_c2];
+            }
+            a -> b;
+            m_m_a -> b;
+            m_m_a -> c;
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+    }
+
+    #[test]
+    fn error_on_conflict() {
+        let mut graph = base();
+        assert_eq!(graph.merge(&other(),&GvMergeStrategy::ErrorOnConflict),Err(GvMergeError::NodeConflict("a".to_string())));
+        assert_eq!(graph.to_dot_string(),base().to_dot_string());
+        let clusters_only = crate::digraph!{ subgraph c2 { h; } };
+        assert_eq!(graph.merge(&clusters_only,&GvMergeStrategy::ErrorOnConflict),Err(GvMergeError::ClusterConflict("c2".to_string())));
+        assert_eq!(graph.to_dot_string(),base().to_dot_string());
+        // ***
+        let disjoint = crate::digraph!{ subgraph c3 { h; } h -> a; };
+        graph.merge(&disjoint,&GvMergeStrategy::ErrorOnConflict).unwrap();
+        let expected = crate::digraph!{
+            a; m_a;
+            subgraph c1 { b; subgraph c2 { d; } }
+            subgraph m_c1 { e; }
+            subgraph c3 { h; }
+            a -> b;
+            h -> a;
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+    }
+
+    #[test]
+    fn merge_all() {
+        let merged = GraphVizDiGraph::merge_all([&base(),&other()],&GvMergeStrategy::Unify).unwrap();
+        let mut expected = base();
+        expected.merge(&other(),&GvMergeStrategy::Unify).unwrap();
+        assert_eq!(merged.to_dot_string(),expected.to_dot_string());
+    }
+
+}
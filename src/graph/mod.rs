@@ -16,8 +16,22 @@ limitations under the License.
 
 pub mod style;
 pub mod graph;
+pub mod indexed;
+pub mod validate;
+pub mod render;
+pub mod format;
+pub mod diff;
+pub mod merge;
+pub mod subgraph;
+pub mod collapse;
+pub mod algo;
+pub mod clustering;
 
 mod build;
 mod print;
+mod query;
+mod canonical;
+#[cfg(feature = "petgraph")]
+mod from_petgraph;
 
 
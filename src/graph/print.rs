@@ -17,10 +17,10 @@ limitations under the License.
 
 
 use std::fs::File;
-use std::io::Write;
 use std::path::PathBuf;
 use std::process::Output;
 use crate::graph::graph::GraphVizDiGraph;
+use crate::graph::indexed::GraphVizIndexedDiGraph;
 use crate::traits::{DotPrintable, DotTranslatable, GraphVizOutputFormat};
 
 impl DotPrintable for GraphVizDiGraph {
@@ -29,44 +29,62 @@ impl DotPrintable for GraphVizDiGraph {
                  parent_folder_path: &[String],
                  output_file_name: &str,
                  output_file_format: &GraphVizOutputFormat) -> std::io::Result<Output> {
+        print_dot_source(self,parent_folder_path,output_file_name,output_file_format)
+    }
+}
+
+impl DotPrintable for GraphVizIndexedDiGraph {
+
+    fn print_dot(&self,
+                 parent_folder_path: &[String],
+                 output_file_name: &str,
+                 output_file_format: &GraphVizOutputFormat) -> std::io::Result<Output> {
+        print_dot_source(self,parent_folder_path,output_file_name,output_file_format)
+    }
+}
+
+/// streams the DOT source into a file and calls "dot" to draw it
+fn print_dot_source(source : &impl DotTranslatable,
+                    parent_folder_path: &[String],
+                    output_file_name: &str,
+                    output_file_format: &GraphVizOutputFormat) -> std::io::Result<Output> {
 
-        let dot_file_path : Vec<String>;
-        {
+    let dot_file_path : Vec<String>;
+    {
+        let mut fp : Vec<String> = parent_folder_path.to_vec();
+        fp.push( format!("{:}.dot", output_file_name) );
+        dot_file_path = fp;
+    }
+    // ***
+    let image_file_path : Vec<String>;
+    let command_format_argument : &str;
+    match output_file_format {
+        GraphVizOutputFormat::svg => {
             let mut fp : Vec<String> = parent_folder_path.to_vec();
-            fp.push( format!("{:}.dot", output_file_name) );
-            dot_file_path = fp;
-        }
-        // ***
-        let image_file_path : Vec<String>;
-        let command_format_argument : &str;
-        match output_file_format {
-            GraphVizOutputFormat::svg => {
-                let mut fp : Vec<String> = parent_folder_path.to_vec();
-                fp.push( format!("{:}.svg", output_file_name) );
-                // ***
-                image_file_path = fp;
-                command_format_argument = "-Tsvg:cairo";
-            },
-            GraphVizOutputFormat::png => {
-                let mut fp : Vec<String> = parent_folder_path.to_vec();
-                fp.push( format!("{:}.png", output_file_name) );
-                // ***
-                image_file_path = fp;
-                command_format_argument = "-Tpng";
-            }
+            fp.push( format!("{:}.svg", output_file_name) );
+            // ***
+            image_file_path = fp;
+            command_format_argument = "-Tsvg:cairo";
+        },
+        GraphVizOutputFormat::png => {
+            let mut fp : Vec<String> = parent_folder_path.to_vec();
+            fp.push( format!("{:}.png", output_file_name) );
+            // ***
+            image_file_path = fp;
+            command_format_argument = "-Tpng";
         }
-        // ***
-        let dot_buf : PathBuf = dot_file_path.iter().collect();
-        let mut dot_file = File::create(dot_buf.as_path()).unwrap();
-        let _ = dot_file.write(self.to_dot_string().as_bytes());
+    }
+    // ***
+    let dot_buf : PathBuf = dot_file_path.iter().collect();
+    let mut dot_file = File::create(dot_buf.as_path())?;
+    source.write_dot(&mut dot_file)?;
 
-        let img_buf: PathBuf = image_file_path.iter().collect();
+    let img_buf: PathBuf = image_file_path.iter().collect();
 
-        std::process::Command::new("dot")
-            .arg(command_format_argument)
-            .arg(dot_buf.as_path())
-            .arg("-o")
-            .arg(img_buf.as_path())
-            .output()
-    }
-}
\ No newline at end of file
+    std::process::Command::new("dot")
+        .arg(command_format_argument)
+        .arg(dot_buf.as_path())
+        .arg("-o")
+        .arg(img_buf.as_path())
+        .output()
+}
@@ -0,0 +1,482 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use std::collections::HashSet;
+
+use crate::edge::edge::GraphVizEdge;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+
+
+/// calls "visit" on every item, with the ids of the clusters in which it is nested, in depth-first order
+fn visit_items<'a>(items : &mut dyn Iterator<Item=&'a GraphVizGraphItem>,
+                   path : &mut Vec<&'a str>,
+                   visit : &mut dyn FnMut(&[&'a str],&'a GraphVizGraphItem)) {
+    for item in items {
+        visit(path,item);
+        if let GraphVizGraphItem::Cluster(cluster) = item {
+            path.push(&cluster.id);
+            visit_items(&mut cluster.items.iter().map(|item| item.as_ref()),path,visit);
+            path.pop();
+        }
+    }
+}
+
+fn find_node<'a>(items : &mut dyn Iterator<Item=&'a GraphVizGraphItem>,
+                 id : &str) -> Option<&'a GraphVizNode> {
+    for item in items {
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                if node.id == id {
+                    return Some(node);
+                }
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                if let Some(node) = find_node(&mut cluster.items.iter().map(|item| item.as_ref()),id) {
+                    return Some(node);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn find_node_mut<'a>(items : &mut dyn Iterator<Item=&'a mut GraphVizGraphItem>,
+                     id : &str) -> Option<&'a mut GraphVizNode> {
+    for item in items {
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                if node.id == id {
+                    return Some(node);
+                }
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                if let Some(node) = find_node_mut(&mut cluster.items.iter_mut().map(|item| item.as_mut()),id) {
+                    return Some(node);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn find_cluster_mut<'a>(items : &mut dyn Iterator<Item=&'a mut GraphVizGraphItem>,
+                        id : &str) -> Option<&'a mut GraphVizCluster> {
+    for item in items {
+        if let GraphVizGraphItem::Cluster(cluster) = item {
+            if cluster.id == id {
+                return Some(cluster);
+            }
+            if let Some(nested) = find_cluster_mut(&mut cluster.items.iter_mut().map(|item| item.as_mut()),id) {
+                return Some(nested);
+            }
+        }
+    }
+    None
+}
+
+fn find_cluster<'a>(items : &mut dyn Iterator<Item=&'a GraphVizGraphItem>,
+                    id : &str) -> Option<&'a GraphVizCluster> {
+    for item in items {
+        if let GraphVizGraphItem::Cluster(cluster) = item {
+            if cluster.id == id {
+                return Some(cluster);
+            }
+            if let Some(nested) = find_cluster(&mut cluster.items.iter().map(|item| item.as_ref()),id) {
+                return Some(nested);
+            }
+        }
+    }
+    None
+}
+
+/// pushes on "path" the ids of the clusters in which the node is nested and returns true if the node is found
+fn find_node_path<'a>(items : &mut dyn Iterator<Item=&'a GraphVizGraphItem>,
+                      id : &str,
+                      path : &mut Vec<&'a str>) -> bool {
+    for item in items {
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                if node.id == id {
+                    return true;
+                }
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                path.push(&cluster.id);
+                if find_node_path(&mut cluster.items.iter().map(|item| item.as_ref()),id,path) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+    }
+    false
+}
+
+/// removes the first item satisfying the predicate, looking at the items of the cluster before searching its nested clusters one by one
+fn remove_nested_item(cluster : &mut GraphVizCluster,
+                      predicate : &dyn Fn(&GraphVizGraphItem) -> bool) -> Option<GraphVizGraphItem> {
+    if let Some(position) = cluster.items.iter().position(|item| predicate(item)) {
+        return Some(*cluster.items.remove(position));
+    }
+    for item in cluster.items.iter_mut() {
+        if let GraphVizGraphItem::Cluster(nested) = item.as_mut() {
+            if let Some(removed) = remove_nested_item(nested,predicate) {
+                return Some(removed);
+            }
+        }
+    }
+    None
+}
+
+fn retain_cluster_edges(cluster : &mut GraphVizCluster, keep : &mut dyn FnMut(&GraphVizEdge) -> bool) {
+    cluster.edges.retain(|edge| keep(edge));
+    for item in cluster.items.iter_mut() {
+        if let GraphVizGraphItem::Cluster(nested) = item.as_mut() {
+            retain_cluster_edges(nested,keep);
+        }
+    }
+}
+
+fn for_each_cluster_edge_mut(cluster : &mut GraphVizCluster, update : &mut dyn FnMut(&mut GraphVizEdge)) {
+    cluster.edges.iter_mut().for_each(&mut *update);
+    for item in cluster.items.iter_mut() {
+        if let GraphVizGraphItem::Cluster(nested) = item.as_mut() {
+            for_each_cluster_edge_mut(nested,update);
+        }
+    }
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     All the nodes of the graph, including those in (nested) clusters,
+     each with the path of the ids of the clusters in which it is drawn (outermost first).
+     **/
+    pub fn nodes_with_path(&self) -> impl Iterator<Item=(Vec<&str>,&GraphVizNode)> {
+        let mut nodes = vec![];
+        visit_items(&mut self.items.iter(),&mut vec![],&mut |path,item| {
+            if let GraphVizGraphItem::Node(node) = item {
+                nodes.push((path.to_vec(),node));
+            }
+        });
+        nodes.into_iter()
+    }
+
+    /// all the nodes of the graph, including those in (nested) clusters
+    pub fn all_nodes(&self) -> impl Iterator<Item=&GraphVizNode> {
+        self.nodes_with_path().map(|(_,node)| node)
+    }
+
+    /// all the clusters of the graph, including nested ones, in depth-first order
+    pub fn all_clusters(&self) -> impl Iterator<Item=&GraphVizCluster> {
+        let mut clusters = vec![];
+        visit_items(&mut self.items.iter(),&mut vec![],&mut |_,item| {
+            if let GraphVizGraphItem::Cluster(cluster) = item {
+                clusters.push(cluster);
+            }
+        });
+        clusters.into_iter()
+    }
+
+    /// edges of the graph followed by the edges of its clusters (each cluster's own edges before those of its nested clusters)
+    pub fn all_edges(&self) -> impl Iterator<Item=&GraphVizEdge> {
+        let mut edges : Vec<&GraphVizEdge> = self.edges.iter().collect();
+        visit_items(&mut self.items.iter(),&mut vec![],&mut |_,item| {
+            if let GraphVizGraphItem::Cluster(cluster) = item {
+                edges.extend(cluster.edges.iter());
+            }
+        });
+        edges.into_iter()
+    }
+
+    pub fn find_node(&self, id : &str) -> Option<&GraphVizNode> {
+        find_node(&mut self.items.iter(),id)
+    }
+
+    pub fn find_node_mut(&mut self, id : &str) -> Option<&mut GraphVizNode> {
+        find_node_mut(&mut self.items.iter_mut(),id)
+    }
+
+    pub fn find_cluster(&self, id : &str) -> Option<&GraphVizCluster> {
+        find_cluster(&mut self.items.iter(),id)
+    }
+
+    pub fn find_cluster_mut(&mut self, id : &str) -> Option<&mut GraphVizCluster> {
+        find_cluster_mut(&mut self.items.iter_mut(),id)
+    }
+
+    /// ids of the clusters in which the node is drawn (outermost first), if the node exists
+    pub fn node_cluster_path(&self, id : &str) -> Option<Vec<&str>> {
+        let mut path = vec![];
+        if find_node_path(&mut self.items.iter(),id,&mut path) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// all the edges (including those declared in clusters) going out of the node
+    pub fn edges_from<'a>(&'a self, id : &'a str) -> impl Iterator<Item=&'a GraphVizEdge> {
+        self.all_edges().filter(move |edge| edge.origin_node_id == id)
+    }
+
+    /// all the edges (including those declared in clusters) going into the node
+    pub fn edges_to<'a>(&'a self, id : &'a str) -> impl Iterator<Item=&'a GraphVizEdge> {
+        self.all_edges().filter(move |edge| edge.target_node_id == id)
+    }
+
+    /// keeps only the edges (including those declared in clusters) satisfying the predicate
+    pub fn retain_edges<F>(&mut self, mut keep : F)
+        where F : FnMut(&GraphVizEdge) -> bool {
+        self.edges.retain(|edge| keep(edge));
+        for item in self.items.iter_mut() {
+            if let GraphVizGraphItem::Cluster(cluster) = item {
+                retain_cluster_edges(cluster,&mut keep);
+            }
+        }
+    }
+
+    /// applies a function to all the edges (including those declared in clusters)
+    pub fn for_each_edge_mut<F>(&mut self, mut update : F)
+        where F : FnMut(&mut GraphVizEdge) {
+        self.edges.iter_mut().for_each(&mut update);
+        for item in self.items.iter_mut() {
+            if let GraphVizGraphItem::Cluster(cluster) = item {
+                for_each_cluster_edge_mut(cluster,&mut update);
+            }
+        }
+    }
+
+    /**
+     Removes the node, wherever it is, together with all its incoming and outgoing edges.
+     **/
+    pub fn remove_node(&mut self, id : &str) -> Option<GraphVizNode> {
+        let predicate = |item : &GraphVizGraphItem| matches!(item, GraphVizGraphItem::Node(node) if node.id == id);
+        let removed = match self.remove_item(&predicate) {
+            Some(GraphVizGraphItem::Node(node)) => node,
+            _ => return None
+        };
+        self.retain_edges(|edge| edge.origin_node_id != id && edge.target_node_id != id);
+        Some(removed)
+    }
+
+    /**
+     Removes the cluster, wherever it is, together with everything it contains.
+     The edges from or to the nodes it contains are removed
+     and the remaining edges which referred to it (or its nested clusters) via "ltail" or "lhead" no longer do.
+     **/
+    pub fn remove_cluster(&mut self, id : &str) -> Option<GraphVizCluster> {
+        let predicate = |item : &GraphVizGraphItem| matches!(item, GraphVizGraphItem::Cluster(cluster) if cluster.id == id);
+        let removed = match self.remove_item(&predicate) {
+            Some(GraphVizGraphItem::Cluster(cluster)) => cluster,
+            _ => return None
+        };
+        let mut node_ids = HashSet::new();
+        let mut cluster_ids = HashSet::from([removed.id.as_str()]);
+        visit_items(&mut removed.items.iter().map(|item| item.as_ref()),&mut vec![],&mut |_,item| {
+            match item {
+                GraphVizGraphItem::Node(node) => node_ids.insert(node.id.as_str()),
+                GraphVizGraphItem::Cluster(cluster) => cluster_ids.insert(cluster.id.as_str())
+            };
+        });
+        self.retain_edges(|edge| {
+            !node_ids.contains(edge.origin_node_id.as_str()) && !node_ids.contains(edge.target_node_id.as_str())
+        });
+        self.for_each_edge_mut(|edge| {
+            if edge.origin_cluster.as_ref().is_some_and(|cluster_id| cluster_ids.contains(cluster_id.as_str())) {
+                edge.origin_cluster = None;
+            }
+            if edge.target_cluster.as_ref().is_some_and(|cluster_id| cluster_ids.contains(cluster_id.as_str())) {
+                edge.target_cluster = None;
+            }
+        });
+        Some(removed)
+    }
+
+    fn remove_item(&mut self, predicate : &dyn Fn(&GraphVizGraphItem) -> bool) -> Option<GraphVizGraphItem> {
+        if let Some(position) = self.items.iter().position(predicate) {
+            return Some(self.items.remove(position));
+        }
+        for item in self.items.iter_mut() {
+            if let GraphVizGraphItem::Cluster(cluster) = item {
+                if let Some(removed) = remove_nested_item(cluster,predicate) {
+                    return Some(removed);
+                }
+            }
+        }
+        None
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::traits::DotTranslatable;
+
+    fn nested() -> GraphVizDiGraph {
+        crate::digraph!{
+            a;
+            subgraph c1 {
+                b;
+                subgraph c2 {
+                    c [label = "C"];
+                    c -> b;
+                }
+                b -> c [lhead = c2];
+            }
+            subgraph c3 {
+                d;
+            }
+            a -> b [lhead = c1];
+            a -> d [lhead = c3];
+            d -> c [ltail = c3, lhead = c2];
+        }
+    }
+
+    fn edge_ends<'a>(edges : impl Iterator<Item=&'a GraphVizEdge>) -> Vec<(&'a str,&'a str)> {
+        edges.map(|edge| (edge.origin_node_id.as_str(),edge.target_node_id.as_str())).collect()
+    }
+
+    #[test]
+    fn nodes_clusters_and_edges() {
+        let graph = nested();
+        let nodes : Vec<(Vec<&str>,&str)> = graph.nodes_with_path().map(|(path,node)| (path,node.id.as_str())).collect();
+        assert_eq!(nodes,vec![(vec![],"a"),(vec!["c1"],"b"),(vec!["c1","c2"],"c"),(vec!["c3"],"d")]);
+        let clusters : Vec<&str> = graph.all_clusters().map(|cluster| cluster.id.as_str()).collect();
+        assert_eq!(clusters,vec!["c1","c2","c3"]);
+        assert_eq!(edge_ends(graph.all_edges()),vec![("a","b"),("a","d"),("d","c"),("b","c"),("c","b")]);
+        assert_eq!(edge_ends(graph.edges_from("a")),vec![("a","b"),("a","d")]);
+        assert_eq!(edge_ends(graph.edges_to("c")),vec![("d","c"),("b","c")]);
+    }
+
+    #[test]
+    fn find_nested_elements() {
+        let mut graph = nested();
+        assert_eq!(graph.find_node("c").unwrap().to_dot_string(),"c [label=\"C\"];");
+        assert!(graph.find_node("c2").is_none());
+        assert_eq!(graph.find_cluster("c2").unwrap().items.len(),1);
+        assert!(graph.find_cluster("c").is_none());
+        assert_eq!(graph.node_cluster_path("c"),Some(vec!["c1","c2"]));
+        assert_eq!(graph.node_cluster_path("a"),Some(vec![]));
+        assert_eq!(graph.node_cluster_path("e"),None);
+        // ***
+        graph.find_node_mut("d").unwrap().style.clear();
+        graph.find_cluster_mut("c2").unwrap().edges.clear();
+        graph.for_each_edge_mut(|edge| edge.target_cluster = None);
+        graph.retain_edges(|edge| edge.origin_node_id != "d");
+        let expected = crate::digraph!{
+            a;
+            subgraph c1 {
+                b;
+                subgraph c2 {
+                    c [label = "C"];
+                }
+                b -> c;
+            }
+            subgraph c3 {
+                d;
+            }
+            a -> b;
+            a -> d;
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+    }
+
+    #[test]
+    fn remove_nested_node() {
+        let mut graph = nested();
+        assert_eq!(graph.remove_node("c").unwrap().to_dot_string(),"c [label=\"C\"];");
+        assert!(graph.remove_node("c").is_none());
+        assert!(graph.remove_node("c1").is_none());
+        let expected = crate::digraph!{
+            a;
+            subgraph c1 {
+                b;
+                subgraph c2 {}
+            }
+            subgraph c3 {
+                d;
+            }
+            a -> b [lhead = c1];
+            a -> d [lhead = c3];
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+    }
+
+    #[test]
+    fn remove_cluster_with_nested_clusters() {
+        let mut graph = nested();
+        assert!(graph.remove_cluster("b").is_none());
+        let removed = graph.remove_cluster("c1").unwrap();
+        assert_eq!(removed.id,"c1");
+        assert!(graph.find_cluster("c2").is_none());
+        let expected = crate::digraph!{
+            a;
+            subgraph c3 {
+                d;
+            }
+            a -> d [lhead = c3];
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+    }
+
+    #[test]
+    fn remove_nested_cluster() {
+        let mut graph = nested();
+        graph.remove_cluster("c2").unwrap();
+        // the edges which referred to the removed cluster via "lhead" are kept without it
+        let expected = crate::digraph!{
+            a;
+            subgraph c1 {
+                b;
+            }
+            subgraph c3 {
+                d;
+            }
+            a -> b [lhead = c1];
+            a -> d [lhead = c3];
+        };
+        assert_eq!(graph.to_dot_string(),expected.to_dot_string());
+        // ***
+        let mut graph = crate::digraph!{
+            subgraph c1 {
+                subgraph c2 {
+                    a;
+                }
+                b;
+            }
+            b -> b [lhead = c2];
+        };
+        graph.remove_cluster("c2").unwrap();
+        assert_eq!(graph.to_dot_string(),"digraph G {\ncompound=true;\n\tsubgraph cluster_c1 {\n\tb;\n}\n\tb->b;\n}");
+    }
+
+}
@@ -0,0 +1,382 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use std::fmt;
+use std::process::Output;
+
+use crate::export::GvExportElement;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::traits::{DotPrintable, DotTranslatable, GraphVizOutputFormat};
+
+
+#[derive(Eq,PartialEq,Clone,Copy,Debug)]
+pub enum GvRenderSeverity {
+    Warning,
+    Error
+}
+
+/**
+ Message of Graphviz, recognized from its text.
+ Cluster names are given as ids of the GraphVizDiGraph (without the "cluster_" prefix).
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub enum GvRenderMessage {
+    /// the "lhead" cluster of the edge does not contain its target
+    HeadNotInsideCluster{origin : String, target : String, cluster : String},
+    /// the "ltail" cluster of the edge does not contain its origin
+    TailNotInsideCluster{origin : String, target : String, cluster : String},
+    HeadInsideTailCluster{origin : String, target : String, cluster : String},
+    TailInsideHeadCluster{origin : String, target : String, cluster : String},
+    /// a "lhead" or "ltail" refers to a cluster which does not exist
+    ClusterNotFound{cluster : String},
+    /// the node was declared in several clusters and removed from this one
+    NodeInSeveralClusters{node : String, cluster : String},
+    SyntaxError{line : usize, near : String},
+    UnknownShape{shape : String},
+    UnknownColor{color : String},
+    ImageNotFound{path : String},
+    /// any other message
+    Other
+}
+
+/**
+ A warning or error written by Graphviz on its standard error output,
+ with the element of the graph to which it refers, when it can be found.
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub struct GvRenderDiagnostic {
+    pub severity : GvRenderSeverity,
+    pub message : GvRenderMessage,
+    pub element : Option<GvExportElement>,
+    /// text of the message, without the "Warning: " or "Error: " prefix
+    pub text : String
+}
+
+impl fmt::Display for GvRenderDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            GvRenderSeverity::Warning => "warning",
+            GvRenderSeverity::Error => "error"
+        };
+        match &self.element {
+            None => write!(f, "{} : {}", severity, self.text),
+            Some(element) => write!(f, "{} ({}) : {}", severity, element, self.text)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GvRenderError {
+    /// the DOT file could not be written or Graphviz could not be launched
+    Io(std::io::Error),
+    /// Graphviz failed, or reported problems in strict mode
+    Failed{output : Output, diagnostics : Vec<GvRenderDiagnostic>}
+}
+
+impl fmt::Display for GvRenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GvRenderError::Io(error) => write!(f, "{}", error),
+            GvRenderError::Failed{output,diagnostics} => {
+                write!(f, "graphviz rendering failed ({})", output.status)?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n{}", diagnostic)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for GvRenderError {}
+
+
+
+fn cluster_id(name : &str) -> String {
+    name.trim().strip_prefix("cluster_").unwrap_or(name.trim()).to_string()
+}
+
+fn unquote(text : &str) -> String {
+    text.trim().trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+/// "origin -> target: rest"
+fn split_edge_message(text : &str) -> Option<(String,String,&str)> {
+    let (edge,rest) = text.split_once(": ")?;
+    let (origin,target) = edge.split_once(" -> ")?;
+    Some((origin.trim().to_string(),target.trim().to_string(),rest))
+}
+
+/// builds the message of an edge from its origin, target and cluster
+type EdgeMessage = fn(String,String,String) -> GvRenderMessage;
+
+fn parse_message(text : &str) -> GvRenderMessage {
+    if let Some((origin,target,rest)) = split_edge_message(text) {
+        let edge_messages : [(&str,EdgeMessage);4] = [
+            ("head not inside head cluster ", |origin,target,cluster| GvRenderMessage::HeadNotInsideCluster{origin,target,cluster}),
+            ("tail not inside tail cluster ", |origin,target,cluster| GvRenderMessage::TailNotInsideCluster{origin,target,cluster}),
+            ("head is inside tail cluster ", |origin,target,cluster| GvRenderMessage::HeadInsideTailCluster{origin,target,cluster}),
+            ("tail is inside head cluster ", |origin,target,cluster| GvRenderMessage::TailInsideHeadCluster{origin,target,cluster})
+        ];
+        for (prefix,message) in edge_messages {
+            if let Some(cluster) = rest.strip_prefix(prefix) {
+                return message(origin,target,cluster_id(cluster));
+            }
+        }
+    }
+    if let Some(cluster) = text.strip_prefix("cluster named ").and_then(|rest| rest.strip_suffix(" not found")) {
+        return GvRenderMessage::ClusterNotFound{cluster:cluster_id(cluster)};
+    }
+    if let Some((node,cluster)) = text.split_once(" was already in a rankset, deleted from cluster ") {
+        return GvRenderMessage::NodeInSeveralClusters{node:unquote(node),cluster:cluster_id(cluster)};
+    }
+    if let Some((_,rest)) = text.split_once("syntax error in line ") {
+        let (line,near) = match rest.split_once(" near ") {
+            Some((line,near)) => (line,unquote(near)),
+            None => (rest,String::new())
+        };
+        if let Ok(line) = line.trim().parse::<usize>() {
+            return GvRenderMessage::SyntaxError{line,near};
+        }
+    }
+    if let Some(shape) = text.strip_prefix("using box for unknown shape ") {
+        return GvRenderMessage::UnknownShape{shape:unquote(shape)};
+    }
+    if let Some(color) = text.strip_suffix(" is not a known color.") {
+        return GvRenderMessage::UnknownColor{color:unquote(color)};
+    }
+    if let Some(path) = text.strip_prefix("No or improper image file=") {
+        return GvRenderMessage::ImageNotFound{path:unquote(path)};
+    }
+    if let Some(path) = text.strip_prefix("No such file or directory while opening ") {
+        return GvRenderMessage::ImageNotFound{path:unquote(path)};
+    }
+    GvRenderMessage::Other
+}
+
+/// element declared at the given line of the DOT source
+fn element_at_line(dot : &str, line : usize) -> Option<GvExportElement> {
+    let declaration = dot.lines().nth(line.checked_sub(1)?)?.trim().trim_end_matches(';');
+    if let Some(cluster) = declaration.strip_prefix("subgraph ") {
+        return Some(GvExportElement::Cluster(cluster_id(cluster.trim_end_matches('{'))));
+    }
+    let declaration = declaration.split(" [").next()?;
+    match declaration.split_once("->") {
+        Some((origin,target)) => Some(GvExportElement::Edge(origin.trim().to_string(),target.trim().to_string())),
+        None if !declaration.is_empty() && !declaration.contains(['=','{','}']) => Some(GvExportElement::Node(declaration.to_string())),
+        None => None
+    }
+}
+
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Parses the standard error output of Graphviz when it draws this graph
+     into a list of diagnostics, each one referring to an element of the graph when it can be found.
+     **/
+    pub fn parse_graphviz_stderr(&self, stderr : &str) -> Vec<GvRenderDiagnostic> {
+        let mut diagnostics : Vec<GvRenderDiagnostic> = vec![];
+        for line in stderr.lines() {
+            let (severity,text) = if let Some(text) = line.strip_prefix("Warning: ") {
+                (GvRenderSeverity::Warning,text)
+            } else if let Some(text) = line.strip_prefix("Error: ") {
+                (GvRenderSeverity::Error,text)
+            } else {
+                // continuation of the previous message
+                if let Some(previous) = diagnostics.last_mut() {
+                    previous.text.push('\n');
+                    previous.text.push_str(line);
+                }
+                continue;
+            };
+            let text = text.trim();
+            let message = parse_message(text);
+            let element = self.element_of(&message);
+            diagnostics.push(GvRenderDiagnostic{severity,message,element,text:text.to_string()});
+        }
+        diagnostics
+    }
+
+    fn element_of(&self, message : &GvRenderMessage) -> Option<GvExportElement> {
+        match message {
+            GvRenderMessage::HeadNotInsideCluster{origin,target,..}
+            | GvRenderMessage::TailNotInsideCluster{origin,target,..}
+            | GvRenderMessage::HeadInsideTailCluster{origin,target,..}
+            | GvRenderMessage::TailInsideHeadCluster{origin,target,..} => {
+                self.all_edges()
+                    .find(|edge| edge.origin_node_id == *origin && edge.target_node_id == *target)
+                    .map(|edge| GvExportElement::Edge(edge.origin_node_id.clone(),edge.target_node_id.clone()))
+            },
+            GvRenderMessage::ClusterNotFound{cluster} => {
+                // the edge which refers to the missing cluster
+                self.all_edges()
+                    .find(|edge| edge.origin_cluster.as_ref() == Some(cluster) || edge.target_cluster.as_ref() == Some(cluster))
+                    .map(|edge| GvExportElement::Edge(edge.origin_node_id.clone(),edge.target_node_id.clone()))
+            },
+            GvRenderMessage::NodeInSeveralClusters{node,..} => {
+                self.find_node(node).map(|node| GvExportElement::Node(node.id.clone()))
+            },
+            GvRenderMessage::SyntaxError{line,..} => {
+                element_at_line(&self.to_dot_string(),*line)
+            },
+            _ => None
+        }
+    }
+
+    /**
+     Same as "print_dot", the standard error output of Graphviz being parsed into diagnostics.
+     Fails if Graphviz fails or reports an error, or, in strict mode, if it reports any warning.
+     **/
+    pub fn print_dot_checked(&self,
+                             parent_folder_path : &[String],
+                             output_file_name : &str,
+                             output_file_format : &GraphVizOutputFormat,
+                             strict : bool) -> Result<Vec<GvRenderDiagnostic>,GvRenderError> {
+        let output = self.print_dot(parent_folder_path,output_file_name,output_file_format)
+            .map_err(GvRenderError::Io)?;
+        let diagnostics = self.parse_graphviz_stderr(&String::from_utf8_lossy(&output.stderr));
+        let failed = !output.status.success()
+            || diagnostics.iter().any(|diagnostic| strict || diagnostic.severity == GvRenderSeverity::Error);
+        if failed {
+            Err(GvRenderError::Failed{output,diagnostics})
+        } else {
+            Ok(diagnostics)
+        }
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn graph() -> GraphVizDiGraph {
+        crate::digraph!{
+            a; b [shape = Hexagon];
+            subgraph c1 { x; y; x -> y; }
+            subgraph c2 { z; }
+            a -> x [lhead = c2];
+            x -> b [ltail = c1];
+            z -> a [lhead = c3];
+        }
+    }
+
+    fn single(stderr : &str) -> GvRenderDiagnostic {
+        let mut diagnostics = graph().parse_graphviz_stderr(stderr);
+        assert_eq!(diagnostics.len(),1);
+        diagnostics.remove(0)
+    }
+
+    fn edge(origin : &str, target : &str) -> Option<GvExportElement> {
+        Some(GvExportElement::Edge(origin.to_string(),target.to_string()))
+    }
+
+    // the samples follow the messages of Graphviz (lib/dotgen/compound.c, lib/dotgen/rank.c, lib/cgraph/grammar.y, lib/common)
+
+    #[test]
+    fn compound_edges() {
+        let diagnostic = single("Warning: a -> x: head not inside head cluster cluster_c2\n");
+        assert_eq!(diagnostic.severity,GvRenderSeverity::Warning);
+        assert_eq!(diagnostic.message,GvRenderMessage::HeadNotInsideCluster{origin:"a".to_string(),target:"x".to_string(),cluster:"c2".to_string()});
+        assert_eq!(diagnostic.element,edge("a","x"));
+        assert_eq!(diagnostic.text,"a -> x: head not inside head cluster cluster_c2");
+        assert_eq!(diagnostic.to_string(),"warning (edge a->x) : a -> x: head not inside head cluster cluster_c2");
+        // ***
+        let diagnostic = single("Warning: x -> b: tail not inside tail cluster cluster_c1");
+        assert_eq!(diagnostic.message,GvRenderMessage::TailNotInsideCluster{origin:"x".to_string(),target:"b".to_string(),cluster:"c1".to_string()});
+        assert_eq!(diagnostic.element,edge("x","b"));
+        // ***
+        let diagnostic = single("Warning: x -> y: head is inside tail cluster cluster_c1");
+        assert_eq!(diagnostic.message,GvRenderMessage::HeadInsideTailCluster{origin:"x".to_string(),target:"y".to_string(),cluster:"c1".to_string()});
+        assert_eq!(diagnostic.element,edge("x","y"));
+        // ***
+        let diagnostic = single("Warning: x -> y: tail is inside head cluster cluster_c1");
+        assert_eq!(diagnostic.message,GvRenderMessage::TailInsideHeadCluster{origin:"x".to_string(),target:"y".to_string(),cluster:"c1".to_string()});
+        // an edge which is not in the graph
+        let diagnostic = single("Warning: y -> a: head not inside head cluster cluster_c2");
+        assert_eq!(diagnostic.element,None);
+    }
+
+    #[test]
+    fn missing_cluster_and_ranksets() {
+        let diagnostic = single("Warning: cluster named cluster_c3 not found\n");
+        assert_eq!(diagnostic.message,GvRenderMessage::ClusterNotFound{cluster:"c3".to_string()});
+        assert_eq!(diagnostic.element,edge("z","a"));
+        // ***
+        let diagnostic = single("Warning: z was already in a rankset, deleted from cluster cluster_c2\n");
+        assert_eq!(diagnostic.message,GvRenderMessage::NodeInSeveralClusters{node:"z".to_string(),cluster:"c2".to_string()});
+        assert_eq!(diagnostic.element,Some(GvExportElement::Node("z".to_string())));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let diagnostic = single("Error: /tmp/graph.dot: syntax error in line 13 near '->'\n");
+        assert_eq!(diagnostic.severity,GvRenderSeverity::Error);
+        assert_eq!(diagnostic.message,GvRenderMessage::SyntaxError{line:13,near:"->".to_string()});
+        assert_eq!(diagnostic.element,edge("a","x"));
+        // with the context written by older versions of Graphviz on the next line
+        let diagnostic = single("Error: syntax error in line 4 near '['\ncontext: \tb >>>  [ <<< shape=hexagon];\n");
+        assert_eq!(diagnostic.message,GvRenderMessage::SyntaxError{line:4,near:"[".to_string()});
+        assert_eq!(diagnostic.element,Some(GvExportElement::Node("b".to_string())));
+        assert_eq!(diagnostic.text,"syntax error in line 4 near '['\ncontext: \tb >>>  [ <<< shape=hexagon];");
+        // ***
+        let diagnostic = single("Error: <stdin>: syntax error in line 5");
+        assert_eq!(diagnostic.message,GvRenderMessage::SyntaxError{line:5,near:String::new()});
+        assert_eq!(diagnostic.element,Some(GvExportElement::Cluster("c1".to_string())));
+        // the closing brace of the graph
+        assert_eq!(single("Error: syntax error in line 16 near '}'").element,None);
+    }
+
+    #[test]
+    fn unknown_attribute_values() {
+        assert_eq!(single("Warning: using box for unknown shape hexagonal\n").message,
+                   GvRenderMessage::UnknownShape{shape:"hexagonal".to_string()});
+        assert_eq!(single("Warning: bluish is not a known color.\n").message,
+                   GvRenderMessage::UnknownColor{color:"bluish".to_string()});
+        assert_eq!(single("Warning: No or improper image file=\"images/missing.png\"\n").message,
+                   GvRenderMessage::ImageNotFound{path:"images/missing.png".to_string()});
+        assert_eq!(single("Warning: No such file or directory while opening images/missing.png\n").message,
+                   GvRenderMessage::ImageNotFound{path:"images/missing.png".to_string()});
+        let diagnostic = single("Warning: Unable to load font \"Foo\"");
+        assert_eq!(diagnostic.message,GvRenderMessage::Other);
+        assert_eq!(diagnostic.element,None);
+    }
+
+    #[test]
+    fn several_messages() {
+        let stderr = "Warning: cluster named cluster_c3 not found\n\
+                      Warning: bluish is not a known color.\n\
+                      Error: syntax error in line 14 near 'ltail'\n";
+        let diagnostics = graph().parse_graphviz_stderr(stderr);
+        let severities : Vec<GvRenderSeverity> = diagnostics.iter().map(|diagnostic| diagnostic.severity).collect();
+        assert_eq!(severities,vec![GvRenderSeverity::Warning,GvRenderSeverity::Warning,GvRenderSeverity::Error]);
+        assert_eq!(diagnostics[2].element,edge("x","b"));
+        // lines before the first message are ignored
+        assert!(graph().parse_graphviz_stderr("dot: graph is too large\n").is_empty());
+    }
+
+}
@@ -16,11 +16,15 @@ limitations under the License.
 
 
 
-use strum_macros::IntoStaticStr;
+use std::fmt;
+use strum_macros::{EnumString, IntoStaticStr};
 
-use crate::traits::DotTranslatable;
+use crate::traits::{write_lowercase, DotTranslatable};
 
-#[derive(IntoStaticStr,Eq,PartialEq,Clone)]
+#[derive(IntoStaticStr,EnumString,Eq,PartialEq,Clone)]
+#[strum(ascii_case_insensitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GvGraphRankDir {
     TB,
     BT,
@@ -29,9 +33,9 @@ pub enum GvGraphRankDir {
 }
 
 impl DotTranslatable for GvGraphRankDir {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         let as_static_str : &'static str = self.into();
-        as_static_str.to_string().to_lowercase()
+        write_lowercase(writer, as_static_str)
     }
 }
 
@@ -39,6 +43,8 @@ impl DotTranslatable for GvGraphRankDir {
 
 
 #[derive(IntoStaticStr,Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GraphvizSplines {
     None, // doesn't draw edges at all
     Line, // draw edges as straight line, can be drawn over nodes
@@ -50,6 +56,8 @@ pub enum GraphvizSplines {
 
 
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GraphvizGraphStyleItem {
     Rankdir(GvGraphRankDir),
     NodeSep(u32,u32),
@@ -60,20 +68,22 @@ pub enum GraphvizGraphStyleItem {
 
 
 impl DotTranslatable for GraphvizGraphStyleItem {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         match self {
             GraphvizGraphStyleItem::Rankdir(ref rd) => {
-                format!("rankdir={}", rd.to_dot_string())
+                writer.write_str("rankdir=")?;
+                rd.write_dot_fmt(writer)
             },
             GraphvizGraphStyleItem::NodeSep(unit,decimal) => {
-                format!("nodesep={}.{}", unit, decimal)
+                write!(writer, "nodesep={}.{}", unit, decimal)
             },
             GraphvizGraphStyleItem::Concentrate(cnc) => {
-                format!("concentrate={}", cnc)
+                write!(writer, "concentrate={}", cnc)
             },
             GraphvizGraphStyleItem::Splines(spline) => {
                 let spline_as_static_str : &'static str = spline.into();
-                format!("splines={}", spline_as_static_str.to_lowercase())
+                writer.write_str("splines=")?;
+                write_lowercase(writer, spline_as_static_str)
             }
         }
     }
@@ -0,0 +1,250 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::edge::edge::GraphVizEdge;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::cluster::GraphVizCluster;
+use crate::item::item::GraphVizGraphItem;
+use crate::item::node::node::GraphVizNode;
+
+
+/// the edges along which a neighborhood is explored
+#[derive(Eq,PartialEq,Clone,Copy,Debug)]
+pub enum GvEdgeDirection {
+    /// from origin to target (successors)
+    Forward,
+    /// from target to origin (predecessors)
+    Backward,
+    /// both ways
+    Both
+}
+
+
+/**
+ Keeps the items whose nodes are in the set, dropping the clusters which become empty.
+ Returns the kept items and the kept edges (those whose origin and target are in the set) of dropped clusters,
+ which are moved to the enclosing cluster (or graph).
+ **/
+fn induced_items(items : &mut dyn Iterator<Item=&GraphVizGraphItem>,
+                 node_ids : &HashSet<&str>,
+                 kept_clusters : &mut HashSet<String>) -> (Vec<GraphVizGraphItem>,Vec<GraphVizEdge>) {
+    let mut kept = vec![];
+    let mut orphan_edges = vec![];
+    for item in items {
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                if node_ids.contains(node.id.as_str()) {
+                    kept.push(GraphVizGraphItem::Node(node.clone()));
+                }
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                let (nested,nested_orphans) = induced_items(&mut cluster.items.iter().map(|item| item.as_ref()),node_ids,kept_clusters);
+                let mut edges = induced_edges(&cluster.edges,node_ids);
+                edges.extend(nested_orphans);
+                if nested.is_empty() {
+                    orphan_edges.extend(edges);
+                } else {
+                    kept_clusters.insert(cluster.id.clone());
+                    let nested = nested.into_iter().map(Box::new).collect();
+                    kept.push(GraphVizGraphItem::Cluster(GraphVizCluster::new(cluster.id.clone(),cluster.style.clone(),nested,edges)));
+                }
+            }
+        }
+    }
+    (kept,orphan_edges)
+}
+
+fn induced_edges(edges : &[GraphVizEdge], node_ids : &HashSet<&str>) -> Vec<GraphVizEdge> {
+    edges.iter()
+        .filter(|edge| node_ids.contains(edge.origin_node_id.as_str()) && node_ids.contains(edge.target_node_id.as_str()))
+        .cloned()
+        .collect()
+}
+
+
+
+impl GraphVizDiGraph {
+
+    /**
+     New graph with only the given nodes and the edges between them (the induced subgraph).
+     The nodes stay in their clusters, the clusters which become empty being dropped
+     (together with the "ltail" and "lhead" of edges which referred to them).
+     **/
+    pub fn induced_subgraph<'a>(&self, node_ids : impl IntoIterator<Item=&'a str>) -> GraphVizDiGraph {
+        let node_ids : HashSet<&str> = node_ids.into_iter().collect();
+        let mut kept_clusters = HashSet::new();
+        let (items,orphan_edges) = induced_items(&mut self.items.iter(),&node_ids,&mut kept_clusters);
+        let mut subgraph = GraphVizDiGraph{style:self.style.clone(),items,edges:induced_edges(&self.edges,&node_ids)};
+        subgraph.edges.extend(orphan_edges);
+        subgraph.for_each_edge_mut(|edge| {
+            if edge.origin_cluster.as_ref().is_some_and(|cluster_id| !kept_clusters.contains(cluster_id)) {
+                edge.origin_cluster = None;
+            }
+            if edge.target_cluster.as_ref().is_some_and(|cluster_id| !kept_clusters.contains(cluster_id)) {
+                edge.target_cluster = None;
+            }
+        });
+        subgraph
+    }
+
+    /// induced subgraph of the nodes satisfying the predicate
+    pub fn filter_nodes<F>(&self, mut keep : F) -> GraphVizDiGraph
+        where F : FnMut(&GraphVizNode) -> bool {
+        let node_ids : Vec<&str> = self.all_nodes().filter(|node| keep(node)).map(|node| node.id.as_str()).collect();
+        self.induced_subgraph(node_ids)
+    }
+
+    /**
+     Ids of the nodes at most "hops" edges away from the start nodes (included) following edges in the given direction,
+     in breadth-first order.
+     Nodes which are only referred to by edges (without being declared) are included as well.
+     **/
+    pub fn nodes_within(&self, start : &[&str], hops : usize, direction : GvEdgeDirection) -> Vec<String> {
+        let mut neighbors : HashMap<&str,Vec<&str>> = HashMap::new();
+        for edge in self.all_edges() {
+            if direction != GvEdgeDirection::Backward {
+                neighbors.entry(&edge.origin_node_id).or_default().push(&edge.target_node_id);
+            }
+            if direction != GvEdgeDirection::Forward {
+                neighbors.entry(&edge.target_node_id).or_default().push(&edge.origin_node_id);
+            }
+        }
+        // ***
+        let mut visited : HashSet<&str> = HashSet::new();
+        let mut order = vec![];
+        let mut queue : VecDeque<(&str,usize)> = VecDeque::new();
+        for id in start {
+            if visited.insert(id) {
+                order.push(id.to_string());
+                queue.push_back((id,0));
+            }
+        }
+        while let Some((id,distance)) = queue.pop_front() {
+            if distance >= hops {
+                continue;
+            }
+            for next in neighbors.get(id).into_iter().flatten() {
+                if visited.insert(next) {
+                    order.push(next.to_string());
+                    queue.push_back((next,distance + 1));
+                }
+            }
+        }
+        order
+    }
+
+    /// induced subgraph of the nodes at most "hops" edges away from the start nodes
+    pub fn neighborhood(&self, start : &[&str], hops : usize, direction : GvEdgeDirection) -> GraphVizDiGraph {
+        let node_ids = self.nodes_within(start,hops,direction);
+        self.induced_subgraph(node_ids.iter().map(|id| id.as_str()))
+    }
+
+    /// induced subgraph of the nodes reachable from the start nodes
+    pub fn reachable_from(&self, start : &[&str]) -> GraphVizDiGraph {
+        self.neighborhood(start,usize::MAX,GvEdgeDirection::Forward)
+    }
+
+    /// induced subgraph of the nodes from which one of the start nodes is reachable
+    pub fn reaching(&self, start : &[&str]) -> GraphVizDiGraph {
+        self.neighborhood(start,usize::MAX,GvEdgeDirection::Backward)
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::traits::DotTranslatable;
+
+    #[test]
+    fn induced_subgraph() {
+        let graph = crate::digraph!{
+            a;
+            subgraph c1 {
+                b;
+                subgraph c2 { c; d; c -> d; }
+                subgraph c3 { e; b -> a; }
+                b -> c;
+            }
+            subgraph c4 {
+                subgraph c5 { f; a -> c [ltail = c5]; }
+                f -> a;
+            }
+            a -> b [lhead = c1];
+            a -> e [lhead = c3];
+            a -> b [lhead = c3];
+        };
+        // c3, c5 and c4 become empty : the edges declared in c3 move to c1 and those of c5 to the graph,
+        // through c4, and the edges no longer refer to them
+        let expected = crate::digraph!{
+            a;
+            subgraph c1 {
+                b;
+                subgraph c2 { c; d; c -> d; }
+                b -> c;
+                b -> a;
+            }
+            a -> b [lhead = c1];
+            a -> b;
+            a -> c;
+        };
+        assert_eq!(graph.induced_subgraph(["a","b","c","d"]).to_dot_string(),expected.to_dot_string());
+        assert_eq!(graph.filter_nodes(|node| node.id != "e" && node.id != "f").to_dot_string(),expected.to_dot_string());
+        // ***
+        let expected = crate::digraph!{
+            subgraph c1 { subgraph c2 { c; d; c -> d; } }
+        };
+        assert_eq!(graph.induced_subgraph(["c","d"]).to_dot_string(),expected.to_dot_string());
+        assert_eq!(graph.induced_subgraph([]).to_dot_string(),crate::digraph!{}.to_dot_string());
+    }
+
+    #[test]
+    fn nodes_within() {
+        // z is only referred to by an edge
+        let graph = crate::digraph!{
+            a; b; c; d; e;
+            a -> b -> c -> d -> z;
+            e -> a;
+        };
+        assert_eq!(graph.nodes_within(&["a"],0,GvEdgeDirection::Forward),vec!["a"]);
+        assert_eq!(graph.nodes_within(&["a"],1,GvEdgeDirection::Forward),vec!["a","b"]);
+        assert_eq!(graph.nodes_within(&["a"],2,GvEdgeDirection::Forward),vec!["a","b","c"]);
+        assert_eq!(graph.nodes_within(&["c"],2,GvEdgeDirection::Forward),vec!["c","d","z"]);
+        assert_eq!(graph.nodes_within(&["b"],1,GvEdgeDirection::Backward),vec!["b","a"]);
+        assert_eq!(graph.nodes_within(&["b"],2,GvEdgeDirection::Backward),vec!["b","a","e"]);
+        assert_eq!(graph.nodes_within(&["a"],1,GvEdgeDirection::Both),vec!["a","b","e"]);
+        assert_eq!(graph.nodes_within(&["a","d"],1,GvEdgeDirection::Forward),vec!["a","d","b","z"]);
+        // ***
+        let expected = crate::digraph!{ a; b; c; a -> b -> c; };
+        assert_eq!(graph.neighborhood(&["a"],2,GvEdgeDirection::Forward).to_dot_string(),expected.to_dot_string());
+        let expected = crate::digraph!{ a; b; c; d; e; a -> b -> c -> d; e -> a; };
+        assert_eq!(graph.reaching(&["d"]).to_dot_string(),expected.to_dot_string());
+        let expected = crate::digraph!{ c; d; c -> d -> z; };
+        assert_eq!(graph.reachable_from(&["c"]).to_dot_string(),expected.to_dot_string());
+    }
+
+}
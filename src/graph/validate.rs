@@ -0,0 +1,303 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::edge::edge::GraphVizEdge;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::item::GraphVizGraphItem;
+
+
+/// whether a location designates an item (node or cluster) or an edge
+#[derive(Eq,PartialEq,Clone,Copy,Debug)]
+pub enum GvLocationKind {
+    Item,
+    Edge
+}
+
+/**
+ Where an item or an edge is declared :
+ the ids of the nested clusters in which it is declared (outermost first, empty for the root of the graph)
+ and its index in the items or in the edges of this cluster.
+ It is displayed as e.g. "graph > c1 > items[0]" or "graph > edges[2]".
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub struct GvLocation {
+    pub cluster_path : Vec<String>,
+    pub kind : GvLocationKind,
+    pub index : usize
+}
+
+impl fmt::Display for GvLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph")?;
+        for cluster_id in &self.cluster_path {
+            write!(f, " > {}", cluster_id)?;
+        }
+        match self.kind {
+            GvLocationKind::Item => write!(f, " > items[{}]", self.index),
+            GvLocationKind::Edge => write!(f, " > edges[{}]", self.index)
+        }
+    }
+}
+
+/**
+ A problem of a graph found by "GraphVizDiGraph::validate".
+ **/
+#[derive(Eq,PartialEq,Clone,Debug)]
+pub enum GvDiagnostic {
+    /// several nodes have the same id (possibly in different clusters)
+    DuplicateNodeId{id : String, locations : Vec<GvLocation>},
+    /// several clusters have the same id
+    DuplicateClusterId{id : String, locations : Vec<GvLocation>},
+    /// the origin or target of an edge is not a node of the graph
+    DanglingEdge{location : GvLocation, node_id : String},
+    /// the "ltail" or "lhead" of an edge is not a cluster of the graph
+    UnknownEdgeCluster{location : GvLocation, cluster_id : String},
+    /// the "ltail" (resp. "lhead") cluster of an edge does not contain its origin (resp. target) node,
+    /// in which case Graphviz does not clip the edge
+    EdgeClusterWithoutNode{location : GvLocation, cluster_id : String, node_id : String}
+}
+
+impl fmt::Display for GvDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GvDiagnostic::DuplicateNodeId{id,locations} => {
+                let locations : Vec<String> = locations.iter().map(|location| location.to_string()).collect();
+                write!(f, "node id {} is declared several times, at {}", id, locations.join(", "))
+            },
+            GvDiagnostic::DuplicateClusterId{id,locations} => {
+                let locations : Vec<String> = locations.iter().map(|location| location.to_string()).collect();
+                write!(f, "cluster id {} is declared several times, at {}", id, locations.join(", "))
+            },
+            GvDiagnostic::DanglingEdge{location,node_id} => {
+                write!(f, "edge at {} refers to the unknown node {}", location, node_id)
+            },
+            GvDiagnostic::UnknownEdgeCluster{location,cluster_id} => {
+                write!(f, "edge at {} refers to the unknown cluster {}", location, cluster_id)
+            },
+            GvDiagnostic::EdgeClusterWithoutNode{location,cluster_id,node_id} => {
+                write!(f, "edge at {} is clipped at cluster {} which does not contain node {}", location, cluster_id, node_id)
+            }
+        }
+    }
+}
+
+
+#[derive(Default)]
+struct Declarations<'a> {
+    /// ids in order of first declaration
+    node_ids : Vec<&'a str>,
+    nodes : HashMap<&'a str,Vec<GvLocation>>,
+    cluster_ids : Vec<&'a str>,
+    clusters : HashMap<&'a str,Vec<GvLocation>>,
+    edges : Vec<(GvLocation,&'a GraphVizEdge)>
+}
+
+fn collect_declarations<'a>(items : &mut dyn Iterator<Item=&'a GraphVizGraphItem>,
+                            edges : &'a [GraphVizEdge],
+                            path : &mut Vec<String>,
+                            declarations : &mut Declarations<'a>) {
+    for (index,edge) in edges.iter().enumerate() {
+        declarations.edges.push((GvLocation{cluster_path:path.clone(),kind:GvLocationKind::Edge,index},edge));
+    }
+    for (index,item) in items.enumerate() {
+        let location = GvLocation{cluster_path:path.clone(),kind:GvLocationKind::Item,index};
+        match item {
+            GraphVizGraphItem::Node(node) => {
+                let locations = declarations.nodes.entry(&node.id).or_default();
+                if locations.is_empty() {
+                    declarations.node_ids.push(&node.id);
+                }
+                locations.push(location);
+            },
+            GraphVizGraphItem::Cluster(cluster) => {
+                let locations = declarations.clusters.entry(&cluster.id).or_default();
+                if locations.is_empty() {
+                    declarations.cluster_ids.push(&cluster.id);
+                }
+                locations.push(location);
+                path.push(cluster.id.clone());
+                collect_declarations(&mut cluster.items.iter().map(|item| item.as_ref()),&cluster.edges,path,declarations);
+                path.pop();
+            }
+        }
+    }
+}
+
+
+impl GraphVizDiGraph {
+
+    /**
+     Checks that :
+     - the ids of nodes (resp. clusters) are unique, including across nested clusters
+     - the origin and target of every edge are nodes of the graph
+     - the "ltail" and "lhead" clusters of every edge exist and contain respectively its origin and target nodes
+
+     and returns the list of the problems found (empty if the graph is valid).
+     **/
+    pub fn validate(&self) -> Vec<GvDiagnostic> {
+        let mut declarations = Declarations::default();
+        collect_declarations(&mut self.items.iter(),&self.edges,&mut vec![],&mut declarations);
+        let mut diagnostics = vec![];
+        for id in &declarations.node_ids {
+            let locations = &declarations.nodes[id];
+            if locations.len() > 1 {
+                diagnostics.push(GvDiagnostic::DuplicateNodeId{id:id.to_string(),locations:locations.clone()});
+            }
+        }
+        for id in &declarations.cluster_ids {
+            let locations = &declarations.clusters[id];
+            if locations.len() > 1 {
+                diagnostics.push(GvDiagnostic::DuplicateClusterId{id:id.to_string(),locations:locations.clone()});
+            }
+        }
+        // ***
+        for (location,edge) in &declarations.edges {
+            let endpoints = [(&edge.origin_node_id,&edge.origin_cluster),(&edge.target_node_id,&edge.target_cluster)];
+            for (node_id,cluster) in endpoints {
+                let node_locations = declarations.nodes.get(node_id.as_str());
+                if node_locations.is_none() {
+                    diagnostics.push(GvDiagnostic::DanglingEdge{location:location.clone(),node_id:node_id.clone()});
+                }
+                if let Some(cluster_id) = cluster {
+                    if !declarations.clusters.contains_key(cluster_id.as_str()) {
+                        diagnostics.push(GvDiagnostic::UnknownEdgeCluster{location:location.clone(),cluster_id:cluster_id.clone()});
+                    } else if let Some(node_locations) = node_locations {
+                        // the node is in the cluster if it is in the cluster path of its (first) declaration
+                        if !node_locations[0].cluster_path.contains(cluster_id) {
+                            diagnostics.push(GvDiagnostic::EdgeClusterWithoutNode{location:location.clone(),
+                                cluster_id:cluster_id.clone(),
+                                node_id:node_id.clone()});
+                        }
+                    }
+                }
+            }
+        }
+        diagnostics
+    }
+
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn item(path : &[&str], index : usize) -> GvLocation {
+        GvLocation{cluster_path:path.iter().map(|id| id.to_string()).collect(),kind:GvLocationKind::Item,index}
+    }
+
+    fn edge(path : &[&str], index : usize) -> GvLocation {
+        GvLocation{cluster_path:path.iter().map(|id| id.to_string()).collect(),kind:GvLocationKind::Edge,index}
+    }
+
+    #[test]
+    fn valid_graph() {
+        let graph = crate::digraph!{
+            a;
+            subgraph c1 {
+                b;
+                subgraph c2 { c; }
+                b -> c [lhead = c2];
+            }
+            a -> b [lhead = c1];
+            c -> a [ltail = c1];
+        };
+        assert!(graph.validate().is_empty());
+    }
+
+    #[test]
+    fn duplicate_node_id_across_nested_clusters() {
+        let graph = crate::digraph!{
+            a;
+            subgraph c1 {
+                b;
+                subgraph c2 { a; }
+            }
+        };
+        let diagnostics = graph.validate();
+        assert_eq!(diagnostics,vec![GvDiagnostic::DuplicateNodeId{id:"a".to_string(),locations:vec![item(&[],0),item(&["c1","c2"],0)]}]);
+        assert_eq!(diagnostics[0].to_string(),"node id a is declared several times, at graph > items[0], graph > c1 > c2 > items[0]");
+    }
+
+    #[test]
+    fn duplicate_cluster_id() {
+        let graph = crate::digraph!{
+            subgraph c1 {
+                subgraph c2 { a; }
+            }
+            subgraph c2 { b; }
+        };
+        let diagnostics = graph.validate();
+        assert_eq!(diagnostics,vec![GvDiagnostic::DuplicateClusterId{id:"c2".to_string(),locations:vec![item(&["c1"],0),item(&[],1)]}]);
+        assert_eq!(diagnostics[0].to_string(),"cluster id c2 is declared several times, at graph > c1 > items[0], graph > items[1]");
+    }
+
+    #[test]
+    fn dangling_origin_and_target() {
+        let graph = crate::digraph!{
+            a;
+            subgraph c1 {
+                x -> a;
+            }
+            a -> y;
+        };
+        let diagnostics = graph.validate();
+        assert_eq!(diagnostics,vec![GvDiagnostic::DanglingEdge{location:edge(&[],0),node_id:"y".to_string()},
+                                    GvDiagnostic::DanglingEdge{location:edge(&["c1"],0),node_id:"x".to_string()}]);
+        assert_eq!(diagnostics[0].to_string(),"edge at graph > edges[0] refers to the unknown node y");
+        assert_eq!(diagnostics[1].to_string(),"edge at graph > c1 > edges[0] refers to the unknown node x");
+    }
+
+    #[test]
+    fn unknown_ltail_and_lhead() {
+        let graph = crate::digraph!{
+            a;
+            b;
+            a -> b [ltail = c1, lhead = c2];
+        };
+        let diagnostics = graph.validate();
+        assert_eq!(diagnostics,vec![GvDiagnostic::UnknownEdgeCluster{location:edge(&[],0),cluster_id:"c1".to_string()},
+                                    GvDiagnostic::UnknownEdgeCluster{location:edge(&[],0),cluster_id:"c2".to_string()}]);
+        assert_eq!(diagnostics[1].to_string(),"edge at graph > edges[0] refers to the unknown cluster c2");
+    }
+
+    #[test]
+    fn edge_cluster_without_node() {
+        let graph = crate::digraph!{
+            a;
+            subgraph c1 { b; }
+            subgraph c2 { c; }
+            a -> c [lhead = c1];
+            b -> c [ltail = c1, lhead = c2];
+        };
+        let diagnostics = graph.validate();
+        assert_eq!(diagnostics,vec![GvDiagnostic::EdgeClusterWithoutNode{location:edge(&[],0),
+                                                                        cluster_id:"c1".to_string(),
+                                                                        node_id:"c".to_string()}]);
+        assert_eq!(diagnostics[0].to_string(),"edge at graph > edges[0] is clipped at cluster c1 which does not contain node c");
+    }
+
+}
@@ -14,13 +14,15 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use crate::traits::{DotTranslatable, RenameableWithPrefix, DotBuildable};
+use std::fmt;
+use crate::traits::{DotTranslatable, RenameableWith, RenameableWithPrefix, DotBuildable};
 use crate::edge::edge::GraphVizEdge;
 use crate::item::item::GraphVizGraphItem;
 use crate::item::node::style::GraphvizNodeStyle;
 
 
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GraphVizCluster {
     pub id : String,
     pub style : GraphvizNodeStyle,
@@ -37,40 +39,73 @@ impl GraphVizCluster {
     }
 }
 
+/**
+ Writes the DOT name "cluster_<id>" of the subgraph of a cluster (also used by "ltail" and "lhead"),
+ which is quoted as a whole when the id is not made of alphanumeric characters and underscores only.
+ **/
+pub(crate) fn write_cluster_name(writer : &mut dyn fmt::Write, id : &str) -> fmt::Result {
+    if id.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return write!(writer, "cluster_{}", id);
+    }
+    writer.write_str("\"cluster_")?;
+    for c in id.chars() {
+        if c == '"' {
+            writer.write_char('\\')?;
+        }
+        writer.write_char(c)?;
+    }
+    writer.write_char('"')
+}
+
+/// the DOT name of the subgraph of a cluster
+pub(crate) fn cluster_name(id : &str) -> String {
+    let mut name = String::new();
+    let _ = write_cluster_name(&mut name,id);
+    name
+}
+
 impl DotTranslatable for GraphVizCluster {
-    fn to_dot_string(&self) -> String {
-        let mut res = String::new();
-        res.push_str(&format!("subgraph cluster_{:} {{\n",self.id));
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
+        writer.write_str("subgraph ")?;
+        write_cluster_name(writer,&self.id)?;
+        writer.write_str(" {\n")?;
         // ***
         for item in &self.style {
-            res.push_str(&format!("{};\n",item.to_dot_string()) );
+            item.write_dot_fmt(writer)?;
+            writer.write_str(";\n")?;
         }
         // ***
         for item in &self.items {
-            res.push('\t');
-            res.push_str(& item.to_dot_string() );
-            res.push('\n');
+            writer.write_char('\t')?;
+            item.write_dot_fmt(writer)?;
+            writer.write_char('\n')?;
         }
         for edge in &self.edges {
-            res.push('\t');
-            res.push_str(& edge.to_dot_string() );
-            res.push('\n');
+            writer.write_char('\t')?;
+            edge.write_dot_fmt(writer)?;
+            writer.write_char('\n')?;
         }
-        res.push('}');
-        // ***
-        res
+        writer.write_char('}')
     }
 }
 
 
 impl RenameableWithPrefix for GraphVizCluster {
     fn rename_with_prefix(&self, prefix: &str) -> Self {
+        self.rename_with(&|id| format!("{}{}",prefix,id))
+    }
+}
+
+impl RenameableWith for GraphVizCluster {
+    fn rename_with_maps(&self,
+                        node_map : &dyn Fn(&str) -> String,
+                        cluster_map : &dyn Fn(&str) -> String) -> Self {
         let new_items : Vec<Box<GraphVizGraphItem>> = self.items.iter().map(
-            |item| Box::new(item.rename_with_prefix(prefix))).collect();
+            |item| Box::new(item.rename_with_maps(node_map,cluster_map))).collect();
         let new_edges : Vec<GraphVizEdge> = self.edges.iter().map(
-            |edge| edge.rename_with_prefix(prefix)).collect();
+            |edge| edge.rename_with_maps(node_map,cluster_map)).collect();
         // ***
-        GraphVizCluster::new(format!("{}{}",prefix,self.id),
+        GraphVizCluster::new(cluster_map(&self.id),
                                     self.style.clone(),
                                     new_items,
                                     new_edges)
@@ -14,24 +14,27 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+use std::fmt;
 use crate::item::cluster::GraphVizCluster;
 use crate::item::node::node::GraphVizNode;
-use crate::traits::{DotTranslatable, RenameableWithPrefix};
+use crate::traits::{DotTranslatable, RenameableWith, RenameableWithPrefix};
 
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GraphVizGraphItem {
     Node(GraphVizNode),
     Cluster(GraphVizCluster)
 }
 
 impl DotTranslatable for GraphVizGraphItem {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         match self {
             GraphVizGraphItem::Cluster(cluster) => {
-                cluster.to_dot_string()
+                cluster.write_dot_fmt(writer)
             },
             GraphVizGraphItem::Node(node) => {
-                node.to_dot_string()
+                node.write_dot_fmt(writer)
             }
         }
     }
@@ -39,12 +42,20 @@ impl DotTranslatable for GraphVizGraphItem {
 
 impl RenameableWithPrefix for GraphVizGraphItem {
     fn rename_with_prefix(&self, prefix: &str) -> Self {
+        self.rename_with(&|id| format!("{}{}",prefix,id))
+    }
+}
+
+impl RenameableWith for GraphVizGraphItem {
+    fn rename_with_maps(&self,
+                        node_map : &dyn Fn(&str) -> String,
+                        cluster_map : &dyn Fn(&str) -> String) -> Self {
         match self {
             GraphVizGraphItem::Cluster(cluster) => {
-                GraphVizGraphItem::Cluster(cluster.rename_with_prefix(prefix))
+                GraphVizGraphItem::Cluster(cluster.rename_with_maps(node_map,cluster_map))
             },
             GraphVizGraphItem::Node(node) => {
-                GraphVizGraphItem::Node(node.rename_with_prefix(prefix))
+                GraphVizGraphItem::Node(node.rename_with_maps(node_map,cluster_map))
             }
         }
     }
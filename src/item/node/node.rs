@@ -16,10 +16,12 @@ limitations under the License.
 
 
 
+use std::fmt;
 use crate::item::node::style::GraphvizNodeStyle;
-use crate::traits::{DotTranslatable, RenameableWithPrefix};
+use crate::traits::{DotTranslatable, RenameableWith, RenameableWithPrefix};
 
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GraphVizNode {
     pub id : String,
     pub style : GraphvizNodeStyle
@@ -30,24 +32,42 @@ impl GraphVizNode {
                style : GraphvizNodeStyle) -> GraphVizNode {
         GraphVizNode{id,style}
     }
+
+    /// the DOT attributes of the node
+    pub fn dot_attributes(&self) -> Vec<String> {
+        self.style.iter().map(
+            |item| item.to_dot_string()).collect()
+    }
 }
 
 impl DotTranslatable for GraphVizNode {
-    fn to_dot_string(&self) -> String {
-        let style : Vec<String> = self.style.iter().map(
-            |item| item.to_dot_string()).collect();
-
-        if style.is_empty() {
-            format!("{};", self.id)
-        } else {
-            format!("{} [{}];", self.id, style.join(","))
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
+        writer.write_str(&self.id)?;
+        if !self.style.is_empty() {
+            writer.write_str(" [")?;
+            for (index, item) in self.style.iter().enumerate() {
+                if index > 0 {
+                    writer.write_char(',')?;
+                }
+                item.write_dot_fmt(writer)?;
+            }
+            writer.write_char(']')?;
         }
+        writer.write_char(';')
     }
 }
 
 impl RenameableWithPrefix for GraphVizNode {
     fn rename_with_prefix(&self, prefix: &str) -> Self {
-        GraphVizNode::new(format!("{}{}",prefix,self.id),self.style.clone())
+        self.rename_with(&|id| format!("{}{}",prefix,id))
+    }
+}
+
+impl RenameableWith for GraphVizNode {
+    fn rename_with_maps(&self,
+                        node_map : &dyn Fn(&str) -> String,
+                        _cluster_map : &dyn Fn(&str) -> String) -> Self {
+        GraphVizNode::new(node_map(&self.id),self.style.clone())
     }
 }
 
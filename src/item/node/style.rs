@@ -16,12 +16,15 @@ limitations under the License.
 
 
 
-use strum_macros::IntoStaticStr;
+use std::fmt;
+use strum_macros::{EnumString, IntoStaticStr};
 use crate::colors::GraphvizColor;
-use crate::traits::DotTranslatable;
+use crate::traits::{write_lowercase, DotTranslatable};
 
 
 #[derive(IntoStaticStr,Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GvNodeStyleKind {
     Solid,
     Dashed,
@@ -36,24 +39,32 @@ pub enum GvNodeStyleKind {
 }
 
 impl DotTranslatable for GvNodeStyleKind {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         let as_static_str : &'static str = self.into();
-        as_static_str.to_string().to_lowercase()
+        write_lowercase(writer, as_static_str)
     }
 }
 
 pub type GvNodeStyle = Vec<GvNodeStyleKind>;
 
 impl DotTranslatable for GvNodeStyle {
-    fn to_dot_string(&self) -> String {
-        let elements : Vec<String> = self.iter().map(
-            |item| item.to_dot_string()).collect();
-        format!("\"{}\"", elements.join(","))
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
+        writer.write_char('"')?;
+        for (index, item) in self.iter().enumerate() {
+            if index > 0 {
+                writer.write_char(',')?;
+            }
+            item.write_dot_fmt(writer)?;
+        }
+        writer.write_char('"')
     }
 }
 
 
-#[derive(IntoStaticStr,Eq,PartialEq,Clone)]
+#[derive(IntoStaticStr,EnumString,Eq,PartialEq,Clone)]
+#[strum(ascii_case_insensitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GvNodeShape {
     Ellipse,
     Circle,
@@ -78,9 +89,9 @@ pub enum GvNodeShape {
 }
 
 impl DotTranslatable for GvNodeShape {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         let as_static_str : &'static str = self.into();
-        as_static_str.to_string().to_lowercase()
+        write_lowercase(writer, as_static_str)
     }
 }
 
@@ -88,6 +99,8 @@ impl DotTranslatable for GvNodeShape {
 
 
 #[derive(Eq,PartialEq,Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum GraphvizNodeStyleItem {
     Style(GvNodeStyle),
     Shape(GvNodeShape),
@@ -105,45 +118,50 @@ pub enum GraphvizNodeStyleItem {
 }
 
 impl DotTranslatable for GraphvizNodeStyleItem {
-    fn to_dot_string(&self) -> String {
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
         match self {
             GraphvizNodeStyleItem::PenWidth(pw) => {
-                format!("penwidth={:}",pw)
+                write!(writer, "penwidth={:}",pw)
             },
             GraphvizNodeStyleItem::Height(height) => {
-                format!("height={:}",height)
+                write!(writer, "height={:}",height)
             },
             GraphvizNodeStyleItem::Width(width) => {
-                format!("width={:}",width)
+                write!(writer, "width={:}",width)
             },
             GraphvizNodeStyleItem::Peripheries(per) => {
-                format!("peripheries={:}",per)
+                write!(writer, "peripheries={:}",per)
             },
             GraphvizNodeStyleItem::Style(node_style) => {
-                format!("style={:}",node_style.to_dot_string())
+                writer.write_str("style=")?;
+                node_style.write_dot_fmt(writer)
             },
             GraphvizNodeStyleItem::Shape(node_shape) => {
-                format!("shape={:}",node_shape.to_dot_string())
+                writer.write_str("shape=")?;
+                node_shape.write_dot_fmt(writer)
             },
             GraphvizNodeStyleItem::Label(label) => {
-                format!("label=\"{}\"",label)
+                write!(writer, "label=\"{}\"",label)
             },
             GraphvizNodeStyleItem::Image(imgpath) => {
-                format!("imagescale=true;image=\"{}\"",imgpath)
+                write!(writer, "imagescale=true;image=\"{}\"",imgpath)
             },
             GraphvizNodeStyleItem::Color(graphviz_color) => {
-                format!("color={:}",graphviz_color.to_dot_string())
+                writer.write_str("color=")?;
+                graphviz_color.write_dot_fmt(writer)
             },
             GraphvizNodeStyleItem::FillColor(graphviz_color) => {
-                format!("style=filled;fillcolor={:}",graphviz_color.to_dot_string())
+                writer.write_str("style=filled;fillcolor=")?;
+                graphviz_color.write_dot_fmt(writer)
             },
             GraphvizNodeStyleItem::FontColor(graphviz_color) => {
-                format!("fontcolor={:}",graphviz_color.to_dot_string())
+                writer.write_str("fontcolor=")?;
+                graphviz_color.write_dot_fmt(writer)
             },
             GraphvizNodeStyleItem::FontSize(size) => {
-                format!("fontsize={:}",size)
+                write!(writer, "fontsize={:}",size)
             },GraphvizNodeStyleItem::FontName(fname) => {
-                format!("fontname=\"{}\"",fname)
+                write!(writer, "fontname=\"{}\"",fname)
             }
         }
     }
@@ -14,11 +14,16 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+#![allow(clippy::module_inception)]
+
 pub mod traits;
 pub mod colors;
 pub mod edge;
 pub mod item;
 pub mod graph;
+pub mod builder;
+mod macros;
+pub mod export;
 
 #[cfg(test)]
 mod tests {
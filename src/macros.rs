@@ -0,0 +1,214 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+/**
+ Builds a "GraphVizDiGraph" with a syntax which mirrors that of DOT :
+
+ digraph!{
+     rankdir = LR;
+     a [label = "A", shape = Rectangle, fillcolor = lightblue];
+     subgraph c1 {
+         label = "C";
+         b; c;
+         b -> c [style = Dashed];
+     }
+     (my_id) [label = (format!("{} items", count))];
+     a -> b -> (my_id) [lhead = c1];
+ }
+
+ - node ids are either identifiers, literals or parenthesized Rust expressions
+ - attribute values are either literals, parenthesized Rust expressions
+   or, for enumerated attributes, the name of the variant (e.g. "Rectangle" for "GvNodeShape::Rectangle", "red" for "GraphvizColor::red")
+ - attribute names are those of DOT, an unknown attribute is a compilation error
+ **/
+#[macro_export]
+macro_rules! digraph {
+    // *** statements
+    (@stmts $ctx:ident $b:ident ; ) => {};
+    (@stmts $ctx:ident $b:ident ; subgraph $id:tt { $($body:tt)* } $($rest:tt)*) => {
+        $b = $b.cluster($crate::digraph!(@id $id), |cluster_builder| {
+            #[allow(unused_mut)]
+            let mut cluster_builder = cluster_builder;
+            $crate::digraph!(@stmts cluster cluster_builder ; $($body)*);
+            cluster_builder
+        });
+        $crate::digraph!(@stmts $ctx $b ; $($rest)*);
+    };
+    (@stmts $ctx:ident $b:ident ; $k:ident = $v:tt ; $($rest:tt)*) => {
+        $b = $crate::digraph!(@attr $ctx $b ; $k = $v);
+        $crate::digraph!(@stmts $ctx $b ; $($rest)*);
+    };
+    (@stmts $ctx:ident $b:ident ; $x:tt $(-> $y:tt)+ [ $($attrs:tt)* ] ; $($rest:tt)*) => {
+        $crate::digraph!(@chain $b ; [ $($attrs)* ] ; $x $(-> $y)+);
+        $crate::digraph!(@stmts $ctx $b ; $($rest)*);
+    };
+    (@stmts $ctx:ident $b:ident ; $x:tt $(-> $y:tt)+ ; $($rest:tt)*) => {
+        $crate::digraph!(@chain $b ; [] ; $x $(-> $y)+);
+        $crate::digraph!(@stmts $ctx $b ; $($rest)*);
+    };
+    (@stmts $ctx:ident $b:ident ; $id:tt [ $($attrs:tt)* ] ; $($rest:tt)*) => {
+        $b = $b.node($crate::digraph!(@attrs node
+            $crate::builder::node::GvNodeBuilder::new($crate::digraph!(@id $id)) ; $($attrs)*));
+        $crate::digraph!(@stmts $ctx $b ; $($rest)*);
+    };
+    (@stmts $ctx:ident $b:ident ; $id:tt ; $($rest:tt)*) => {
+        $b = $b.node($crate::builder::node::GvNodeBuilder::new($crate::digraph!(@id $id)));
+        $crate::digraph!(@stmts $ctx $b ; $($rest)*);
+    };
+    (@stmts $ctx:ident $b:ident ; $($rest:tt)*) => {
+        compile_error!(concat!("invalid statement : ", stringify!($($rest)*)))
+    };
+    // *** each "x -> y" of a chain of edges
+    (@chain $b:ident ; [ $($attrs:tt)* ] ; $x:tt) => {};
+    (@chain $b:ident ; [ $($attrs:tt)* ] ; $x:tt -> $y:tt $(-> $z:tt)*) => {
+        $b = $b.edge($crate::digraph!(@attrs edge
+            $crate::builder::edge::GvEdgeBuilder::new($crate::digraph!(@id $x),$crate::digraph!(@id $y)) ; $($attrs)*));
+        $crate::digraph!(@chain $b ; [ $($attrs)* ] ; $y $(-> $z)*);
+    };
+    // *** identifiers and values
+    (@id $id:ident) => { stringify!($id) };
+    (@id $id:tt) => { ::std::string::ToString::to_string(&$id) };
+    (@enum [ $($path:tt)* ] $v:ident) => { $($path)*::$v };
+    (@enum [ $($path:tt)* ] $v:tt) => { $v };
+    // *** attribute lists
+    (@attrs $ctx:ident $e:expr ; ) => { $e };
+    (@attrs $ctx:ident $e:expr ; $k:ident = $v:tt $(, $($rest:tt)*)?) => {
+        $crate::digraph!(@attrs $ctx $crate::digraph!(@attr $ctx $e ; $k = $v) ; $($($rest)*)?)
+    };
+    // *** graph attributes
+    (@attr graph $e:expr ; rankdir = $v:tt) => {
+        $e.rankdir($crate::digraph!(@enum [$crate::graph::style::GvGraphRankDir] $v))
+    };
+    (@attr graph $e:expr ; nodesep = ($unit:tt , $decimal:tt)) => { $e.node_sep($unit,$decimal) };
+    (@attr graph $e:expr ; concentrate = $v:tt) => { $e.concentrate($v) };
+    (@attr graph $e:expr ; splines = $v:tt) => {
+        $e.splines($crate::digraph!(@enum [$crate::graph::style::GraphvizSplines] $v))
+    };
+    // *** attributes shared by nodes and clusters
+    (@attr node $e:expr ; shape = $v:tt) => {
+        $e.shape($crate::digraph!(@enum [$crate::item::node::style::GvNodeShape] $v))
+    };
+    (@attr node $e:expr ; image = $v:tt) => { $e.image($v) };
+    (@attr node $e:expr ; height = $v:tt) => { $e.height($v) };
+    (@attr node $e:expr ; width = $v:tt) => { $e.width($v) };
+    (@attr node $e:expr ; peripheries = $v:tt) => { $e.peripheries($v) };
+    (@attr $ctx:ident $e:expr ; style = $v:tt) => { $crate::digraph!(@style $ctx $e ; $v) };
+    (@attr $ctx:ident $e:expr ; penwidth = $v:tt) => { $crate::digraph!(@penwidth $ctx $e ; $v) };
+    (@attr $ctx:ident $e:expr ; fillcolor = $v:tt) => { $crate::digraph!(@fill $ctx $e ; $v) };
+    (@attr $ctx:ident $e:expr ; fontname = $v:tt) => { $crate::digraph!(@fontname $ctx $e ; $v) };
+    // *** edge attributes
+    (@attr edge $e:expr ; arrowhead = $v:tt) => {
+        $e.head($crate::digraph!(@enum [$crate::edge::style::GvArrowHeadStyle] $v))
+    };
+    (@attr edge $e:expr ; arrowtail = $v:tt) => {
+        $e.tail($crate::digraph!(@enum [$crate::edge::style::GvArrowHeadStyle] $v))
+    };
+    (@attr edge $e:expr ; arrowsize = $v:tt) => { $e.arrow_size($v) };
+    (@attr edge $e:expr ; constraint = $v:tt) => { $e.constraint($v) };
+    (@attr edge $e:expr ; lhead = $v:tt) => { $e.lhead($crate::digraph!(@id $v)) };
+    (@attr edge $e:expr ; ltail = $v:tt) => { $e.ltail($crate::digraph!(@id $v)) };
+    // *** attributes shared by nodes, clusters and edges
+    (@attr $ctx:ident $e:expr ; label = $v:tt) => { $crate::digraph!(@label $ctx $e ; $v) };
+    (@attr $ctx:ident $e:expr ; color = $v:tt) => {
+        $crate::digraph!(@color $ctx $e ; $crate::digraph!(@enum [$crate::colors::GraphvizColor] $v))
+    };
+    (@attr $ctx:ident $e:expr ; fontcolor = $v:tt) => {
+        $crate::digraph!(@fontcolor $ctx $e ; $crate::digraph!(@enum [$crate::colors::GraphvizColor] $v))
+    };
+    (@attr $ctx:ident $e:expr ; fontsize = $v:tt) => { $crate::digraph!(@fontsize $ctx $e ; $v) };
+    (@attr $ctx:ident $e:expr ; $k:ident = $v:tt) => {
+        compile_error!(concat!("unknown ", stringify!($ctx), " attribute : ", stringify!($k)))
+    };
+    // *** attributes which only exist in some contexts
+    (@style edge $e:expr ; $v:tt) => {
+        $e.line_style($crate::digraph!(@enum [$crate::edge::style::GvEdgeLineStyle] $v))
+    };
+    (@style $ctx:ident $e:expr ; $v:tt) => {
+        $crate::digraph!(@nodelike $ctx style ; $e.style($crate::digraph!(@enum [$crate::item::node::style::GvNodeStyleKind] $v)))
+    };
+    (@penwidth edge $e:expr ; $v:tt) => { $e.pen_width($v) };
+    (@penwidth $ctx:ident $e:expr ; $v:tt) => { $crate::digraph!(@nodelike $ctx penwidth ; $e.pen_width($v)) };
+    (@fill $ctx:ident $e:expr ; $v:tt) => {
+        $crate::digraph!(@nodelike $ctx fillcolor ; $e.fill($crate::digraph!(@enum [$crate::colors::GraphvizColor] $v)))
+    };
+    (@fontname $ctx:ident $e:expr ; $v:tt) => { $crate::digraph!(@nodelike $ctx fontname ; $e.font_name($v)) };
+    (@label graph $e:expr ; $v:tt) => { compile_error!("unknown graph attribute : label") };
+    (@label $ctx:ident $e:expr ; $v:tt) => { $e.label($v) };
+    (@color graph $e:expr ; $v:expr) => { compile_error!("unknown graph attribute : color") };
+    (@color $ctx:ident $e:expr ; $v:expr) => { $e.color($v) };
+    (@fontcolor graph $e:expr ; $v:expr) => { compile_error!("unknown graph attribute : fontcolor") };
+    (@fontcolor $ctx:ident $e:expr ; $v:expr) => { $e.font_color($v) };
+    (@fontsize graph $e:expr ; $v:tt) => { compile_error!("unknown graph attribute : fontsize") };
+    (@fontsize $ctx:ident $e:expr ; $v:tt) => { $e.font_size($v) };
+    (@nodelike node $k:ident ; $e:expr) => { $e };
+    (@nodelike cluster $k:ident ; $e:expr) => { $e };
+    (@nodelike $ctx:ident $k:ident ; $e:expr) => {
+        compile_error!(concat!("unknown ", stringify!($ctx), " attribute : ", stringify!($k)))
+    };
+    // *** entry point
+    ($($body:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut graph_builder = $crate::builder::graph::GvGraphBuilder::new();
+        $crate::digraph!(@stmts graph graph_builder ; $($body)*);
+        graph_builder.build()
+    }};
+}
+
+
+
+/**
+ Applies DOT attributes, with the syntax of the attribute lists of "digraph!", to a node or an edge builder :
+
+ __dot_attributes!(node GvNodeBuilder::new("a") ; shape = Rectangle, fillcolor = lightblue)
+ __dot_attributes!(edge GvEdgeBuilder::new("a","b") ; style = Dashed)
+
+ It is the entry point of the code generated by "#[derive(DotNode)]" into "digraph!",
+ whose internal rules may change, and is not meant to be used directly.
+ **/
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dot_attributes {
+    (node $e:expr ; $($attrs:tt)*) => { $crate::digraph!(@attrs node $e ; $($attrs)*) };
+    (edge $e:expr ; $($attrs:tt)*) => { $crate::digraph!(@attrs edge $e ; $($attrs)*) };
+}
+
+
+
+/**
+ Asserts that two "GraphVizDiGraph" have the same canonical DOT source
+ (see "GraphVizDiGraph::to_canonical_dot_string"), i.e. that they only differ by the order of insertion
+ of their elements.
+ On failure, the panic message lists the statements found in only one of both graphs
+ instead of the whole DOT sources.
+
+ assert_dot_eq!(built_graph, expected_graph);
+ assert_dot_eq!(built_graph, expected_graph, "graph of {}", name);
+ **/
+#[macro_export]
+macro_rules! assert_dot_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        if let Some(report) = $crate::graph::graph::GraphVizDiGraph::canonical_dot_mismatch(&$left, &$right) {
+            panic!("assertion `left == right` failed: the graphs differ\n{}", report);
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        if let Some(report) = $crate::graph::graph::GraphVizDiGraph::canonical_dot_mismatch(&$left, &$right) {
+            panic!("assertion `left == right` failed: {}\n{}", format_args!($($arg)+), report);
+        }
+    };
+}
@@ -15,6 +15,7 @@ limitations under the License.
 */
 
 use std::fmt;
+use std::io;
 use strum_macros::IntoStaticStr;
 
 
@@ -22,6 +23,10 @@ use crate::item::item::GraphVizGraphItem;
 use crate::item::node::node::GraphVizNode;
 use crate::item::cluster::GraphVizCluster;
 use crate::edge::edge::GraphVizEdge;
+use crate::builder::cluster::GvClusterBuilder;
+use crate::edge::style::GraphvizEdgeStyle;
+use crate::graph::graph::GraphVizDiGraph;
+use crate::item::node::style::{GraphvizNodeStyle, GraphvizNodeStyleItem};
 
 #[allow(non_camel_case_types)]
 #[derive(IntoStaticStr, Clone, PartialEq, Debug, Eq, Hash)]
@@ -37,14 +42,76 @@ impl fmt::Display for GraphVizOutputFormat {
     }
 }
 
+/**
+ Translation of an element into DOT source.
+ At least one of "to_dot_string" and "write_dot_fmt" must be implemented, each being defined in terms of the other :
+ elements implementing "write_dot_fmt" stream their source without building intermediate strings,
+ "to_dot_string" and "write_dot" then being thin wrappers over it.
+ **/
 pub trait DotTranslatable {
-    fn to_dot_string(&self) -> String;
+    fn to_dot_string(&self) -> String {
+        let mut dot_string = String::new();
+        let _ = self.write_dot_fmt(&mut dot_string);
+        dot_string
+    }
+    /// streams the DOT source of the element into a formatter
+    fn write_dot_fmt(&self, writer : &mut dyn fmt::Write) -> fmt::Result {
+        writer.write_str(&self.to_dot_string())
+    }
+    /// streams the DOT source of the element into a (buffered) byte sink such as a file or a pipe
+    fn write_dot(&self, writer : &mut dyn io::Write) -> io::Result<()> {
+        let mut buffered = io::BufWriter::new(writer);
+        let mut adapter = GvIoAdapter{inner : &mut buffered, error : None};
+        if self.write_dot_fmt(&mut adapter).is_err() {
+            return Err(adapter.error.unwrap_or_else(|| io::Error::other("formatting error")));
+        }
+        io::Write::flush(&mut buffered)
+    }
+}
+
+/// forwards "fmt::Write" calls to an "io::Write", keeping the underlying io error
+struct GvIoAdapter<'a, W : io::Write> {
+    inner : &'a mut W,
+    error : Option<io::Error>
+}
+
+impl<W : io::Write> fmt::Write for GvIoAdapter<'_, W> {
+    fn write_str(&mut self, s : &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+/// writes the lowercase version of a variant name without allocating
+pub(crate) fn write_lowercase(writer : &mut dyn fmt::Write, name : &str) -> fmt::Result {
+    for c in name.chars() {
+        writer.write_char(c.to_ascii_lowercase())?;
+    }
+    Ok(())
 }
 
 pub trait RenameableWithPrefix {
     fn rename_with_prefix(&self, prefix : &str) -> Self;
 }
 
+/**
+ Renaming of the ids of nodes and clusters with mapping functions,
+ e.g. to add a suffix, to use a namespace separator, to hash long ids or to look ids up in a table.
+ The references to nodes and clusters in edges (origin, target, "ltail" and "lhead") are renamed accordingly.
+ **/
+pub trait RenameableWith {
+    /// renames the ids of nodes with "node_map" and those of clusters with "cluster_map"
+    fn rename_with_maps(&self,
+                        node_map : &dyn Fn(&str) -> String,
+                        cluster_map : &dyn Fn(&str) -> String) -> Self;
+    /// renames the ids of both nodes and clusters with the same function
+    fn rename_with(&self, map : &dyn Fn(&str) -> String) -> Self where Self : Sized {
+        self.rename_with_maps(map,map)
+    }
+}
+
 pub trait DotBuildable {
     fn add_item(&mut self, item : GraphVizGraphItem);
     fn add_edge(&mut self, edge : GraphVizEdge);
@@ -55,6 +122,102 @@ pub trait DotBuildable {
     fn add_cluster(&mut self, cluster : GraphVizCluster) {
         self.add_item(GraphVizGraphItem::Cluster(cluster));
     }
+    // *** chainable variants, accepting builders
+    fn node(&mut self, node : impl Into<GraphVizNode>) -> &mut Self where Self : Sized {
+        self.add_node(node.into());
+        self
+    }
+    fn edge(&mut self, edge : impl Into<GraphVizEdge>) -> &mut Self where Self : Sized {
+        self.add_edge(edge.into());
+        self
+    }
+    /// adds the node of a value and its edges
+    fn add_dot_node(&mut self, value : &impl DotNode) -> &mut Self where Self : Sized {
+        self.add_node(value.to_dot_node());
+        for edge in value.to_dot_edges() {
+            self.add_edge(edge);
+        }
+        self
+    }
+    /// adds a cluster, built by the given function
+    fn cluster<F>(&mut self, id : impl Into<String>, build : F) -> &mut Self
+        where Self : Sized, F : FnOnce(GvClusterBuilder) -> GvClusterBuilder {
+        self.add_cluster(build(GvClusterBuilder::new(id)).build());
+        self
+    }
+}
+
+/**
+ A type whose values can be drawn as nodes of a graph, together with edges towards other nodes.
+ With the "derive" feature, it can be derived with "#[derive(DotNode)]".
+ **/
+pub trait DotNode {
+    fn dot_node_id(&self) -> String;
+    fn to_dot_node(&self) -> GraphVizNode;
+    fn to_dot_edges(&self) -> Vec<GraphVizEdge> {
+        vec![]
+    }
+}
+
+#[cfg(feature = "derive")]
+pub use graphviz_dot_builder_derive::DotNode;
+
+/**
+ A graph structure of the user, which can be translated into a "GraphVizDiGraph".
+ Only the nodes, edges, node ids and edge endpoints are required,
+ the style of nodes, edges and clusters and the hierarchy of clusters being optional.
+ **/
+pub trait GraphVisualizable {
+    type Node;
+    type Edge;
+    // ***
+    fn nodes(&self) -> impl Iterator<Item = &Self::Node>;
+    fn edges(&self) -> impl Iterator<Item = &Self::Edge>;
+    fn node_id(&self, node : &Self::Node) -> String;
+    /// ids of the origin and target nodes of the edge
+    fn edge_endpoints(&self, edge : &Self::Edge) -> (String,String);
+    // ***
+    fn node_style(&self, _node : &Self::Node) -> GraphvizNodeStyle {
+        vec![]
+    }
+    fn edge_style(&self, _edge : &Self::Edge) -> GraphvizEdgeStyle {
+        vec![]
+    }
+    /// id of the cluster in which the node is drawn, if any
+    fn node_cluster(&self, _node : &Self::Node) -> Option<String> {
+        None
+    }
+    /// id of the cluster in which a cluster is nested, if any
+    fn cluster_parent(&self, _cluster_id : &str) -> Option<String> {
+        None
+    }
+    fn cluster_style(&self, cluster_id : &str) -> GraphvizNodeStyle {
+        vec![GraphvizNodeStyleItem::Label(cluster_id.to_string())]
+    }
+    // ***
+    fn to_graphviz(&self) -> GraphVizDiGraph {
+        let mut graph = GraphVizDiGraph::new(vec![]);
+        for node in self.nodes() {
+            let gv_node = GraphVizNode::new(self.node_id(node),self.node_style(node));
+            // path of nested clusters, outermost first
+            let mut path : Vec<String> = vec![];
+            let mut cluster = self.node_cluster(node);
+            while let Some(cluster_id) = cluster {
+                if path.contains(&cluster_id) {
+                    // a cycle in the hierarchy of clusters
+                    break;
+                }
+                cluster = self.cluster_parent(&cluster_id);
+                path.insert(0,cluster_id);
+            }
+            graph.add_node_in_clusters(&path,gv_node,&mut |cluster_id| self.cluster_style(cluster_id));
+        }
+        for edge in self.edges() {
+            let (origin,target) = self.edge_endpoints(edge);
+            graph.edges.push(GraphVizEdge::new(origin,None,target,None,self.edge_style(edge)));
+        }
+        graph
+    }
 }
 
 pub trait DotPrintable {
@@ -66,3 +229,172 @@ pub trait DotPrintable {
 }
 
 
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// an element implementing only "to_dot_string", as before "write_dot_fmt" existed
+    struct Legacy;
+
+    impl DotTranslatable for Legacy {
+        fn to_dot_string(&self) -> String {
+            "legacy;".to_string()
+        }
+    }
+
+    #[test]
+    fn to_dot_string_alone_is_enough() {
+        let mut streamed = String::new();
+        Legacy.write_dot_fmt(&mut streamed).unwrap();
+        assert_eq!(streamed,"legacy;");
+        let mut bytes : Vec<u8> = vec![];
+        let element : &dyn DotTranslatable = &Legacy;
+        element.write_dot(&mut bytes).unwrap();
+        assert_eq!(bytes,b"legacy;");
+    }
+
+    #[test]
+    fn rename_nodes_and_clusters_with_separate_maps() {
+        let graph = crate::digraph!{
+            a;
+            subgraph c1 {
+                b;
+                subgraph c2 {
+                    c;
+                    c -> b [ltail = c2];
+                }
+            }
+            a -> c [lhead = c2];
+            b -> a [ltail = c1];
+        };
+        let renamed = graph.rename_with_maps(&|id| format!("n_{}", id),&|id| format!("{}_k", id));
+        let expected = crate::digraph!{
+            n_a;
+            subgraph c1_k {
+                n_b;
+                subgraph c2_k {
+                    n_c;
+                    n_c -> n_b [ltail = c2_k];
+                }
+            }
+            n_a -> n_c [lhead = c2_k];
+            n_b -> n_a [ltail = c1_k];
+        };
+        assert_eq!(renamed.to_dot_string(),expected.to_dot_string());
+        // ***
+        let renamed = graph.rename_with(&|id| id.to_uppercase());
+        let expected = crate::digraph!{
+            A;
+            subgraph C1 {
+                B;
+                subgraph C2 {
+                    C;
+                    C -> B [ltail = C2];
+                }
+            }
+            A -> C [lhead = C2];
+            B -> A [ltail = C1];
+        };
+        assert_eq!(renamed.to_dot_string(),expected.to_dot_string());
+    }
+
+    #[test]
+    fn rename_edge_with_separate_maps() {
+        let edge = GraphVizEdge::new("x".to_string(),Some("x".to_string()),"y".to_string(),Some("y".to_string()),vec![]);
+        let renamed = edge.rename_with_maps(&|id| format!("node_{}", id),&|id| format!("group_{}", id));
+        assert!(renamed == GraphVizEdge::new("node_x".to_string(),
+                                             Some("group_x".to_string()),
+                                             "node_y".to_string(),
+                                             Some("group_y".to_string()),
+                                             vec![]));
+    }
+
+    /// nodes with the id of their cluster, the parents of clusters and edges between nodes
+    struct Hierarchy {
+        nodes : Vec<(&'static str,Option<&'static str>)>,
+        parents : Vec<(&'static str,&'static str)>,
+        edges : Vec<(&'static str,&'static str)>
+    }
+
+    impl GraphVisualizable for Hierarchy {
+        type Node = (&'static str,Option<&'static str>);
+        type Edge = (&'static str,&'static str);
+        fn nodes(&self) -> impl Iterator<Item = &Self::Node> {
+            self.nodes.iter()
+        }
+        fn edges(&self) -> impl Iterator<Item = &Self::Edge> {
+            self.edges.iter()
+        }
+        fn node_id(&self, node : &Self::Node) -> String {
+            node.0.to_string()
+        }
+        fn edge_endpoints(&self, edge : &Self::Edge) -> (String,String) {
+            (edge.0.to_string(),edge.1.to_string())
+        }
+        fn node_cluster(&self, node : &Self::Node) -> Option<String> {
+            node.1.map(|cluster_id| cluster_id.to_string())
+        }
+        fn cluster_parent(&self, cluster_id : &str) -> Option<String> {
+            self.parents.iter().find(|(child,_)| *child == cluster_id).map(|(_,parent)| parent.to_string())
+        }
+    }
+
+    #[test]
+    fn nodes_are_placed_in_nested_clusters() {
+        let hierarchy = Hierarchy{
+            nodes : vec![("a",Some("inner")),("b",None),("c",Some("outer")),("d",Some("inner"))],
+            parents : vec![("inner","outer")],
+            edges : vec![("a","b"),("c","d")]
+        };
+        let expected = crate::digraph!{
+            subgraph outer {
+                label = "outer";
+                subgraph inner {
+                    label = "inner";
+                    a;
+                    d;
+                }
+                c;
+            }
+            b;
+            a -> b;
+            c -> d;
+        };
+        assert_eq!(hierarchy.to_graphviz().to_dot_string(),expected.to_dot_string());
+    }
+
+    #[test]
+    fn cycles_in_the_cluster_hierarchy_are_broken() {
+        // "x" is nested in "y", which is nested in "x" : the node is drawn in "x" inside "y"
+        let hierarchy = Hierarchy{
+            nodes : vec![("a",Some("x")),("b",Some("y"))],
+            parents : vec![("x","y"),("y","x")],
+            edges : vec![]
+        };
+        let expected = crate::digraph!{
+            subgraph y {
+                label = "y";
+                subgraph x {
+                    label = "x";
+                    a;
+                }
+            }
+            subgraph x {
+                label = "x";
+                subgraph y {
+                    label = "y";
+                    b;
+                }
+            }
+        };
+        assert_eq!(hierarchy.to_graphviz().to_dot_string(),expected.to_dot_string());
+    }
+
+}
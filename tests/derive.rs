@@ -0,0 +1,81 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+#![cfg(feature = "derive")]
+
+
+use graphviz_dot_builder::builder::edge::GvEdgeBuilder;
+use graphviz_dot_builder::builder::node::GvNodeBuilder;
+use graphviz_dot_builder::colors::GraphvizColor;
+use graphviz_dot_builder::edge::style::GvEdgeLineStyle;
+use graphviz_dot_builder::item::node::style::GvNodeShape;
+use graphviz_dot_builder::traits::{DotNode, DotTranslatable};
+
+
+#[derive(DotNode)]
+#[dot(style(shape = Rectangle, fillcolor = lightblue))]
+struct Task {
+    #[dot(id)]
+    name : String,
+    #[dot(label)]
+    title : String,
+    #[dot(edge_to(style = Dashed))]
+    depends_on : Vec<String>,
+    #[dot(edge_to)]
+    parent : Option<String>
+}
+
+#[derive(DotNode)]
+struct Step {
+    #[dot(id)]
+    index : u32,
+    #[dot(edge_to = "color = red")]
+    next : u32
+}
+
+#[test]
+fn derived_node_and_edges() {
+    let task = Task {
+        name : "build".to_string(),
+        title : "Build the crate".to_string(),
+        depends_on : vec!["fetch".to_string(),"configure".to_string()],
+        parent : Some("release".to_string())
+    };
+    assert_eq!(task.dot_node_id(),"build");
+    let expected = GvNodeBuilder::new("build")
+        .shape(GvNodeShape::Rectangle)
+        .fill(GraphvizColor::lightblue)
+        .label("Build the crate")
+        .build();
+    assert_eq!(task.to_dot_node().to_dot_string(),expected.to_dot_string());
+    let edges : Vec<String> = task.to_dot_edges().iter().map(|edge| edge.to_dot_string()).collect();
+    let expected : Vec<String> = [
+        GvEdgeBuilder::new("build","fetch").line_style(GvEdgeLineStyle::Dashed).build(),
+        GvEdgeBuilder::new("build","configure").line_style(GvEdgeLineStyle::Dashed).build(),
+        GvEdgeBuilder::new("build","release").build()
+    ].iter().map(|edge| edge.to_dot_string()).collect();
+    assert_eq!(edges,expected);
+}
+
+#[test]
+fn single_targets_and_string_attributes() {
+    let step = Step { index : 1, next : 2 };
+    assert_eq!(step.to_dot_node().to_dot_string(),GvNodeBuilder::new("1").build().to_dot_string());
+    let edges : Vec<String> = step.to_dot_edges().iter().map(|edge| edge.to_dot_string()).collect();
+    assert_eq!(edges,vec![GvEdgeBuilder::new("1","2").color(GraphvizColor::red).build().to_dot_string()]);
+}
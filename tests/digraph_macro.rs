@@ -0,0 +1,150 @@
+/*
+Copyright 2020 Erwan Mahe (github.com/erwanM974)
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+
+
+
+use graphviz_dot_builder::assert_dot_eq;
+use graphviz_dot_builder::builder::edge::GvEdgeBuilder;
+use graphviz_dot_builder::builder::graph::GvGraphBuilder;
+use graphviz_dot_builder::builder::node::GvNodeBuilder;
+use graphviz_dot_builder::colors::GraphvizColor;
+use graphviz_dot_builder::digraph;
+use graphviz_dot_builder::edge::style::{GvArrowHeadFill, GvArrowHeadSide, GvArrowHeadStyle, GvEdgeLineStyle};
+use graphviz_dot_builder::graph::style::{GraphvizSplines, GvGraphRankDir};
+use graphviz_dot_builder::item::node::style::{GvNodeShape, GvNodeStyleKind};
+
+
+#[test]
+fn graph_attributes() {
+    let built = digraph!{
+        rankdir = LR;
+        nodesep = (1, 5);
+        concentrate = true;
+        splines = Ortho;
+    };
+    let expected = GvGraphBuilder::new()
+        .rankdir(GvGraphRankDir::LR)
+        .node_sep(1,5)
+        .concentrate(true)
+        .splines(GraphvizSplines::Ortho)
+        .build();
+    assert_dot_eq!(built, expected);
+}
+
+#[test]
+fn nodes() {
+    let built = digraph!{
+        a;
+        b [label = "B", shape = Rectangle, style = Dashed, fillcolor = lightblue, color = red];
+        c [fontcolor = blue, fontsize = 12, fontname = "Courier", penwidth = 2, peripheries = 2];
+        d [image = "d.png", height = 1, width = 2];
+        "quoted id";
+        42;
+    };
+    let expected = GvGraphBuilder::new()
+        .node(GvNodeBuilder::new("a"))
+        .node(GvNodeBuilder::new("b").label("B").shape(GvNodeShape::Rectangle).style(GvNodeStyleKind::Dashed)
+            .fill(GraphvizColor::lightblue).color(GraphvizColor::red))
+        .node(GvNodeBuilder::new("c").font_color(GraphvizColor::blue).font_size(12).font_name("Courier")
+            .pen_width(2).peripheries(2))
+        .node(GvNodeBuilder::new("d").image("d.png").height(1).width(2))
+        .node(GvNodeBuilder::new("quoted id"))
+        .node(GvNodeBuilder::new("42"))
+        .build();
+    assert_dot_eq!(built, expected);
+}
+
+#[test]
+fn chains_of_edges() {
+    let built = digraph!{
+        a -> b;
+        a -> b -> c [label = "l", style = Dotted, arrowhead = NoArrow, color = green, constraint = false];
+        c -> a [arrowtail = (GvArrowHeadStyle::Normal(GvArrowHeadFill::Open,GvArrowHeadSide::Left)), arrowsize = 2, penwidth = 3];
+    };
+    let styled = |origin : &str, target : &str| {
+        GvEdgeBuilder::new(origin,target).label("l").line_style(GvEdgeLineStyle::Dotted)
+            .head(GvArrowHeadStyle::NoArrow).color(GraphvizColor::green).constraint(false)
+    };
+    let expected = GvGraphBuilder::new()
+        .edge(GvEdgeBuilder::new("a","b"))
+        .edge(styled("a","b"))
+        .edge(styled("b","c"))
+        .edge(GvEdgeBuilder::new("c","a").tail(GvArrowHeadStyle::Normal(GvArrowHeadFill::Open,GvArrowHeadSide::Left))
+            .arrow_size(2).pen_width(3))
+        .build();
+    assert_dot_eq!(built, expected);
+}
+
+#[test]
+fn subgraphs() {
+    let built = digraph!{
+        a;
+        subgraph c1 {
+            label = "C1";
+            style = Rounded;
+            fillcolor = yellow;
+            b;
+            subgraph c2 {
+                c [shape = Circle];
+                c -> c;
+            }
+            b -> c [lhead = c2];
+        }
+        a -> b [ltail = c1];
+    };
+    let expected = GvGraphBuilder::new()
+        .node(GvNodeBuilder::new("a"))
+        .cluster("c1", |c1| {
+            c1.label("C1").style(GvNodeStyleKind::Rounded).fill(GraphvizColor::yellow)
+                .node(GvNodeBuilder::new("b"))
+                .cluster("c2", |c2| {
+                    c2.node(GvNodeBuilder::new("c").shape(GvNodeShape::Circle))
+                        .edge(GvEdgeBuilder::new("c","c"))
+                })
+                .edge(GvEdgeBuilder::new("b","c").lhead("c2"))
+        })
+        .edge(GvEdgeBuilder::new("a","b").ltail("c1"))
+        .build();
+    assert_dot_eq!(built, expected);
+}
+
+#[test]
+fn expressions() {
+    let id = "n1".to_string();
+    let count = 3;
+    let cluster = format!("c{}", count);
+    let built = digraph!{
+        rankdir = (GvGraphRankDir::BT);
+        (id) [label = (format!("{} items", count)), fillcolor = (GraphvizColor::red), fontsize = (count as u32)];
+        subgraph (cluster) {
+            (format!("n{}", count + 1));
+        }
+        (id) -> (format!("n{}", count + 1)) [lhead = (cluster), label = (id.clone())];
+    };
+    let expected = GvGraphBuilder::new()
+        .rankdir(GvGraphRankDir::BT)
+        .node(GvNodeBuilder::new("n1").label("3 items").fill(GraphvizColor::red).font_size(3))
+        .cluster("c3", |c3| c3.node(GvNodeBuilder::new("n4")))
+        .edge(GvEdgeBuilder::new("n1","n4").lhead("c3").label("n1"))
+        .build();
+    assert_dot_eq!(built, expected);
+}
+
+#[test]
+fn compile_failures() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,7 @@
+fn main() {
+    let _ = graphviz_dot_builder::digraph!{
+        subgraph c1 {
+            shape = Rectangle;
+        }
+    };
+}
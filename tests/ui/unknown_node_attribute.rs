@@ -0,0 +1,5 @@
+fn main() {
+    let _ = graphviz_dot_builder::digraph!{
+        a [arrowhead = NoArrow];
+    };
+}